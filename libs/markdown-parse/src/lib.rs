@@ -1,11 +1,20 @@
 mod component_parse;
 mod value_objects;
 mod parse;
+mod rename_image;
 
-pub use parse::{parse, parse_preview, Error, ImageUrlInjector, BlogParse, PreviewParse};
+pub use parse::{
+    parse, parse_preview, BlogParse, ContentStats, Error, ImageMetadata, ImageSources,
+    ImageUrlInjector, LinkUrlInjector, PreviewParse, TocEntry,
+};
+pub use rename_image::rename_image;
 pub use value_objects::{content, preview};
 pub use pulldown_cmark::CowStr;
 
+/// Version of the html emitted by [`parse`]. Bump it whenever the generated output changes, so
+/// stored blogs compiled by an older version can be detected and recompiled.
+pub const COMPILER_VERSION: i32 = 6;
+
 mod vec_set {
     #[derive(Debug, Default)]
     pub struct VecSet<T: Eq>(Vec<T>);
@@ -1,6 +1,10 @@
 mod lines_indices;
 
-use pulldown_cmark::{html::push_html, CowStr, Event, HeadingLevel, LinkType, Parser, Tag};
+use pulldown_cmark::{
+    escape::{escape_href, escape_html},
+    html::push_html,
+    CowStr, Event, HeadingLevel, LinkType, Parser, Tag,
+};
 
 use crate::{component_parse::MarkdownParser, vec_set::VecSet};
 
@@ -9,12 +13,153 @@ pub struct BlogParse {
     pub title: String,
     pub content: String,
     pub images: VecSet<String>,
+    /// Headings after the title, in order
+    pub toc: Vec<TocEntry>,
+    pub stats: ContentStats,
+}
+
+/// What the content after the title is made of
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ContentStats {
+    /// Words of the text, inline code included
+    pub words: u32,
+    /// Words inside code blocks
+    pub code_words: u32,
+    pub images: u32,
+    pub code_blocks: u32,
+    pub links: u32,
+}
+
+impl ContentStats {
+    fn count(events: &[Event<'_>]) -> Self {
+        let mut stats = Self::default();
+        let mut in_code_block = false;
+        // Alt texts are not read
+        let mut in_image = false;
+        // Text may be split in many events, even in the middle of a word
+        let mut previous_text: Option<&str> = None;
+
+        for event in events {
+            let text = match event {
+                Event::Start(Tag::CodeBlock(_)) => {
+                    in_code_block = true;
+                    stats.code_blocks += 1;
+                    None
+                }
+                Event::End(Tag::CodeBlock(_)) => {
+                    in_code_block = false;
+                    None
+                }
+                Event::Start(Tag::Image(..)) => {
+                    in_image = true;
+                    stats.images += 1;
+                    None
+                }
+                Event::End(Tag::Image(..)) => {
+                    in_image = false;
+                    None
+                }
+                Event::Start(Tag::Link(..)) => {
+                    stats.links += 1;
+                    None
+                }
+                Event::Text(text) if !in_image => Some(text.as_ref()),
+                _ => None,
+            };
+
+            let Some(text) = text else {
+                previous_text = None;
+                continue;
+            };
+
+            let mut words = text.split_whitespace().count() as u32;
+            if previous_text.is_some_and(|previous| ends_in_word(previous, text)) {
+                words -= 1;
+            }
+
+            if in_code_block {
+                stats.code_words += words;
+            } else {
+                stats.words += words;
+            }
+
+            previous_text = Some(text);
+        }
+
+        // Inline code reads as text, it is counted apart as it is never split
+        stats.words += events
+            .iter()
+            .map(|event| match event {
+                Event::Code(code) => code.split_whitespace().count() as u32,
+                _ => 0,
+            })
+            .sum::<u32>();
+
+        stats
+    }
+}
+
+/// Whether `next` continues the last word of `previous`
+fn ends_in_word(previous: &str, next: &str) -> bool {
+    let ends = previous.chars().last().is_some_and(|c| !c.is_whitespace());
+    let starts = next.chars().next().is_some_and(|c| !c.is_whitespace());
+
+    ends && starts
+}
+
+/// Heading of the content, linkable by its `id`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TocEntry {
+    pub level: u8,
+    pub id: String,
+    pub text: String,
 }
 
 /// Modifies the url of an image
 pub trait ImageUrlInjector {
     fn inject(&self, url: &mut CowStr<'_>);
     fn is_valid(&self, url: &str) -> bool;
+
+    /// Responsive sources of an injected `url`, the image is rendered in a `<picture>` when given
+    fn sources(&self, _url: &str) -> Option<ImageSources> {
+        None
+    }
+
+    /// Known metadata of a valid `url`, before being injected
+    fn metadata(&self, _url: &str) -> Option<ImageMetadata> {
+        None
+    }
+}
+
+/// Modifies the url of a link, like those naming files attached to the content
+pub trait LinkUrlInjector {
+    /// Links are left as written unless valid
+    fn is_valid_link(&self, _url: &str) -> bool {
+        false
+    }
+
+    fn inject_link(&self, _url: &mut CowStr<'_>) {}
+}
+
+/// Intrinsic size of an image, so the page does not shift as it loads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    /// Url of a tiny version shown blurred until the image loads, usually a data url
+    pub placeholder: Option<String>,
+}
+
+/// Candidates a browser picks from by the width it shows the image at and the formats it
+/// supports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSources {
+    /// Width the image is shown at, as the `sizes` attribute
+    pub sizes: String,
+    /// Candidates in the format of the image itself
+    pub srcset: String,
+    /// Candidates by mime type of other formats, preferred when supported
+    pub alternates: Vec<(String, String)>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -32,22 +177,148 @@ impl std::fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
-fn mutate_item(
-    item: &mut Event<'_>,
-    images: &mut VecSet<String>,
-    injector: &impl ImageUrlInjector,
-) {
+fn collect_image(item: &Event<'_>, images: &mut VecSet<String>, injector: &impl ImageUrlInjector) {
     if let Event::Start(Tag::Image(LinkType::Inline, url, _)) = item {
-        if !injector.is_valid(url) {
-            return;
+        if injector.is_valid(url) {
+            images.insert(url.to_string());
+        }
+    }
+}
+
+/// Points valid images to where they are hosted, rendering those with sources as a `<picture>`
+/// and those with metadata lazily, sized and with their placeholder
+fn inject_images<'a>(events: Vec<Event<'a>>, injector: &impl ImageUrlInjector) -> Vec<Event<'a>> {
+    let mut injected = Vec::with_capacity(events.len());
+    let mut events = events.into_iter();
+
+    while let Some(event) = events.next() {
+        let Event::Start(Tag::Image(LinkType::Inline, mut url, title)) = event else {
+            injected.push(event);
+            continue;
+        };
+
+        if !injector.is_valid(&url) {
+            injected.push(Event::Start(Tag::Image(LinkType::Inline, url, title)));
+            continue;
+        }
+
+        let metadata = injector.metadata(&url);
+        injector.inject(&mut url);
+        let sources = injector.sources(&url);
+
+        if sources.is_none() && metadata.is_none() {
+            injected.push(Event::Start(Tag::Image(LinkType::Inline, url, title)));
+            continue;
         }
 
-        images.insert(url.to_string());
-        injector.inject(url);
+        // Alt texts are plain text, as when rendered by pulldown
+        let mut alt = String::new();
+        for event in events.by_ref() {
+            match event {
+                Event::End(Tag::Image(..)) => break,
+                Event::Text(text) | Event::Code(text) => alt.push_str(&text),
+                Event::SoftBreak | Event::HardBreak => alt.push(' '),
+                _ => {}
+            }
+        }
+
+        let html = image(&url, &title, &alt, sources.as_ref(), metadata.as_ref());
+        injected.push(Event::Html(html.into()));
     }
+
+    injected
 }
 
-pub fn parse(markdown: &str, injector: &impl ImageUrlInjector) -> Result<BlogParse, Error> {
+/// Points valid links to where they are hosted. Both ends of a link carry its url, and the end
+/// is what gets rendered
+fn inject_links<'a>(events: Vec<Event<'a>>, injector: &impl LinkUrlInjector) -> Vec<Event<'a>> {
+    // Links do not nest
+    let mut injected_url = None;
+
+    events
+        .into_iter()
+        .map(|event| match event {
+            Event::Start(Tag::Link(LinkType::Inline, mut url, title))
+                if injector.is_valid_link(&url) =>
+            {
+                injector.inject_link(&mut url);
+                injected_url = Some(url.clone());
+
+                Event::Start(Tag::Link(LinkType::Inline, url, title))
+            }
+            Event::End(Tag::Link(LinkType::Inline, url, title)) => {
+                let url = injected_url.take().unwrap_or(url);
+                Event::End(Tag::Link(LinkType::Inline, url, title))
+            }
+            event => event,
+        })
+        .collect()
+}
+
+fn image(
+    url: &str,
+    title: &str,
+    alt: &str,
+    sources: Option<&ImageSources>,
+    metadata: Option<&ImageMetadata>,
+) -> String {
+    // Writing to a string can not fail
+    let attribute = |html: &mut String, name: &str, value: &str| {
+        html.push_str(&format!(" {}=\"", name));
+        escape_html(&mut *html, value).unwrap();
+        html.push('"');
+    };
+
+    let mut html = String::new();
+
+    if let Some(sources) = sources {
+        html.push_str("<picture>");
+
+        for (mime, srcset) in &sources.alternates {
+            html.push_str("<source");
+            attribute(&mut html, "type", mime);
+            attribute(&mut html, "srcset", srcset);
+            attribute(&mut html, "sizes", &sources.sizes);
+            html.push('>');
+        }
+    }
+
+    html.push_str("<img src=\"");
+    escape_href(&mut html, url).unwrap();
+    html.push('"');
+    if let Some(sources) = sources {
+        attribute(&mut html, "srcset", &sources.srcset);
+        attribute(&mut html, "sizes", &sources.sizes);
+    }
+    attribute(&mut html, "alt", alt);
+    if !title.is_empty() {
+        attribute(&mut html, "title", title);
+    }
+    if let Some(metadata) = metadata {
+        attribute(&mut html, "width", &metadata.width.to_string());
+        attribute(&mut html, "height", &metadata.height.to_string());
+        attribute(&mut html, "loading", "lazy");
+
+        if let Some(placeholder) = &metadata.placeholder {
+            let mut style = String::from("background-size:cover;background-image:url(\"");
+            escape_href(&mut style, placeholder).unwrap();
+            style.push_str("\")");
+            attribute(&mut html, "style", &style);
+        }
+    }
+    html.push_str(" />");
+
+    if sources.is_some() {
+        html.push_str("</picture>");
+    }
+
+    html
+}
+
+pub fn parse(
+    markdown: &str,
+    injector: &(impl ImageUrlInjector + LinkUrlInjector),
+) -> Result<BlogParse, Error> {
     let mut parser = Parser::new(markdown);
     let mut title_elements = vec![];
 
@@ -99,20 +370,108 @@ pub fn parse(markdown: &str, injector: &impl ImageUrlInjector) -> Result<BlogPar
 
     md_parser.push_parse(&mut content, title_elements.into_iter());
 
-    let parser = parser.map(|mut item| {
-        mutate_item(&mut item, &mut images, injector);
-        item
-    });
+    let parser = parser.inspect(|item| collect_image(item, &mut images, injector));
+
+    let (events, toc) = anchor_headings(parser);
+    let stats = ContentStats::count(&events);
+    let events = inject_images(events, injector);
+    let events = inject_links(events, injector);
 
-    md_parser.push_parse(&mut content, parser);
+    md_parser.push_parse(&mut content, events.into_iter());
 
     Ok(BlogParse {
         title,
         content,
         images,
+        toc,
+        stats,
     })
 }
 
+/// Gives every heading an id made from its text, unique within the content
+fn anchor_headings<'a>(events: impl Iterator<Item = Event<'a>>) -> (Vec<Event<'a>>, Vec<TocEntry>) {
+    let mut anchored = vec![];
+    let mut toc = vec![];
+    let mut ids = heading_ids::HeadingIds::default();
+
+    // Index of the start of the heading being read, with its level and text
+    let mut heading: Option<(usize, HeadingLevel, String)> = None;
+
+    for event in events {
+        match &event {
+            Event::Start(Tag::Heading(level, ..)) => {
+                heading = Some((anchored.len(), *level, String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, heading_text)) = heading.as_mut() {
+                    heading_text.push_str(text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some((start, level, text)) = heading.take() {
+                    let id = ids.unique(&text);
+                    anchored[start] = Event::Html(format!("<{} id=\"{}\">", level, id).into());
+
+                    toc.push(TocEntry {
+                        level: level as u8,
+                        id,
+                        text,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        anchored.push(event);
+    }
+
+    (anchored, toc)
+}
+
+mod heading_ids {
+    use std::collections::HashSet;
+
+    #[derive(Default)]
+    pub struct HeadingIds(HashSet<String>);
+
+    impl HeadingIds {
+        /// Slug of `text`, suffixed by `-2`, `-3` and so on when already taken
+        pub fn unique(&mut self, text: &str) -> String {
+            let slug = slugify(text);
+
+            let mut id = slug.clone();
+            let mut n = 1;
+            while self.0.contains(&id) {
+                n += 1;
+                id = format!("{}-{}", slug, n);
+            }
+
+            self.0.insert(id.clone());
+            id
+        }
+    }
+
+    /// Lowercase alphanumerics joined by `-`, punctuation is dropped
+    fn slugify(text: &str) -> String {
+        let mut slug = String::with_capacity(text.len());
+
+        for c in text.chars() {
+            if c.is_alphanumeric() {
+                slug.extend(c.to_lowercase());
+            } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+
+        let slug = slug.trim_matches('-');
+        if slug.is_empty() {
+            return "section".to_owned();
+        }
+
+        slug.to_owned()
+    }
+}
+
 pub struct PreviewParse {
     pub preview: String,
     pub description: String,
@@ -262,6 +621,7 @@ First of all, if you want to just copy and paste, go [here](#copy-and-paste)
         }
         fn inject(&self, _url: &mut CowStr<'_>) {}
     }
+    impl LinkUrlInjector for NoopInjector {}
 
     #[test]
     fn validates_title_is_first() {
@@ -297,6 +657,175 @@ Hello
         );
     }
 
+    #[test]
+    fn anchors_headings() {
+        let markdown = r#"# Title
+
+## Getting `started`
+
+### Setup, again!
+
+## Getting started
+"#;
+
+        let BlogParse { content, toc, .. } = parse(markdown, &NoopInjector {}).unwrap();
+
+        assert_eq!(
+            toc,
+            vec![
+                TocEntry {
+                    level: 2,
+                    id: "getting-started".to_string(),
+                    text: "Getting started".to_string(),
+                },
+                TocEntry {
+                    level: 3,
+                    id: "setup-again".to_string(),
+                    text: "Setup, again!".to_string(),
+                },
+                TocEntry {
+                    level: 2,
+                    id: "getting-started-2".to_string(),
+                    text: "Getting started".to_string(),
+                },
+            ]
+        );
+        assert!(content.contains("<h2 id=\"getting-started\">Getting <code>started</code></h2>"));
+        assert!(content.contains("<h2 id=\"getting-started-2\">Getting started</h2>"));
+    }
+
+    struct ResponsiveInjector;
+    impl ImageUrlInjector for ResponsiveInjector {
+        fn is_valid(&self, url: &str) -> bool {
+            !url.starts_with("http")
+        }
+        fn inject(&self, url: &mut CowStr<'_>) {
+            *url = format!("/public/{}", url).into();
+        }
+        fn sources(&self, url: &str) -> Option<ImageSources> {
+            Some(ImageSources {
+                sizes: "100vw".to_owned(),
+                srcset: format!("{}?w=480 480w", url),
+                alternates: vec![(
+                    "image/webp".to_owned(),
+                    format!("{}?w=480&format=webp 480w", url),
+                )],
+            })
+        }
+    }
+    impl LinkUrlInjector for ResponsiveInjector {}
+
+    #[test]
+    fn renders_pictures_of_injected_images() {
+        let markdown = r#"# Title
+
+![a *cat*](cat.png "Cat") ![remote](https://example.com/dog.png)
+"#;
+
+        let BlogParse {
+            content, images, ..
+        } = parse(markdown, &ResponsiveInjector).unwrap();
+
+        assert!(content.contains(concat!(
+            r#"<picture><source type="image/webp" srcset="/public/cat.png?w=480&amp;format=webp 480w" sizes="100vw">"#,
+            r#"<img src="/public/cat.png" srcset="/public/cat.png?w=480 480w" sizes="100vw" alt="a cat" title="Cat" /></picture>"#
+        )));
+        assert!(content.contains(r#"<img src="https://example.com/dog.png" alt="remote" />"#));
+        assert_eq!(images.into_inner(), vec!["cat.png".to_string()]);
+    }
+
+    struct SizedInjector;
+    impl ImageUrlInjector for SizedInjector {
+        fn is_valid(&self, url: &str) -> bool {
+            !url.starts_with("http")
+        }
+        fn inject(&self, url: &mut CowStr<'_>) {
+            *url = format!("/public/{}", url).into();
+        }
+        fn metadata(&self, url: &str) -> Option<ImageMetadata> {
+            (url == "cat.png").then(|| ImageMetadata {
+                width: 640,
+                height: 480,
+                placeholder: Some("data:image/png;base64,iVBO".to_owned()),
+            })
+        }
+    }
+    impl LinkUrlInjector for SizedInjector {}
+
+    #[test]
+    fn sizes_images_with_metadata() {
+        let markdown = r#"# Title
+
+![cat](cat.png) ![dog](dog.png)
+"#;
+
+        let BlogParse { content, .. } = parse(markdown, &SizedInjector).unwrap();
+
+        assert!(content.contains(concat!(
+            r#"<img src="/public/cat.png" alt="cat" width="640" height="480" loading="lazy" "#,
+            r#"style="background-size:cover;background-image:url(&quot;data:image/png;base64,iVBO&quot;)" />"#
+        )));
+        assert!(content.contains(r#"<img src="/public/dog.png" alt="dog" />"#));
+    }
+
+    struct AttachmentInjector;
+    impl ImageUrlInjector for AttachmentInjector {
+        fn is_valid(&self, _: &str) -> bool {
+            false
+        }
+        fn inject(&self, _url: &mut CowStr<'_>) {}
+    }
+    impl LinkUrlInjector for AttachmentInjector {
+        fn is_valid_link(&self, url: &str) -> bool {
+            url.ends_with(".pdf") && !url.contains('/')
+        }
+        fn inject_link(&self, url: &mut CowStr<'_>) {
+            *url = format!("/attachments/{}/", url).into();
+        }
+    }
+
+    #[test]
+    fn injects_valid_links() {
+        let markdown = r#"# Title
+
+Read [the paper](paper.pdf "Paper"), or [the docs](https://docs.rs/paper.pdf).
+"#;
+
+        let BlogParse { content, stats, .. } = parse(markdown, &AttachmentInjector).unwrap();
+
+        assert!(content.contains(r#"href="/attachments/paper.pdf/""#));
+        assert!(content.contains(r#"href="https://docs.rs/paper.pdf""#));
+        assert!(!content.contains(r#"href="paper.pdf""#));
+        assert_eq!(stats.links, 2);
+    }
+
+    #[test]
+    fn counts_content() {
+        let markdown = r#"# Some long title
+
+Read [the docs](https://docs.rs) with `cargo doc` first, it's worth [it].
+
+![an image with alt](image.png)
+
+```rust
+fn main() {}
+```
+"#;
+
+        let BlogParse { stats, .. } = parse(markdown, &NoopInjector {}).unwrap();
+
+        assert_eq!(
+            stats,
+            ContentStats {
+                words: 10,
+                code_words: 3,
+                images: 1,
+                code_blocks: 1,
+                links: 1,
+            }
+        );
+    }
+
     #[test]
     fn collects_preview() {
         let markdown = r#"# hello world
@@ -0,0 +1,86 @@
+use pulldown_cmark::{Event, LinkType, Parser, Tag};
+
+/// Points the inline images of `markdown` linking to `from` to `to`, leaving the rest of the
+/// markdown untouched. `None` when no image links to `from`
+pub fn rename_image(markdown: &str, from: &str, to: &str) -> Option<String> {
+    let mut starts = vec![];
+
+    for (event, range) in Parser::new(markdown).into_offset_iter() {
+        let Event::Start(Tag::Image(LinkType::Inline, url, _)) = event else {
+            continue;
+        };
+
+        if url.as_ref() != from {
+            continue;
+        }
+
+        if let Some(start) = destination_start(&markdown[range.clone()], from) {
+            starts.push(range.start + start);
+        }
+    }
+
+    if starts.is_empty() {
+        return None;
+    }
+
+    let mut renamed = String::with_capacity(markdown.len());
+    let mut copied = 0;
+    for start in starts {
+        renamed.push_str(&markdown[copied..start]);
+        renamed.push_str(to);
+        copied = start + from.len();
+    }
+    renamed.push_str(&markdown[copied..]);
+
+    Some(renamed)
+}
+
+/// Where `url` starts in the source of an inline image, `![alt](url "title")`, the alt text
+/// and title may contain it too
+fn destination_start(image: &str, url: &str) -> Option<usize> {
+    image.match_indices("](").find_map(|(at, opening)| {
+        let after = &image[at + opening.len()..];
+        let trimmed = after.trim_start();
+        let destination = trimmed.strip_prefix('<').unwrap_or(trimmed);
+
+        let rest = destination.strip_prefix(url)?;
+        if !rest.starts_with(|c: char| c == ')' || c == '>' || c.is_whitespace()) {
+            return None;
+        }
+
+        Some(image.len() - destination.len())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renames_inline_images() {
+        let markdown = r#"# Cats
+
+![cat.png](cat.png "cat.png") and ![a cat]( <cat.png> )
+
+[cat.png](cat.png) ![dog](dog.png) ![cats](cat.pngs)
+"#;
+
+        assert_eq!(
+            rename_image(markdown, "cat.png", "kitten.png").unwrap(),
+            r#"# Cats
+
+![cat.png](kitten.png "cat.png") and ![a cat]( <kitten.png> )
+
+[cat.png](cat.png) ![dog](dog.png) ![cats](cat.pngs)
+"#
+        );
+    }
+
+    #[test]
+    fn skips_markdown_without_the_image() {
+        assert_eq!(
+            rename_image("# Dogs\n\n![dog](dog.png)", "cat.png", "kitten.png"),
+            None
+        );
+    }
+}
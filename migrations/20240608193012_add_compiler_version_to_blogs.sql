@@ -0,0 +1,3 @@
+-- Blogs compiled before the stamp existed are considered stale
+ALTER TABLE blogs
+	ADD COLUMN compiler_version INT NOT NULL DEFAULT 0;
@@ -0,0 +1,35 @@
+CREATE TYPE job_kind AS ENUM ('recompile_markdowns');
+CREATE TYPE job_status AS ENUM ('pending', 'running', 'succeeded', 'failed');
+
+CREATE TABLE jobs (
+	id              UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+	kind            job_kind NOT NULL,
+	payload         JSONB NOT NULL DEFAULT '{}',
+	status          job_status NOT NULL DEFAULT 'pending',
+
+	attempts        INT NOT NULL DEFAULT 0,
+	max_attempts    INT NOT NULL DEFAULT 3,
+	-- A pending job is not picked before this, used to back off retries
+	run_at          timestamp NOT NULL DEFAULT now(),
+	-- A running job whose lock expired is considered abandoned and picked again
+	locked_until    timestamp,
+
+	progress        INT NOT NULL DEFAULT 0,
+	total           INT,
+	result          JSONB,
+	last_error      TEXT,
+
+	created_at      timestamp NOT NULL DEFAULT now(),
+	started_at      timestamp,
+	finished_at     timestamp
+);
+
+CREATE INDEX jobs_pending_idx ON jobs (run_at) WHERE status IN ('pending', 'running');
+
+CREATE TABLE job_item_errors (
+	id          UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+	job_id      UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+	item        TEXT NOT NULL,
+	message     TEXT NOT NULL,
+	created_at  timestamp NOT NULL DEFAULT now()
+);
@@ -0,0 +1,23 @@
+CREATE TYPE event_kind AS ENUM (
+	'blog_created',
+	'blog_updated',
+	'blog_content_set',
+	'comment_created',
+	'reply_created',
+	'account_registered'
+);
+
+-- Outbox of domain events, written in the same transaction as the change they describe
+CREATE TABLE events (
+	seq             BIGSERIAL PRIMARY KEY,
+	id              UUID NOT NULL UNIQUE DEFAULT gen_random_uuid(),
+	kind            event_kind NOT NULL,
+	payload         JSONB NOT NULL,
+	created_at      timestamp NOT NULL DEFAULT now(),
+
+	dispatched_at   timestamp,
+	attempts        INT NOT NULL DEFAULT 0,
+	last_error      TEXT
+);
+
+CREATE INDEX events_undispatched_idx ON events (seq) WHERE dispatched_at IS NULL;
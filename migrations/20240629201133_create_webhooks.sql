@@ -0,0 +1,27 @@
+ALTER TYPE event_kind ADD VALUE 'blog_deleted';
+ALTER TYPE job_kind ADD VALUE 'deliver_webhook';
+
+CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'delivered', 'failed');
+
+CREATE TABLE webhooks (
+	id          UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+	url         TEXT NOT NULL,
+	secret      TEXT NOT NULL,
+	events      event_kind[] NOT NULL,
+	active      BOOLEAN NOT NULL DEFAULT true,
+	created_at  timestamp NOT NULL DEFAULT now()
+);
+
+CREATE TABLE webhook_deliveries (
+	id              UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+	webhook_id      UUID NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+	event_id        UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+	status          webhook_delivery_status NOT NULL DEFAULT 'pending',
+	attempts        INT NOT NULL DEFAULT 0,
+	response_status INT,
+	last_error      TEXT,
+	created_at      timestamp NOT NULL DEFAULT now(),
+	delivered_at    timestamp,
+
+	UNIQUE (webhook_id, event_id)
+);
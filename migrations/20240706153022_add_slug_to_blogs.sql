@@ -0,0 +1,14 @@
+ALTER TABLE blogs ADD COLUMN slug TEXT;
+
+UPDATE blogs SET slug = trim(BOTH '-' FROM regexp_replace(
+	translate(lower(title), 'áàäâéèëêíìïîóòöôúùüûñç', 'aaaaeeeeiiiioooouuuunc'),
+	'[^a-z0-9]+', '-', 'g'
+));
+
+UPDATE blogs SET slug = left(id::text, 8) WHERE slug = '';
+
+UPDATE blogs b SET slug = b.slug || '-' || left(b.id::text, 8)
+WHERE EXISTS (SELECT 1 FROM blogs o WHERE o.slug = b.slug AND o.id < b.id);
+
+ALTER TABLE blogs ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE blogs ADD CONSTRAINT blogs_slug_key UNIQUE (slug);
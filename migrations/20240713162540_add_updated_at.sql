@@ -0,0 +1,63 @@
+ALTER TABLE blogs ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT now();
+UPDATE blogs SET updated_at = created_at;
+
+ALTER TABLE categories ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT now();
+ALTER TABLE sub_categories ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT now();
+ALTER TABLE tags ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT now();
+
+CREATE FUNCTION set_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+	NEW.updated_at = now();
+	RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER blogs_updated_at BEFORE UPDATE ON blogs
+	FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+CREATE TRIGGER categories_updated_at BEFORE UPDATE ON categories
+	FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+CREATE TRIGGER sub_categories_updated_at BEFORE UPDATE ON sub_categories
+	FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+CREATE TRIGGER tags_updated_at BEFORE UPDATE ON tags
+	FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- Rows shown along with a blog change the blog too
+CREATE FUNCTION touch_blog() RETURNS TRIGGER AS $$
+DECLARE
+	changed RECORD;
+BEGIN
+	IF TG_OP = 'DELETE' THEN
+		changed = OLD;
+	ELSE
+		changed = NEW;
+	END IF;
+
+	UPDATE blogs SET updated_at = now() WHERE id = changed.blog_id;
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION touch_blog_of_comment() RETURNS TRIGGER AS $$
+DECLARE
+	changed RECORD;
+BEGIN
+	IF TG_OP = 'DELETE' THEN
+		changed = OLD;
+	ELSE
+		changed = NEW;
+	END IF;
+
+	UPDATE blogs SET updated_at = now()
+	WHERE id = (SELECT blog_id FROM comments WHERE id = changed.comment_id);
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER tags_blogs_touch_blog AFTER INSERT OR UPDATE OR DELETE ON tags_blogs
+	FOR EACH ROW EXECUTE FUNCTION touch_blog();
+CREATE TRIGGER sub_categories_blogs_touch_blog AFTER INSERT OR UPDATE OR DELETE ON sub_categories_blogs
+	FOR EACH ROW EXECUTE FUNCTION touch_blog();
+CREATE TRIGGER comments_touch_blog AFTER INSERT OR UPDATE OR DELETE ON comments
+	FOR EACH ROW EXECUTE FUNCTION touch_blog();
+CREATE TRIGGER replies_touch_blog AFTER INSERT OR UPDATE OR DELETE ON replies
+	FOR EACH ROW EXECUTE FUNCTION touch_blog_of_comment();
@@ -0,0 +1,2 @@
+-- Headings of the compiled html, filled as blogs are recompiled
+ALTER TABLE blogs ADD COLUMN toc jsonb NOT NULL DEFAULT '[]';
@@ -0,0 +1,7 @@
+-- Counted while compiling, filled as blogs are recompiled
+ALTER TABLE blogs
+	ADD COLUMN word_count INT NOT NULL DEFAULT 0,
+	ADD COLUMN code_word_count INT NOT NULL DEFAULT 0,
+	ADD COLUMN image_count INT NOT NULL DEFAULT 0,
+	ADD COLUMN code_block_count INT NOT NULL DEFAULT 0,
+	ADD COLUMN link_count INT NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- Filled on upload, images uploaded before are not sized
+CREATE TABLE blog_images (
+	blog_id      UUID NOT NULL REFERENCES blogs(id) ON DELETE CASCADE,
+	filename     TEXT NOT NULL,
+	width        INT NOT NULL,
+	height       INT NOT NULL,
+	placeholder  TEXT NOT NULL,
+	PRIMARY KEY (blog_id, filename)
+);
@@ -0,0 +1 @@
+ALTER TYPE job_kind ADD VALUE 'collect_orphaned_images';
@@ -0,0 +1,18 @@
+-- Uploads are stored once by the hash of their content, shared by every blog uploading it
+CREATE TABLE image_objects (
+	hash         TEXT NOT NULL PRIMARY KEY,
+	extension    TEXT NOT NULL,
+	size         BIGINT NOT NULL,
+	width        INT NOT NULL,
+	height       INT NOT NULL,
+	placeholder  TEXT NOT NULL,
+	created_at   timestamp NOT NULL DEFAULT now()
+);
+
+-- Images are named by an alias of the object holding them, those uploaded before are still
+-- stored by their filename and have none
+ALTER TABLE blog_images
+	ADD COLUMN hash TEXT REFERENCES image_objects(hash),
+	ADD COLUMN uploaded_at timestamp NOT NULL DEFAULT now();
+
+CREATE INDEX blog_images_hash_idx ON blog_images (hash);
@@ -0,0 +1,8 @@
+-- Files attached to a blog, stored by filename under `attachments/{blog_id}/`
+CREATE TABLE blog_attachments (
+	blog_id      UUID NOT NULL REFERENCES blogs(id) ON DELETE CASCADE,
+	filename     TEXT NOT NULL,
+	size         BIGINT NOT NULL,
+	uploaded_at  timestamp NOT NULL DEFAULT now(),
+	PRIMARY KEY (blog_id, filename)
+);
@@ -0,0 +1,9 @@
+-- Events are claimed for a while instead of staying locked while subscribers run
+ALTER TABLE events ADD COLUMN locked_until timestamp;
+-- Set once an event failed too many times, it is no longer retried
+ALTER TABLE events ADD COLUMN dead_at timestamp;
+
+UPDATE events SET dead_at = now() WHERE dispatched_at IS NULL AND attempts >= 10;
+
+DROP INDEX events_undispatched_idx;
+CREATE INDEX events_undispatched_idx ON events (seq) WHERE dispatched_at IS NULL AND dead_at IS NULL;
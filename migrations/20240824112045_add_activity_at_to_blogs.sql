@@ -0,0 +1,60 @@
+-- Comments and replies change what is sent along a blog, but not the blog itself, so they
+-- touch its activity while `updated_at` is left to feeds and sitemaps
+ALTER TABLE blogs ADD COLUMN activity_at TIMESTAMP NOT NULL DEFAULT now();
+
+-- The backfill is no change of the blogs
+ALTER TABLE blogs DISABLE TRIGGER blogs_updated_at;
+UPDATE blogs SET activity_at = updated_at;
+ALTER TABLE blogs ENABLE TRIGGER blogs_updated_at;
+
+-- Changes of the blog are activity too, touching activity alone is not a change
+CREATE FUNCTION set_blog_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+	IF NEW.activity_at IS DISTINCT FROM OLD.activity_at THEN
+		RETURN NEW;
+	END IF;
+
+	NEW.updated_at = now();
+	NEW.activity_at = now();
+	RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER blogs_updated_at ON blogs;
+CREATE TRIGGER blogs_updated_at BEFORE UPDATE ON blogs
+	FOR EACH ROW EXECUTE FUNCTION set_blog_updated_at();
+
+CREATE FUNCTION touch_blog_activity() RETURNS TRIGGER AS $$
+DECLARE
+	changed RECORD;
+BEGIN
+	IF TG_OP = 'DELETE' THEN
+		changed = OLD;
+	ELSE
+		changed = NEW;
+	END IF;
+
+	UPDATE blogs SET activity_at = now() WHERE id = changed.blog_id;
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION touch_blog_of_comment() RETURNS TRIGGER AS $$
+DECLARE
+	changed RECORD;
+BEGIN
+	IF TG_OP = 'DELETE' THEN
+		changed = OLD;
+	ELSE
+		changed = NEW;
+	END IF;
+
+	UPDATE blogs SET activity_at = now()
+	WHERE id = (SELECT blog_id FROM comments WHERE id = changed.comment_id);
+	RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER comments_touch_blog ON comments;
+CREATE TRIGGER comments_touch_blog AFTER INSERT OR UPDATE OR DELETE ON comments
+	FOR EACH ROW EXECUTE FUNCTION touch_blog_activity();
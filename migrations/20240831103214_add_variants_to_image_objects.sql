@@ -0,0 +1,3 @@
+-- Variants stored of each object by their filename in its variants dir, so they are picked
+-- without asking the store. Unknown for objects stored before they were recorded
+ALTER TABLE image_objects ADD COLUMN variants TEXT[];
@@ -2,6 +2,12 @@ pub mod account;
 pub mod blog;
 pub mod blog_grouping;
 pub mod comment;
+pub mod event;
+pub mod feed;
+pub mod job;
+pub mod read_cache;
 pub mod reply;
 pub mod server;
+pub mod sitemap;
 pub mod user;
+pub mod webhook;
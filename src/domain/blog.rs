@@ -1,5 +1,13 @@
+pub mod attachments;
+pub mod bundle;
 pub mod images;
 mod img_host_injector;
+pub mod include;
+pub mod og_image;
+pub mod page;
+pub mod slug;
+pub mod stats;
+pub mod toc;
 
 pub mod features;
 pub mod value_objects;
@@ -14,19 +22,45 @@ mod config {
 
     use crate::{persistence::public::PkgDir, server::AppConfig};
 
+    use super::{
+        attachments::AttachmentLimits,
+        features::{get_by_id::EmbeddedComments, upload_image::UploadLimits},
+        stats::ReadingSpeed,
+    };
+
     #[derive(Clone)]
     pub struct Config {
         pkg_dir: Data<PkgDir>,
+        embedded_comments: Data<EmbeddedComments>,
+        reading_speed: Data<ReadingSpeed>,
+        upload_limits: Data<UploadLimits>,
+        attachment_limits: Data<AttachmentLimits>,
     }
 
     impl Config {
-        pub fn new(pkg_dir: Data<PkgDir>) -> Self {
-            Self { pkg_dir }
+        pub fn new(
+            pkg_dir: Data<PkgDir>,
+            embedded_comments: u32,
+            reading_speed: ReadingSpeed,
+            upload_limits: UploadLimits,
+            attachment_limits: AttachmentLimits,
+        ) -> Self {
+            Self {
+                pkg_dir,
+                embedded_comments: Data::new(EmbeddedComments(embedded_comments)),
+                reading_speed: Data::new(reading_speed),
+                upload_limits: Data::new(upload_limits),
+                attachment_limits: Data::new(attachment_limits),
+            }
         }
     }
 
     impl AppConfig for Config {
         fn configure(self, config: &mut actix_web::web::ServiceConfig) {
+            config.app_data(self.embedded_comments);
+            config.app_data(self.reading_speed);
+            config.app_data(self.upload_limits);
+            config.app_data(self.attachment_limits);
             config.service(actix_files::Files::new(
                 "/blogs/pkg/",
                 PathBuf::from(self.pkg_dir.as_ref()).join("blogs"),
@@ -0,0 +1,120 @@
+//! Files attached to a blog that are not shown in it but downloaded, like papers, source
+//! archives or datasets. Markdown links to their bare filename point to their download.
+
+use uuid::Uuid;
+
+mod subscriber;
+
+pub use filename::Filename;
+pub use subscriber::AttachmentSubscriber;
+
+/// Prefix of every attachment in the blob store, apart from the images of `STATIC_DIR`
+const ATTACHMENTS_DIR: &str = "attachments";
+
+const ALLOWED_EXTENSIONS: [&str; 13] = [
+    "pdf", "zip", "gz", "tgz", "bz2", "xz", "7z", "csv", "tsv", "json", "txt", "parquet", "ipynb",
+];
+
+/// Bytes attachments may take, counted as they are received
+#[derive(Debug, Clone, Copy)]
+pub struct AttachmentLimits {
+    pub file: usize,
+    /// Of every file sent at once
+    pub request: usize,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        Self {
+            file: 100 * 1024 * 1024,
+            request: 200 * 1024 * 1024,
+        }
+    }
+}
+
+/// Key of the attachment in the blob store
+pub fn key(blog_id: Uuid, filename: &Filename) -> String {
+    format!("{}/{}", dir(blog_id), filename.as_ref())
+}
+
+/// Directory of every attachment of the blog in the blob store
+pub fn dir(blog_id: Uuid) -> String {
+    format!("{}/{}", ATTACHMENTS_DIR, blog_id)
+}
+
+pub mod filename {
+    use super::ALLOWED_EXTENSIONS;
+
+    use crate::persistence::images;
+
+    #[derive(Debug)]
+    #[repr(transparent)]
+    pub struct Filename(images::Filename);
+
+    impl Filename {
+        /// Besides having an allowed extension, it can not be read as an url of its own, like
+        /// `mailto:someone@example.com.txt`, nor be hidden
+        pub fn new(filename: &str) -> Result<&Self, images::FilenameError> {
+            let (filename, ext) = images::Filename::new_with_extension(filename)?;
+
+            if !ALLOWED_EXTENSIONS.contains(&ext) {
+                return Err(images::FilenameError::InvalidExtension);
+            }
+
+            let name: &str = filename.as_ref();
+            if name.starts_with('.') || name.contains([':', '?', '#', '\\']) {
+                return Err(images::FilenameError::HasParent);
+            }
+
+            Ok(unsafe { Self::unchecked_from_inner(filename) })
+        }
+
+        /// Extension of the file, which tells its content type
+        pub fn extension(&self) -> &str {
+            let name: &str = self.as_ref();
+            // Validated to have one
+            name.rsplit_once('.').map_or("", |(_, extension)| extension)
+        }
+
+        const unsafe fn unchecked_from_inner(filename: &images::Filename) -> &Self {
+            &*(filename as *const _ as *const Self)
+        }
+    }
+
+    impl AsRef<str> for Filename {
+        fn as_ref(&self) -> &str {
+            self.0.as_ref()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn accepts_documents_and_archives() {
+            for name in ["paper.pdf", "source.tar.gz", "data set.csv"] {
+                Filename::new(name).unwrap();
+            }
+        }
+
+        #[test]
+        fn rejects_what_could_be_urls() {
+            for name in [
+                "cat.png",
+                "run.exe",
+                "../paper.pdf",
+                ".env.txt",
+                "mailto:a@b.txt",
+                "paper.pdf?x.pdf",
+            ] {
+                assert!(Filename::new(name).is_err(), "{}", name);
+            }
+        }
+
+        #[test]
+        fn tells_extension() {
+            assert_eq!(Filename::new("source.tar.gz").unwrap().extension(), "gz");
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use actix_web::web::Data;
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use crate::{
+    domain::event::{DomainEvent, Subscriber},
+    persistence::blobs::{self, BlobStore},
+};
+
+use super::dir;
+
+/// Deletes the files of deleted blogs, whose attachment rows go away with them
+pub struct AttachmentSubscriber {
+    blobs: Data<dyn BlobStore>,
+}
+
+impl AttachmentSubscriber {
+    pub fn new(blobs: Data<dyn BlobStore>) -> Self {
+        Self { blobs }
+    }
+
+    /// Whatever was left by a failed try is deleted when the event is redelivered
+    async fn delete_all(&self, blog_id: Uuid) -> Result<(), blobs::Error> {
+        for blob in self.blobs.list(&dir(blog_id)).await? {
+            self.blobs.delete(&blob.key).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Subscriber for AttachmentSubscriber {
+    fn name(&self) -> &'static str {
+        "attachments"
+    }
+
+    fn handle<'a>(
+        &'a self,
+        _id: Uuid,
+        event: &'a DomainEvent,
+    ) -> LocalBoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            match event {
+                DomainEvent::BlogDeleted { blog_id } => {
+                    self.delete_all(*blog_id).await.map_err(|e| e.to_string())
+                }
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::persistence::blobs::FsStore;
+
+    #[actix_web::test]
+    async fn deletes_the_files_of_deleted_blogs() {
+        let root = std::env::temp_dir().join(format!("attachments-{}", Uuid::new_v4()));
+        let blobs: Arc<dyn BlobStore> = Arc::new(FsStore::new(&root));
+        let blobs = Data::from(blobs);
+        let (deleted, kept) = (Uuid::new_v4(), Uuid::new_v4());
+
+        for blog_id in [deleted, kept] {
+            blobs
+                .put(
+                    &format!("{}/paper.pdf", dir(blog_id)),
+                    vec![1],
+                    "application/pdf",
+                )
+                .await
+                .unwrap();
+        }
+
+        let subscriber = AttachmentSubscriber::new(blobs.clone());
+        let event = DomainEvent::BlogDeleted { blog_id: deleted };
+        subscriber.handle(Uuid::new_v4(), &event).await.unwrap();
+        subscriber.handle(Uuid::new_v4(), &event).await.unwrap();
+
+        assert!(blobs.list(&dir(deleted)).await.unwrap().is_empty());
+        assert_eq!(blobs.list(&dir(kept)).await.unwrap().len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,309 @@
+//! Blogs uploaded as a single zip or tar.gz archive, holding an `index.md` with optional front
+//! matter and the images it shows in an `images` folder. Both may sit inside a single top level
+//! folder, as archiving a folder leaves them.
+
+use std::io::{self, Cursor, Read};
+
+use flate2::read::GzDecoder;
+
+use super::features::upload_image::UploadLimits;
+
+const INDEX: &str = "index.md";
+const IMAGES_DIR: &str = "images";
+
+/// Files of the archive the blog is made of
+#[derive(Debug)]
+pub struct Bundle {
+    pub markdown: String,
+    /// By filename, as they are named in the `images` folder
+    pub images: Vec<(String, Vec<u8>)>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("bundle is neither a zip nor a tar.gz")]
+    UnknownFormat,
+    #[error("could not read bundle: {0}")]
+    Corrupt(String),
+    #[error("bundle has no index.md")]
+    NoIndex,
+    #[error("index.md is not utf-8")]
+    InvalidIndex,
+    #[error("{0} is larger than allowed")]
+    TooLarge(String),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Corrupt(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for Error {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::Corrupt(e.to_string())
+    }
+}
+
+/// Reads the index and images of the archive, other files are ignored. Each of them may take
+/// up to the file limit once decompressed, and every file of the archive, ignored or not, the
+/// request limit, so small archives can not expand into anything larger
+pub fn read(archive: &[u8], limits: &UploadLimits) -> Result<Bundle, Error> {
+    let mut reader = Reader {
+        files: vec![],
+        limits,
+        total: 0,
+    };
+
+    if archive.starts_with(b"PK\x03\x04") {
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive))?;
+
+        for i in 0..zip.len() {
+            let file = zip.by_index(i)?;
+            if file.is_file() {
+                let path = file.name().to_owned();
+                reader.add(path, file)?;
+            }
+        }
+    } else if archive.starts_with(b"\x1f\x8b") {
+        let mut tar = tar::Archive::new(GzDecoder::new(archive));
+
+        for entry in tar.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() {
+                let path = entry.path()?.to_string_lossy().into_owned();
+                reader.add(path, entry)?;
+            }
+        }
+    } else {
+        return Err(Error::UnknownFormat);
+    }
+
+    reader.bundle()
+}
+
+/// Splits the markdown into its front matter, between `---` lines at its start, and the rest
+pub fn split_front_matter(markdown: &str) -> (Option<&str>, &str) {
+    let Some(rest) = markdown
+        .strip_prefix("---\n")
+        .or_else(|| markdown.strip_prefix("---\r\n"))
+    else {
+        return (None, markdown);
+    };
+
+    let mut at = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..at]), &rest[at + line.len()..]);
+        }
+        at += line.len();
+    }
+
+    (None, markdown)
+}
+
+struct Reader<'a> {
+    /// Those that may be the index or an image, by their path in the archive
+    files: Vec<(String, Vec<u8>)>,
+    limits: &'a UploadLimits,
+    total: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn add(&mut self, path: String, file: impl Read) -> Result<(), Error> {
+        let path = path.trim_start_matches("./").to_owned();
+        if !is_relevant(&path) {
+            // Decompressed all the same
+            let remaining = self.limits.request.saturating_sub(self.total);
+            let read = io::copy(&mut file.take(remaining as u64 + 1), &mut io::sink())?;
+
+            self.total += read as usize;
+            if self.total > self.limits.request {
+                return Err(Error::TooLarge(path));
+            }
+
+            return Ok(());
+        }
+
+        let mut content = vec![];
+        file.take(self.limits.file as u64 + 1)
+            .read_to_end(&mut content)?;
+
+        self.total += content.len();
+        if content.len() > self.limits.file || self.total > self.limits.request {
+            return Err(Error::TooLarge(path));
+        }
+
+        self.files.push((path, content));
+        Ok(())
+    }
+
+    fn bundle(self) -> Result<Bundle, Error> {
+        // The shallowest index tells where the bundle is rooted
+        let root = self
+            .files
+            .iter()
+            .filter_map(|(path, _)| path.strip_suffix(INDEX))
+            .filter(|root| root.is_empty() || root.ends_with('/'))
+            .min_by_key(|root| root.len())
+            .ok_or(Error::NoIndex)?
+            .to_owned();
+
+        let images_dir = format!("{}{}/", root, IMAGES_DIR);
+        let mut markdown = None;
+        let mut images = vec![];
+
+        for (path, content) in self.files {
+            if path.strip_prefix(&root) == Some(INDEX) {
+                markdown = Some(String::from_utf8(content).map_err(|_| Error::InvalidIndex)?);
+                continue;
+            }
+
+            let Some(filename) = path.strip_prefix(&images_dir) else {
+                continue;
+            };
+
+            // Nested folders, and files systems hide like `.DS_Store`
+            if filename.contains('/') || filename.starts_with('.') {
+                continue;
+            }
+
+            images.push((filename.to_owned(), content));
+        }
+
+        Ok(Bundle {
+            markdown: markdown.ok_or(Error::NoIndex)?,
+            images,
+        })
+    }
+}
+
+/// An index or an image, at the root or within a top level folder
+fn is_relevant(path: &str) -> bool {
+    let parts = path.split('/').collect::<Vec<_>>();
+
+    matches!(
+        parts.as_slice(),
+        [INDEX] | [_, INDEX] | [IMAGES_DIR, _] | [_, IMAGES_DIR, _]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(vec![]));
+        for (path, content) in files {
+            zip.start_file(*path, Default::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut tar = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, path, *content).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn reads_zips_and_tarballs() {
+        let files: [(&str, &[u8]); 4] = [
+            ("index.md", b"# Hello"),
+            ("images/cat.png", b"png"),
+            ("images/.DS_Store", b"mac"),
+            ("notes.txt", b"ignored"),
+        ];
+
+        for archive in [zip(&files), tar_gz(&files)] {
+            let bundle = read(&archive, &UploadLimits::default()).unwrap();
+
+            assert_eq!(bundle.markdown, "# Hello");
+            assert_eq!(bundle.images, vec![("cat.png".to_owned(), b"png".to_vec())]);
+        }
+    }
+
+    #[test]
+    fn roots_at_a_top_level_folder() {
+        let archive = zip(&[
+            ("post/index.md", b"# Hello"),
+            ("post/images/cat.png", b"png"),
+            ("post/drafts/index.md", b"# Draft"),
+        ]);
+
+        let bundle = read(&archive, &UploadLimits::default()).unwrap();
+
+        assert_eq!(bundle.markdown, "# Hello");
+        assert_eq!(bundle.images.len(), 1);
+    }
+
+    #[test]
+    fn limits_what_files_expand_to() {
+        let limits = UploadLimits {
+            file: 16,
+            request: 24,
+        };
+
+        let archive = tar_gz(&[("index.md", b"# Hello"), ("images/big.png", &[0; 17])]);
+        assert!(
+            matches!(read(&archive, &limits), Err(Error::TooLarge(path)) if path == "images/big.png")
+        );
+
+        let archive = zip(&[
+            ("index.md", b"# Hello"),
+            ("images/a.png", &[0; 16]),
+            ("images/b.png", &[0; 16]),
+        ]);
+        assert!(matches!(read(&archive, &limits), Err(Error::TooLarge(_))));
+
+        for archive in [
+            zip(&[("index.md", b"# Hello"), ("data.bin", &[0; 32])]),
+            tar_gz(&[("index.md", b"# Hello"), ("data.bin", &[0; 32])]),
+        ] {
+            assert!(
+                matches!(read(&archive, &limits), Err(Error::TooLarge(path)) if path == "data.bin")
+            );
+        }
+    }
+
+    #[test]
+    fn needs_an_index() {
+        let archive = zip(&[("README.md", b"# Hello")]);
+
+        assert!(matches!(
+            read(&archive, &UploadLimits::default()),
+            Err(Error::NoIndex)
+        ));
+        assert!(matches!(
+            read(b"plain text", &UploadLimits::default()),
+            Err(Error::UnknownFormat)
+        ));
+    }
+
+    #[test]
+    fn splits_front_matter() {
+        assert_eq!(
+            split_front_matter("---\ntags: []\n---\n# Hello\n"),
+            (Some("tags: []\n"), "# Hello\n")
+        );
+        assert_eq!(
+            split_front_matter("# Hello\n---\n"),
+            (None, "# Hello\n---\n")
+        );
+        assert_eq!(
+            split_front_matter("---\nunclosed\n# Hello"),
+            (None, "---\nunclosed\n# Hello")
+        );
+    }
+}
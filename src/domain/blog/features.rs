@@ -1,10 +1,22 @@
 pub mod create_one;
+pub mod delete_one;
 pub mod get_all;
 pub mod get_by_id;
+pub mod get_post;
 pub mod get_image;
+pub mod get_og_image;
 pub mod upload_image;
+pub mod list_images;
+pub mod delete_image;
+pub mod rename_image;
 pub mod update_one;
 pub mod set_tags;
 pub mod recompile_markdowns;
+pub mod collect_orphaned_images;
 pub mod get_content;
 pub mod set_content;
+pub mod upload_attachment;
+pub mod list_attachments;
+pub mod get_attachment;
+pub mod delete_attachment;
+pub mod create_from_bundle;
@@ -0,0 +1,800 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog::images::{
+            objects, variants::VARIANTS_DIR, Filename, BLOG_IMAGES_DIR, OBJECTS_DIR, QUARANTINE_DIR,
+        },
+        job::{JobKind, JobPayload, JobProgress},
+    },
+    persistence::{
+        blobs::{self, BlobStore},
+        db::Pool,
+    },
+    server::service::sync_service,
+};
+
+sync_service!(CollectOrphanedImages; pool: Data<Pool>, blobs: Data<dyn BlobStore>);
+
+impl CollectOrphanedImages {
+    pub fn new(pool: Data<Pool>, blobs: Data<dyn BlobStore>) -> Self {
+        Self { pool, blobs }
+    }
+}
+
+/// How long uploads are left alone, and then kept in quarantine, unless told otherwise
+pub const DEFAULT_GRACE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Shortest grace, uploads still being named in their blog would be collected otherwise
+pub const MIN_GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// Longest grace, which times and intervals can still be shifted by
+pub const MAX_GRACE: Duration = Duration::from_secs(10 * 365 * 24 * 60 * 60);
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Payload {
+    pub dry_run: bool,
+    pub grace_secs: u64,
+}
+
+impl JobPayload for Payload {
+    const KIND: JobKind = JobKind::CollectOrphanedImages;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("could not list uploads: {0}")]
+    Storage(#[from] blobs::Error),
+}
+
+/// Paths relative to the blog uploads, like `{blog_id}/{filename}`, those of objects are
+/// relative to the objects
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub dry_run: bool,
+    /// Uploads no markdown references, along with variants of missing images, moved to
+    /// quarantine (or that would be on a dry run)
+    pub quarantined: Vec<String>,
+    /// Directories of blogs that do not exist, moved to quarantine
+    pub unknown_blogs: Vec<String>,
+    /// Quarantined uploads referenced again, moved back
+    pub restored: Vec<String>,
+    /// Quarantined for longer than the grace period, deleted
+    pub deleted: Vec<String>,
+    /// Aliases to objects the markdown of their blog does not reference, removed
+    pub pruned_aliases: Vec<String>,
+    /// Objects no alias holds, along with variants of missing objects, moved to quarantine
+    pub objects: Vec<String>,
+}
+
+/// Images each blog references
+type References = HashMap<Uuid, Vec<String>>;
+
+/// Blob listed under the blog uploads, the objects or a quarantined batch, by its key relative
+/// to them
+#[derive(Debug)]
+struct Blob {
+    path: String,
+    modified: SystemTime,
+}
+
+impl CollectOrphanedImages {
+    /// Quarantines the uploads untouched for `grace` that nothing references, then deletes
+    /// those quarantined for `grace` and restores those referenced again meanwhile. On a dry
+    /// run nothing is moved, the report only lists what would be. The grace is at least
+    /// [`MIN_GRACE`] and at most [`MAX_GRACE`]. Everything goes through the blob store, so
+    /// quarantined blobs are moved by key within it
+    pub async fn run(
+        &self,
+        dry_run: bool,
+        grace: Duration,
+        progress: JobProgress,
+    ) -> Result<Report, Error> {
+        let references: References = sqlx::query!("SELECT id, images FROM blogs")
+            .fetch_all(self.pool.as_ref())
+            .await?
+            .into_iter()
+            .map(|blog| (blog.id, blog.images))
+            .collect();
+
+        let grace = grace.clamp(MIN_GRACE, MAX_GRACE);
+        let now = SystemTime::now();
+        let untouched_since = now.checked_sub(grace).unwrap_or(UNIX_EPOCH);
+
+        let mut report = Report {
+            dry_run,
+            ..Default::default()
+        };
+
+        let objects = self.prune_objects(dry_run, grace, &mut report).await?;
+
+        let uploads = self.list(BLOG_IMAGES_DIR).await?;
+        let batches = quarantined_batches(self.list(QUARANTINE_DIR).await?);
+        let orphans = scan(&uploads, &references, untouched_since);
+        let stored_objects = self.list(OBJECTS_DIR).await?;
+        let orphaned_objects = scan_objects(&stored_objects, &objects, untouched_since);
+
+        progress
+            .set_total((batches.len() + orphans.len() + orphaned_objects.len()) as i32)
+            .await?;
+
+        let uploaded: HashSet<&str> = uploads.iter().map(|blob| blob.path.as_str()).collect();
+
+        for (quarantined_at, blobs) in &batches {
+            let batch = format!("{}/{}", QUARANTINE_DIR, quarantined_at);
+            let expired = (UNIX_EPOCH + Duration::from_secs(*quarantined_at))
+                .checked_add(grace)
+                .is_some_and(|expires_at| expires_at <= now);
+
+            let result = if expired {
+                self.delete_batch(&batch, blobs, &references, &uploaded, dry_run)
+                    .await
+                    .map(|deleted| report.deleted.extend(deleted))
+            } else {
+                self.restore_batch(&batch, blobs, &references, &uploaded, dry_run)
+                    .await
+                    .map(|restored| report.restored.extend(restored))
+            };
+
+            if let Err(e) = result {
+                report_error(&progress, &batch, &e.to_string()).await;
+            }
+            step(&progress).await;
+        }
+
+        let batch = format!("{}/{}", QUARANTINE_DIR, secs(now));
+
+        for orphan in orphans {
+            let result = match dry_run {
+                true => Ok(()),
+                false => {
+                    let blobs = unit(&uploads, &orphan.path);
+                    self.move_all(BLOG_IMAGES_DIR, &batch, &blobs).await
+                }
+            };
+
+            match result {
+                Ok(()) if orphan.unknown_blog => report.unknown_blogs.push(orphan.path),
+                Ok(()) => report.quarantined.push(orphan.path),
+                Err(e) => report_error(&progress, &orphan.path, &e.to_string()).await,
+            }
+            step(&progress).await;
+        }
+
+        let objects_batch = format!("{}/{}", batch, OBJECTS_DIR);
+
+        for orphan in orphaned_objects {
+            let result = match dry_run {
+                true => Ok(true),
+                false => {
+                    self.quarantine_object(&stored_objects, &orphan, &objects_batch)
+                        .await
+                }
+            };
+
+            match result {
+                Ok(true) => report.objects.push(orphan),
+                Ok(false) => {}
+                Err(e) => report_error(&progress, &orphan, &e.to_string()).await,
+            }
+            step(&progress).await;
+        }
+
+        Ok(report)
+    }
+
+    /// Every blob under `dir`, keyed relative to it
+    async fn list(&self, dir: &str) -> Result<Vec<Blob>, blobs::Error> {
+        let prefix = format!("{}/", dir);
+
+        Ok(self
+            .blobs
+            .list_all(dir)
+            .await?
+            .into_iter()
+            .filter_map(|listed| {
+                Some(Blob {
+                    path: listed.key.strip_prefix(&prefix)?.to_owned(),
+                    modified: listed.modified,
+                })
+            })
+            .collect())
+    }
+
+    /// Moves the blobs at `paths` from one directory of the store to the other, each copied
+    /// before it is deleted so a failure never loses one
+    async fn move_all(&self, from: &str, to: &str, paths: &[&str]) -> Result<(), blobs::Error> {
+        for path in paths {
+            let from = format!("{}/{}", from, path);
+            let Some(content) = self.blobs.get(&from).await? else {
+                continue;
+            };
+
+            let extension = path.rsplit_once('.').map_or("", |(_, extension)| extension);
+            let content_type = actix_files::file_extension_to_mime(extension);
+
+            self.blobs
+                .put(
+                    &format!("{}/{}", to, path),
+                    content.to_vec(),
+                    content_type.as_ref(),
+                )
+                .await?;
+            self.blobs.delete(&from).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the blobs of the object at `path` to `batch`, unless it was stored again since it
+    /// was scanned. Uploads store its blobs and row under the same lock, so none is moved once
+    /// the row is back. Whether it was moved
+    async fn quarantine_object(
+        &self,
+        stored: &[Blob],
+        path: &str,
+        batch: &str,
+    ) -> Result<bool, Error> {
+        let hash = object_hash(path);
+        let mut tx = self.pool.begin().await?;
+        objects::lock(&mut tx, hash).await?;
+
+        if objects::find(&mut tx, hash).await?.is_some() {
+            return Ok(false);
+        }
+
+        self.move_all(OBJECTS_DIR, batch, &unit(stored, path))
+            .await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Removes the aliases uploaded before `grace` that their blog does not reference, then the
+    /// objects created before it that no alias holds. Returns the objects left, named as they
+    /// are stored. A dry run rolls it all back
+    async fn prune_objects(
+        &self,
+        dry_run: bool,
+        grace: Duration,
+        report: &mut Report,
+    ) -> Result<HashSet<String>, Error> {
+        let grace = grace.as_secs_f64();
+        let mut tx = self.pool.begin().await?;
+
+        let aliases = sqlx::query!(
+            r#"DELETE FROM blog_images i
+            USING blogs b
+            WHERE b.id = i.blog_id
+                AND i.hash IS NOT NULL
+                AND i.uploaded_at < now() - make_interval(secs => $1)
+                AND NOT i.filename = ANY(b.images)
+            RETURNING i.blog_id, i.filename"#,
+            grace
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM image_objects o
+            WHERE o.created_at < now() - make_interval(secs => $1)
+                AND NOT EXISTS (SELECT 1 FROM blog_images i WHERE i.hash = o.hash)"#,
+            grace
+        )
+        .execute(&mut tx)
+        .await?;
+
+        let objects = sqlx::query_scalar!(
+            r#"SELECT hash || '.' || extension as "object!" FROM image_objects"#
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+
+        report.pruned_aliases = aliases
+            .into_iter()
+            .map(|alias| format!("{}/{}", alias.blog_id, alias.filename))
+            .collect();
+
+        Ok(objects.into_iter().collect())
+    }
+
+    /// Deletes the batch, and the metadata of its images not uploaded again. Returns what was
+    /// deleted
+    async fn delete_batch(
+        &self,
+        batch: &str,
+        blobs: &[Blob],
+        references: &References,
+        uploaded: &HashSet<&str>,
+        dry_run: bool,
+    ) -> Result<Vec<String>, Error> {
+        let entries = batch_entries(blobs, references);
+
+        if !dry_run {
+            for entry in &entries {
+                let Some((blog_id, filename)) = entry.image() else {
+                    continue;
+                };
+
+                if uploaded.contains(entry.path.as_str()) {
+                    continue;
+                }
+
+                sqlx::query!(
+                    "DELETE FROM blog_images WHERE blog_id = $1 AND filename = $2",
+                    blog_id,
+                    filename
+                )
+                .execute(self.pool.as_ref())
+                .await?;
+            }
+
+            for blob in blobs {
+                self.blobs
+                    .delete(&format!("{}/{}", batch, blob.path))
+                    .await?;
+            }
+        }
+
+        Ok(entries.into_iter().map(|entry| entry.path).collect())
+    }
+
+    /// Moves back the images of the batch referenced again, unless uploaded again meanwhile.
+    /// Returns those restored
+    async fn restore_batch(
+        &self,
+        batch: &str,
+        blobs: &[Blob],
+        references: &References,
+        uploaded: &HashSet<&str>,
+        dry_run: bool,
+    ) -> Result<Vec<String>, Error> {
+        let mut restored = vec![];
+
+        for entry in batch_entries(blobs, references) {
+            let Some((blog_id, filename)) = entry.image() else {
+                continue;
+            };
+
+            let referenced = references
+                .get(&blog_id)
+                .is_some_and(|images| images.iter().any(|image| image == filename));
+
+            if !referenced || uploaded.contains(entry.path.as_str()) {
+                continue;
+            }
+
+            if !dry_run {
+                self.move_all(batch, BLOG_IMAGES_DIR, &unit(blobs, &entry.path))
+                    .await?;
+            }
+            restored.push(entry.path);
+        }
+
+        Ok(restored)
+    }
+}
+
+async fn report_error(progress: &JobProgress, item: &str, message: &str) {
+    if let Err(e) = progress.item_error(item, message).await {
+        eprintln!("Could not report image collection error: {:?}", e);
+    }
+}
+
+async fn step(progress: &JobProgress) {
+    if let Err(e) = progress.step().await {
+        eprintln!("Could not report image collection progress: {:?}", e);
+    }
+}
+
+fn secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Orphan {
+    /// Relative to the blog uploads
+    path: String,
+    unknown_blog: bool,
+}
+
+/// Blobs making up what is at `path`: the blob itself or those under it as a directory, and
+/// the variants of an image
+fn unit<'a>(blobs: &'a [Blob], path: &str) -> Vec<&'a str> {
+    let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+    let variants = match dir.is_empty() {
+        true => format!("{}/{}/", VARIANTS_DIR, name),
+        false => format!("{}/{}/{}/", dir, VARIANTS_DIR, name),
+    };
+    let is_image = name != VARIANTS_DIR && !dir.ends_with(VARIANTS_DIR);
+
+    blobs
+        .iter()
+        .map(|blob| blob.path.as_str())
+        .filter(|blob| {
+            *blob == path
+                || blob
+                    .strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with('/'))
+                || (is_image && blob.starts_with(&variants))
+        })
+        .collect()
+}
+
+/// Blobs of `blobs` grouped by their first directory, with their paths within it
+fn by_dir(blobs: &[Blob]) -> BTreeMap<&str, Vec<(&str, SystemTime)>> {
+    let mut dirs = BTreeMap::<_, Vec<_>>::new();
+
+    for blob in blobs {
+        if let Some((dir, path)) = blob.path.split_once('/') {
+            dirs.entry(dir).or_default().push((path, blob.modified));
+        }
+    }
+
+    dirs
+}
+
+/// Variants in `paths` by the image they belong to, along with when the newest was stored
+fn variants_of<'a>(paths: &[(&'a str, SystemTime)]) -> BTreeMap<&'a str, SystemTime> {
+    let mut variants = BTreeMap::new();
+
+    for (path, modified) in paths {
+        let Some(image) = path
+            .strip_prefix(VARIANTS_DIR)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .and_then(|rest| rest.split_once('/'))
+            .map(|(image, _)| image)
+        else {
+            continue;
+        };
+
+        let newest = variants.entry(image).or_insert(*modified);
+        *newest = (*newest).max(*modified);
+    }
+
+    variants
+}
+
+/// Uploads not stored since `untouched_since` that no blog references: directories of blogs
+/// that do not exist, images missing from their markdown and variants of missing images
+fn scan(uploads: &[Blob], references: &References, untouched_since: SystemTime) -> Vec<Orphan> {
+    let mut orphans = vec![];
+
+    for (name, paths) in by_dir(uploads) {
+        let Some(images) = Uuid::parse_str(name)
+            .ok()
+            .and_then(|blog_id| references.get(&blog_id))
+        else {
+            if paths
+                .iter()
+                .all(|(_, modified)| *modified < untouched_since)
+            {
+                orphans.push(Orphan {
+                    path: name.to_owned(),
+                    unknown_blog: true,
+                });
+            }
+            continue;
+        };
+
+        // Further down are variants, and what is not an upload like the og image
+        let files: HashSet<&str> = paths
+            .iter()
+            .map(|(path, _)| *path)
+            .filter(|path| !path.contains('/'))
+            .collect();
+
+        for (filename, modified) in &paths {
+            let is_orphan = files.contains(filename)
+                && Filename::new(filename).is_ok()
+                && !images.iter().any(|image| image == filename);
+
+            if is_orphan && *modified < untouched_since {
+                orphans.push(Orphan {
+                    path: format!("{}/{}", name, filename),
+                    unknown_blog: false,
+                });
+            }
+        }
+
+        for (image, modified) in variants_of(&paths) {
+            if !files.contains(image) && modified < untouched_since {
+                orphans.push(Orphan {
+                    path: format!("{}/{}/{}", name, VARIANTS_DIR, image),
+                    unknown_blog: false,
+                });
+            }
+        }
+    }
+
+    orphans.sort();
+
+    orphans
+}
+
+/// Hash of the object at `path`, relative to the objects, which is that of its variants too
+fn object_hash(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+
+    name.split('.').next().unwrap_or(name)
+}
+
+/// Objects not stored since `untouched_since` missing from `objects`, which holds those with a
+/// row, and variants of missing objects. Paths are relative to the objects
+fn scan_objects(
+    stored: &[Blob],
+    objects: &HashSet<String>,
+    untouched_since: SystemTime,
+) -> Vec<String> {
+    let paths: Vec<_> = stored
+        .iter()
+        .map(|blob| (blob.path.as_str(), blob.modified))
+        .collect();
+    let files: HashSet<&str> = paths
+        .iter()
+        .map(|(path, _)| *path)
+        .filter(|path| !path.contains('/'))
+        .collect();
+
+    let mut orphans: Vec<_> = paths
+        .iter()
+        .filter(|(path, modified)| {
+            files.contains(path) && !objects.contains(*path) && *modified < untouched_since
+        })
+        .map(|(path, _)| path.to_string())
+        .collect();
+
+    for (object, modified) in variants_of(&paths) {
+        if !files.contains(object) && modified < untouched_since {
+            orphans.push(format!("{}/{}", VARIANTS_DIR, object));
+        }
+    }
+
+    orphans.sort();
+
+    orphans
+}
+
+/// Blobs of the quarantine by the unix seconds of the batch holding them, each keyed relative
+/// to its batch
+fn quarantined_batches(quarantined: Vec<Blob>) -> BTreeMap<u64, Vec<Blob>> {
+    let mut batches = BTreeMap::<_, Vec<_>>::new();
+
+    for blob in quarantined {
+        let Some((Ok(secs), path)) = blob
+            .path
+            .split_once('/')
+            .map(|(batch, path)| (batch.parse::<u64>(), path))
+        else {
+            continue;
+        };
+
+        let path = path.to_owned();
+        batches.entry(secs).or_default().push(Blob {
+            path,
+            modified: blob.modified,
+        });
+    }
+
+    batches
+}
+
+struct BatchEntry {
+    /// Relative to the batch, as it was to the blog uploads
+    path: String,
+    blog_id: Option<Uuid>,
+}
+
+impl BatchEntry {
+    /// Blog and filename of a quarantined image
+    fn image(&self) -> Option<(Uuid, &str)> {
+        let blog_id = self.blog_id?;
+        let (_, filename) = self.path.split_once('/')?;
+
+        if filename.contains('/') {
+            return None;
+        }
+
+        Some((blog_id, filename))
+    }
+}
+
+/// Images and variants in the batch of blogs that exist, whole directories of the rest
+fn batch_entries(blobs: &[Blob], references: &References) -> Vec<BatchEntry> {
+    let mut entries = vec![];
+
+    for (name, paths) in by_dir(blobs) {
+        let blog_id = Uuid::parse_str(name)
+            .ok()
+            .filter(|blog_id| references.contains_key(blog_id));
+
+        if blog_id.is_none() {
+            entries.push(BatchEntry {
+                path: name.to_owned(),
+                blog_id: None,
+            });
+            continue;
+        }
+
+        for (path, _) in &paths {
+            if !path.contains('/') {
+                entries.push(BatchEntry {
+                    path: format!("{}/{}", name, path),
+                    blog_id,
+                });
+            }
+        }
+
+        for image in variants_of(&paths).into_keys() {
+            entries.push(BatchEntry {
+                path: format!("{}/{}/{}", name, VARIANTS_DIR, image),
+                blog_id,
+            });
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blobs(paths: &[&str], modified: SystemTime) -> Vec<Blob> {
+        paths
+            .iter()
+            .map(|path| Blob {
+                path: path.to_string(),
+                modified,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scans_unreferenced_uploads() {
+        let known = Uuid::new_v4();
+        let unknown = Uuid::new_v4();
+
+        let uploads = blobs(
+            &[
+                &format!("{}/shown.png", known),
+                &format!("{}/dropped.png", known),
+                &format!("{}/variants/shown.png/480.png", known),
+                &format!("{}/variants/missing.png/480.png", known),
+                &format!("{}/og/card.png", known),
+                &format!("{}/failed.png", unknown),
+            ],
+            SystemTime::now(),
+        );
+
+        let references = References::from([(known, vec!["shown.png".to_owned()])]);
+        let later = SystemTime::now() + Duration::from_secs(60);
+
+        let orphans = scan(&uploads, &references, later);
+        let recent = scan(&uploads, &references, UNIX_EPOCH);
+
+        let mut expected = vec![
+            Orphan {
+                path: format!("{}/dropped.png", known),
+                unknown_blog: false,
+            },
+            Orphan {
+                path: format!("{}/variants/missing.png", known),
+                unknown_blog: false,
+            },
+            Orphan {
+                path: unknown.to_string(),
+                unknown_blog: true,
+            },
+        ];
+        expected.sort();
+
+        assert_eq!(orphans, expected);
+        assert!(recent.is_empty());
+    }
+
+    #[test]
+    fn scans_objects_no_alias_holds() {
+        let stored = blobs(
+            &[
+                "held.png",
+                "unheld.png",
+                "variants/held.png/480.png",
+                "variants/unheld.png/480.png",
+                "variants/missing.png/480.png",
+            ],
+            SystemTime::now(),
+        );
+
+        let objects = HashSet::from(["held.png".to_owned()]);
+        let later = SystemTime::now() + Duration::from_secs(60);
+
+        let orphans = scan_objects(&stored, &objects, later);
+        let recent = scan_objects(&stored, &objects, UNIX_EPOCH);
+
+        assert_eq!(orphans, ["unheld.png", "variants/missing.png"]);
+        assert!(recent.is_empty());
+        assert_eq!(object_hash("unheld.png"), "unheld");
+        assert_eq!(object_hash("variants/missing.png"), "missing");
+    }
+
+    #[test]
+    fn moves_images_with_their_variants() {
+        let stored = blobs(
+            &[
+                "b/cat.png",
+                "b/cat.png.png",
+                "b/variants/cat.png/480.png",
+                "b/variants/cat.png.png/480.png",
+                "unheld.png",
+                "variants/unheld.png/480.webp",
+            ],
+            SystemTime::now(),
+        );
+
+        assert_eq!(
+            unit(&stored, "b/cat.png"),
+            ["b/cat.png", "b/variants/cat.png/480.png"]
+        );
+        assert_eq!(
+            unit(&stored, "b/variants/cat.png"),
+            ["b/variants/cat.png/480.png"]
+        );
+        assert_eq!(unit(&stored, "b").len(), 4);
+        assert_eq!(
+            unit(&stored, "unheld.png"),
+            ["unheld.png", "variants/unheld.png/480.webp"]
+        );
+    }
+
+    #[test]
+    fn groups_quarantine_by_batch() {
+        let blog_id = Uuid::new_v4();
+        let quarantined = blobs(
+            &[
+                &format!("100/{}/cat.png", blog_id),
+                &format!("100/{}/variants/cat.png/480.png", blog_id),
+                "100/objects/d0e1.png",
+                "200/gone/dog.png",
+                "notes/readme.txt",
+            ],
+            SystemTime::now(),
+        );
+
+        let batches = quarantined_batches(quarantined);
+        assert_eq!(batches.keys().collect::<Vec<_>>(), [&100, &200]);
+
+        let references = References::from([(blog_id, vec![])]);
+        let entries = batch_entries(&batches[&100], &references);
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.path.clone(), entry.image().is_some()))
+                .collect::<Vec<_>>(),
+            [
+                (format!("{}/cat.png", blog_id), true),
+                (format!("{}/variants/cat.png", blog_id), false),
+                ("objects".to_owned(), false),
+            ]
+        );
+    }
+}
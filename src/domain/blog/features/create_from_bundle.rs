@@ -0,0 +1,290 @@
+use actix_web::web::Data;
+use markdown_parse::{
+    content::ContentBuf, preview::PreviewBuf, BlogParse, CowStr, ImageUrlInjector, LinkUrlInjector,
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog::{
+            images::{
+                metadata::{self, StoredImage, StoredImages},
+                objects::{self, Object},
+                Filename,
+            },
+            value_objects::sub_categories::SubCategories,
+        },
+        user::admin_id::AdminId,
+    },
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+use super::{
+    create_one::{self, CreateOne, NewBlog},
+    upload_image::{self, UploadImage},
+};
+
+/// Folder of the bundle images are referenced from, as in `![cat](images/cat.png)`
+const IMAGES_DIR: &str = "images/";
+
+sync_service!(CreateFromBundle;
+    pool: Data<Pool>,
+    create_one: CreateOne,
+    upload_image: UploadImage
+);
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(markdown_parse::Error),
+    NoPreview,
+    EmptyContent,
+    /// Referenced by the markdown but not in the bundle
+    MissingImages(Vec<String>),
+    InvalidImage(String),
+    Decode(String),
+    Save,
+    Database,
+    Conflict,
+}
+
+impl From<create_one::Error> for Error {
+    fn from(e: create_one::Error) -> Self {
+        match e {
+            create_one::Error::Parse(e) => Self::Parse(e),
+            create_one::Error::NoPreview => Self::NoPreview,
+            create_one::Error::Conflict => Self::Conflict,
+            create_one::Error::Database => Self::Database,
+        }
+    }
+}
+
+impl From<markdown_parse::Error> for Error {
+    fn from(e: markdown_parse::Error) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+/// What the front matter of the bundle, or the request along with it, tells about the blog
+pub struct Details {
+    pub preview: Option<PreviewBuf>,
+    pub category_id: Uuid,
+    pub tags: Vec<Uuid>,
+    pub sub_categories: SubCategories,
+}
+
+impl CreateFromBundle {
+    /// Creates the blog along with every image of the bundle, which the markdown references
+    /// relative to it. Nothing is created unless all of it is: a reference missing from the
+    /// bundle or an image that can not be decoded fail before the blog is inserted, and the
+    /// blog and its images are committed at once. Images stored by content before a failure
+    /// are named by no blog, so the orphaned image collection removes them. Files of the images
+    /// folder that are not images, like a README, are left out unless the markdown shows them
+    pub async fn run(
+        &self,
+        admin_id: AdminId,
+        markdown: &str,
+        details: Details,
+        images: Vec<(String, Vec<u8>)>,
+    ) -> Result<Uuid, Error> {
+        let (content, referenced) = resolve_references(markdown, &images)?;
+        let content = ContentBuf::try_from(content).map_err(|_| Error::EmptyContent)?;
+        let images = shown_or_images(images, &referenced);
+
+        for (filename, _) in &images {
+            if Filename::new(filename).is_err() {
+                return Err(Error::InvalidImage(filename.clone()));
+            }
+        }
+
+        let mut stored = Vec::with_capacity(images.len());
+        for (filename, content) in images {
+            // Validated above
+            let object = match self
+                .upload_image
+                .store(Filename::new(&filename).unwrap(), content)
+                .await
+            {
+                Ok(object) => object,
+                Err(upload_image::Error::Decode) => return Err(Error::Decode(filename)),
+                Err(upload_image::Error::Save) => return Err(Error::Save),
+                Err(upload_image::Error::NotFound | upload_image::Error::Database) => {
+                    return Err(Error::Database)
+                }
+            };
+
+            stored.push((filename, object));
+        }
+
+        let blog_id = Uuid::new_v4();
+        let blog = NewBlog {
+            content: &content,
+            category_id: details.category_id,
+            preview: details.preview.as_ref(),
+            tags: details.tags,
+            sub_categories: details.sub_categories,
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        self.create_one
+            .create(&mut tx, blog_id, admin_id, blog, stored_images(&stored))
+            .await?;
+
+        for (filename, object) in &stored {
+            // Validated above
+            let filename = Filename::new(filename).unwrap();
+            objects::insert(&mut *tx, object).await?;
+            metadata::save(&mut *tx, blog_id, filename, object).await?;
+        }
+
+        self.create_one.commit(tx, blog_id).await?;
+
+        Ok(blog_id)
+    }
+}
+
+fn stored_images(stored: &[(String, Object)]) -> StoredImages {
+    stored
+        .iter()
+        .map(|(filename, object)| {
+            let image = StoredImage {
+                metadata: object.metadata.clone(),
+                object: Some(object.filename()),
+                variants: object.variants.clone(),
+            };
+
+            (filename.clone(), image)
+        })
+        .collect()
+}
+
+/// Leaves out files that are neither named as an image nor shown by the markdown
+fn shown_or_images(
+    images: Vec<(String, Vec<u8>)>,
+    referenced: &[String],
+) -> Vec<(String, Vec<u8>)> {
+    images
+        .into_iter()
+        .filter(|(filename, _)| Filename::new(filename).is_ok() || referenced.contains(filename))
+        .collect()
+}
+
+/// The markdown with images of the bundle referenced by their bare filename, as uploaded images
+/// are, along with the filenames it references. Fails with every relative reference the bundle
+/// does not have
+fn resolve_references(
+    markdown: &str,
+    images: &[(String, Vec<u8>)],
+) -> Result<(String, Vec<String>), Error> {
+    let BlogParse { images: urls, .. } = markdown_parse::parse(markdown, &AnyImage)?;
+
+    let mut markdown = markdown.to_owned();
+    let mut referenced = vec![];
+    let mut missing = vec![];
+
+    for url in urls.into_inner() {
+        if is_external(&url) {
+            continue;
+        }
+
+        let filename = url.strip_prefix("./").unwrap_or(&url);
+        let filename = filename.strip_prefix(IMAGES_DIR).unwrap_or(filename);
+
+        if !images.iter().any(|(name, _)| name == filename) {
+            missing.push(url);
+            continue;
+        }
+
+        referenced.push(filename.to_owned());
+
+        if filename != url {
+            if let Some(renamed) = markdown_parse::rename_image(&markdown, &url, filename) {
+                markdown = renamed;
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(Error::MissingImages(missing));
+    }
+
+    Ok((markdown, referenced))
+}
+
+/// Hosted elsewhere, or embedded
+fn is_external(url: &str) -> bool {
+    url.contains("://") || url.starts_with("//") || url.starts_with('/') || url.starts_with("data:")
+}
+
+/// Collects every image, to tell which of them the bundle should have
+struct AnyImage;
+
+impl ImageUrlInjector for AnyImage {
+    fn inject(&self, _url: &mut CowStr<'_>) {}
+
+    fn is_valid(&self, _url: &str) -> bool {
+        true
+    }
+}
+
+impl LinkUrlInjector for AnyImage {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn images(filenames: &[&str]) -> Vec<(String, Vec<u8>)> {
+        filenames
+            .iter()
+            .map(|filename| (filename.to_string(), vec![]))
+            .collect()
+    }
+
+    #[test]
+    fn references_bundle_images_by_filename() {
+        let markdown = "# Cats\n\n![a](images/a.png) ![b](./images/b.gif) ![c](c.png) ![d](https://example.com/d.png)";
+
+        let (resolved, referenced) =
+            resolve_references(markdown, &images(&["a.png", "b.gif", "c.png"])).unwrap();
+
+        assert_eq!(
+            resolved,
+            "# Cats\n\n![a](a.png) ![b](b.gif) ![c](c.png) ![d](https://example.com/d.png)"
+        );
+        assert_eq!(referenced, vec!["a.png", "b.gif", "c.png"]);
+    }
+
+    #[test]
+    fn leaves_out_files_not_shown_that_are_not_images() {
+        let kept = shown_or_images(
+            images(&["a.png", "README.txt", "notes.txt"]),
+            &["notes.txt".to_owned()],
+        );
+
+        assert_eq!(
+            kept.iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a.png", "notes.txt"]
+        );
+    }
+
+    #[test]
+    fn fails_with_every_missing_image() {
+        let markdown = "# Cats\n\n![a](images/a.png) ![b](images/b.png) ![c](images/c.png)";
+
+        let Err(Error::MissingImages(missing)) = resolve_references(markdown, &images(&["b.png"]))
+        else {
+            panic!("references were not validated");
+        };
+
+        assert_eq!(missing, vec!["images/a.png", "images/c.png"]);
+    }
+}
@@ -1,15 +1,20 @@
 use actix_web::web::Data;
 use markdown_parse::{content::ContentBuf, preview::PreviewBuf};
-use sqlx::query;
+use sqlx::{query, types::Json};
 use uuid::Uuid;
 
 use crate::{
     domain::{
-        blog::{value_objects::sub_categories::SubCategories, ImgHostInjectorFactory},
+        blog::{
+            images::metadata::StoredImages, slug, value_objects::sub_categories::SubCategories,
+            ImgHostInjectorFactory,
+        },
         blog_grouping,
+        event::{self, DomainEvent},
+        read_cache::{Invalidation, ReadCache},
         user::admin_id::AdminId,
     },
-    persistence::db::Pool,
+    persistence::db::{Pool, Transaction},
     server::service::sync_service,
 };
 
@@ -17,7 +22,11 @@ use super::set_tags;
 
 pub use compile_content::{compile_content, BlogCompile};
 
-sync_service!(CreateOne; pool: Data<Pool>, injector_factory: ImgHostInjectorFactory);
+sync_service!(CreateOne;
+    pool: Data<Pool>,
+    injector_factory: ImgHostInjectorFactory,
+    cache: Data<ReadCache>
+);
 
 pub enum Error {
     Parse(markdown_parse::Error),
@@ -41,6 +50,15 @@ impl From<sqlx::Error> for Error {
     }
 }
 
+/// What a blog is created from
+pub struct NewBlog<'a> {
+    pub content: &'a ContentBuf,
+    pub category_id: Uuid,
+    pub preview: Option<&'a PreviewBuf>,
+    pub tags: Vec<Uuid>,
+    pub sub_categories: SubCategories,
+}
+
 impl CreateOne {
     pub async fn run(
         &self,
@@ -52,13 +70,51 @@ impl CreateOne {
         sub_categories: SubCategories,
     ) -> Result<Uuid, Error> {
         let blog_id = Uuid::new_v4();
+        let blog = NewBlog {
+            content,
+            category_id,
+            preview,
+            tags,
+            sub_categories,
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        // Images are uploaded to a blog once it exists
+        self.create(&mut tx, blog_id, admin_id, blog, StoredImages::new())
+            .await?;
+
+        self.commit(tx, blog_id).await?;
+
+        Ok(blog_id)
+    }
+
+    /// Inserts the blog within `tx`, compiled with the `images` it will have once committed.
+    /// It is committed by [`CreateOne::commit`]
+    pub async fn create(
+        &self,
+        tx: &mut Transaction<'_>,
+        blog_id: Uuid,
+        admin_id: AdminId,
+        blog: NewBlog<'_>,
+        images: StoredImages,
+    ) -> Result<(), Error> {
+        let NewBlog {
+            content,
+            category_id,
+            preview,
+            tags,
+            sub_categories,
+        } = blog;
 
-        let injector = self.injector_factory.create(blog_id);
+        let injector = self.injector_factory.create(blog_id, images);
         let BlogCompile {
             title,
             html_content,
             images,
             main_image,
+            toc,
+            stats,
         } = compile_content(content, injector)?;
 
         let markdown_parse::PreviewParse {
@@ -75,9 +131,11 @@ impl CreateOne {
             }
         };
 
-        let mut tx = self.pool.begin().await.unwrap();
-        let result = query!(
-            r#"INSERT INTO
+        // The unique slug tells whether another blog took it, even one created meanwhile
+        let mut created = false;
+        for slug in slug::candidates(blog_id, &title) {
+            let result = query!(
+                r#"INSERT INTO
         blogs(
             id,
             admin_id,
@@ -88,54 +146,93 @@ impl CreateOne {
             preview,
             description,
             main_image,
-            images
-        )
-        VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
-            blog_id,
-            admin_id.into_inner(),
-            title,
-            content.as_ref(),
-            &html_content,
-            category_id,
-            preview.as_str(),
-            description,
-            main_image,
-            &images
+            images,
+            compiler_version,
+            slug,
+            toc,
+            word_count,
+            code_word_count,
+            image_count,
+            code_block_count,
+            link_count
         )
-        .execute(&mut tx)
-        .await?;
+        VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+        ON CONFLICT (slug) DO NOTHING"#,
+                blog_id,
+                admin_id.into_inner(),
+                title,
+                content.as_ref(),
+                &html_content,
+                category_id,
+                preview.as_str(),
+                description,
+                main_image,
+                &images,
+                markdown_parse::COMPILER_VERSION,
+                slug,
+                Json(&toc) as _,
+                stats.words,
+                stats.code_words,
+                stats.images,
+                stats.code_blocks,
+                stats.links
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            if result.rows_affected() == 1 {
+                created = true;
+                break;
+            }
+        }
 
-        if result.rows_affected() != 1 {
+        if !created {
             return Err(Error::Conflict);
         }
 
-        blog_grouping::link_sub_categories(&mut tx, sub_categories.as_ref(), blog_id).await?;
-        set_tags::create_tags(&mut tx, blog_id, tags).await?;
+        blog_grouping::link_sub_categories(&mut *tx, sub_categories.as_ref(), blog_id).await?;
+        set_tags::create_tags(tx, blog_id, tags).await?;
 
-        tx.commit().await?;
+        event::record(tx, &DomainEvent::BlogCreated { blog_id }).await?;
 
-        Ok(blog_id)
+        Ok(())
+    }
+
+    /// Commits the created blog, invalidating the listings it joins
+    pub async fn commit(&self, tx: Transaction<'_>, blog_id: Uuid) -> Result<(), Error> {
+        self.cache
+            .commit(tx, Invalidation::BlogMoved { blog_id })
+            .await
+            .map_err(|_| Error::Database)
     }
 }
 
 mod compile_content {
-    use markdown_parse::{content::ContentBuf, BlogParse, CowStr, ImageUrlInjector};
+    use markdown_parse::{
+        content::ContentBuf, BlogParse, CowStr, ImageUrlInjector, LinkUrlInjector,
+    };
+
+    use crate::domain::blog::{stats::BlogStats, toc::TocEntry};
 
     pub struct BlogCompile {
         pub title: String,
         pub html_content: String,
         pub images: Vec<String>,
         pub main_image: Option<String>,
+        pub toc: Vec<TocEntry>,
+        pub stats: BlogStats,
     }
 
     pub fn compile_content(
         content: &ContentBuf,
-        injector: impl ImageUrlInjector,
+        injector: impl ImageUrlInjector + LinkUrlInjector,
     ) -> Result<BlogCompile, markdown_parse::Error> {
         let BlogParse {
             title,
             content: html_content,
             images,
+            toc,
+            stats,
         } = markdown_parse::parse(content.as_ref(), &injector)?;
 
         let images = images.into_inner();
@@ -151,6 +248,8 @@ mod compile_content {
             html_content,
             images,
             main_image,
+            toc: toc.into_iter().map(Into::into).collect(),
+            stats: stats.into(),
         })
     }
 }
@@ -0,0 +1,62 @@
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::attachments::{self, Filename},
+    persistence::{
+        blobs::{self, BlobStore},
+        db::Pool,
+    },
+    server::service::sync_service,
+};
+
+sync_service!(DeleteAttachment; pool: Data<Pool>, blobs: Data<dyn BlobStore>);
+
+#[derive(Debug)]
+pub enum Error {
+    NotFound,
+    Storage,
+    Database,
+}
+
+impl From<blobs::Error> for Error {
+    fn from(e: blobs::Error) -> Self {
+        eprintln!("Could not delete attachment: {}", e);
+        Self::Storage
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+impl DeleteAttachment {
+    /// Links to it in the blog are left as they are, the download is just missing. The row is
+    /// kept when the file could not be deleted, so it can be tried again
+    pub async fn run(&self, blog_id: Uuid, filename: &Filename) -> Result<(), Error> {
+        let name: &str = filename.as_ref();
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            "DELETE FROM blog_attachments WHERE blog_id = $1 AND filename = $2",
+            blog_id,
+            name
+        )
+        .execute(&mut tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+
+        self.blobs
+            .delete(&attachments::key(blog_id, filename))
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use actix_web::web::Data;
+use markdown_parse::content::ContentBuf;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog::{
+            images::{keys, metadata, Filename},
+            ImgHostInjectorFactory,
+        },
+        read_cache::{Invalidation, ReadCache},
+    },
+    persistence::{
+        blobs::{self, BlobStore},
+        db::Pool,
+    },
+    server::service::sync_service,
+};
+
+use super::create_one::compile_content;
+
+sync_service!(DeleteImage;
+    pool: Data<Pool>,
+    blobs: Data<dyn BlobStore>,
+    injector_factory: ImgHostInjectorFactory,
+    cache: Data<ReadCache>
+);
+
+#[derive(Debug)]
+pub enum Error {
+    NotFound,
+    Database,
+    Storage,
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+impl From<blobs::Error> for Error {
+    fn from(_: blobs::Error) -> Self {
+        Self::Storage
+    }
+}
+
+impl DeleteImage {
+    /// Removes the image the blog names, with the variants of uploads stored by filename. Objects
+    /// are shared between blogs, so they are left for the orphaned image collection. Blogs still
+    /// showing it are compiled again, the image is just missing. The alias goes first, so a
+    /// failure never leaves it naming removed files
+    pub async fn run(&self, blog_id: Uuid, filename: &Filename) -> Result<(), Error> {
+        let deleted = self.delete_alias(blog_id, filename).await?;
+
+        let upload = keys::upload(blog_id, filename);
+        let legacy = self.blobs.exists(&upload).await?;
+
+        for variant in self
+            .blobs
+            .list(&keys::upload_variants(blog_id, filename))
+            .await?
+        {
+            self.blobs.delete(&variant.key).await?;
+        }
+        self.blobs.delete(&upload).await?;
+
+        if !legacy && !deleted {
+            return Err(Error::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the blog had the alias, recompiling the blog when it shows it
+    async fn delete_alias(&self, blog_id: Uuid, filename: &Filename) -> Result<bool, Error> {
+        let filename: &str = filename.as_ref().as_ref();
+
+        let mut tx = self.pool.begin().await?;
+
+        let deleted = sqlx::query!(
+            "DELETE FROM blog_images WHERE blog_id = $1 AND filename = $2",
+            blog_id,
+            filename
+        )
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        if deleted == 0 {
+            return Ok(false);
+        }
+
+        let content = sqlx::query_scalar!(
+            "SELECT content FROM blogs WHERE id = $1 AND $2 = ANY(images) FOR UPDATE",
+            blog_id,
+            filename
+        )
+        .fetch_optional(&mut tx)
+        .await?;
+
+        if let Some(content) = content {
+            // Is always valid because it is stored
+            let content = ContentBuf::from_boxed_unchecked(content.into_boxed_str());
+            let stored_images = metadata::load(&mut tx, blog_id).await?;
+
+            // Compiled before, so it still is
+            if let Ok(compiled) = compile_content(
+                &content,
+                self.injector_factory.create(blog_id, stored_images),
+            ) {
+                sqlx::query!(
+                    "UPDATE blogs SET html = $1, main_image = $2 WHERE id = $3",
+                    compiled.html_content,
+                    compiled.main_image,
+                    blog_id
+                )
+                .execute(&mut tx)
+                .await?;
+            }
+        }
+
+        self.cache
+            .commit(tx, Invalidation::BlogChanged { blog_id })
+            .await?;
+
+        Ok(true)
+    }
+}
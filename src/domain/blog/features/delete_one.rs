@@ -0,0 +1,48 @@
+use actix_web::web::Data;
+use sqlx::query;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        event::{self, DomainEvent},
+        read_cache::{Invalidation, ReadCache},
+    },
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+sync_service!(DeleteOne; pool: Data<Pool>, cache: Data<ReadCache>);
+
+pub enum Error {
+    NotFound,
+    Database,
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+impl DeleteOne {
+    /// Comments, tags and sub categories links are removed by the database along with the blog
+    pub async fn run(&self, id: Uuid) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = query!("DELETE FROM blogs WHERE id = $1", id)
+            .execute(&mut tx)
+            .await?;
+
+        if result.rows_affected() != 1 {
+            return Err(Error::NotFound);
+        }
+
+        event::record(&mut tx, &DomainEvent::BlogDeleted { blog_id: id }).await?;
+
+        self.cache
+            .commit(tx, Invalidation::BlogMoved { blog_id: id })
+            .await?;
+
+        Ok(())
+    }
+}
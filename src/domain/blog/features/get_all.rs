@@ -1,17 +1,29 @@
+use std::sync::Arc;
+
 use actix_web::web::Data;
-use sqlx::query_as;
+use sqlx::{query_as, types::Json};
 use uuid::Uuid;
 
 use crate::{
-    domain::blog_grouping::{category, headless_sub_category, headless_tag},
-    persistence::db::{decode::inline_vec::InlineVec, DateTime, Pool, Slice},
+    domain::{
+        blog::{include::Include, stats::ReadingSpeed},
+        blog_grouping::{category, headless_sub_category, headless_tag},
+        read_cache::ReadCache,
+    },
+    persistence::db::{DateTime, Pool, Slice},
     server::{service::sync_service, shared::query::QuerySlice},
 };
 
 use headless_sub_category::HeadlessSubCategory;
+use headless_tag::HeadlessTag;
 
-sync_service!(GetAll; pool: Data<Pool>);
+sync_service!(GetAll;
+    pool: Data<Pool>,
+    cache: Data<ReadCache>,
+    reading_speed: Data<ReadingSpeed>
+);
 
+/// Blog as listed along with what was included, the rest is left out of its json
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BlogPreview {
@@ -20,9 +32,13 @@ pub struct BlogPreview {
     pub preview: String,
     pub main_image: Option<String>,
     pub created_at: DateTime,
+    /// Minutes
+    pub reading_time: i32,
     pub category: category::Category,
-    pub tags: Vec<headless_tag::HeadlessTag>,
-    pub sub_categories: Vec<headless_sub_category::HeadlessSubCategory>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<HeadlessTag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_categories: Option<Vec<HeadlessSubCategory>>,
 }
 
 pub struct BlogData {
@@ -33,27 +49,40 @@ pub struct BlogData {
     pub created_at: DateTime,
     pub category_id: Uuid,
     pub category_name: String,
-    pub tags: Option<InlineVec<headless_tag::HeadlessTag>>,
-    pub sub_categories: InlineVec<headless_sub_category::HeadlessSubCategory>,
+    pub word_count: i32,
+    pub code_word_count: i32,
+    pub tags: Option<Json<Vec<HeadlessTag>>>,
+    pub sub_categories: Option<Json<Vec<HeadlessSubCategory>>>,
 }
 
-impl From<BlogData> for BlogPreview {
-    fn from(data: BlogData) -> Self {
+impl BlogPreview {
+    /// Keys of its json, those of what may be included too
+    pub const FIELDS: &'static [&'static str] = &[
+        "id",
+        "title",
+        "preview",
+        "mainImage",
+        "createdAt",
+        "readingTime",
+        "category",
+        "tags",
+        "subCategories",
+    ];
+
+    fn new(data: BlogData, reading_speed: &ReadingSpeed) -> Self {
         Self {
             id: data.id,
             title: data.title,
             preview: data.preview,
             main_image: data.main_image,
             created_at: data.created_at,
+            reading_time: reading_speed.reading_time(data.word_count, data.code_word_count),
             category: category::Category {
                 id: data.category_id,
                 name: data.category_name,
             },
-            tags: match data.tags {
-                Some(tags) => tags.into_inner(),
-                None => vec![],
-            },
-            sub_categories: data.sub_categories.into_inner(),
+            tags: data.tags.map(|tags| tags.0),
+            sub_categories: data.sub_categories.map(|sub_categories| sub_categories.0),
         }
     }
 }
@@ -63,41 +92,99 @@ impl GetAll {
         &self,
         slice: QuerySlice,
         search: &str,
-    ) -> Result<Vec<BlogPreview>, sqlx::Error> {
+        include: Include,
+    ) -> Result<Arc<Vec<BlogPreview>>, sqlx::Error> {
         let Slice { limit, offset } = slice.into();
+        let key = (limit, offset, search.to_owned(), include);
+
+        if let Some(blogs) = self.cache.listings.get(&key) {
+            return Ok(blogs);
+        }
+
+        let epoch = self.cache.listings.epoch();
+
+        let blogs = Arc::new(self.load(limit, offset, search, include).await?);
+        self.cache.listings.insert(key, blogs.clone(), epoch);
+
+        Ok(blogs)
+    }
 
+    /// Tags and sub categories are only aggregated when included
+    async fn load(
+        &self,
+        limit: i64,
+        offset: i64,
+        search: &str,
+        include: Include,
+    ) -> Result<Vec<BlogPreview>, sqlx::Error> {
         let blogs = query_as!(
             BlogData,
-                r#"SELECT 
-                    b.id, b.title, b.preview, b.main_image, c.id as category_id, c.name as category_name, b.created_at, 
-                    STRING_AGG(t.id || ',' || t.name || ',' || t.color, ';') AS "tags!: Option<InlineVec<headless_tag::HeadlessTag>>",
-                    STRING_AGG(sc.id || ',' || sc.name, ';') AS "sub_categories!: InlineVec<HeadlessSubCategory>"
-                FROM blogs b
-                JOIN 
-                    categories c ON b.category_id = c.id
-                LEFT JOIN
-                    tags_blogs bt ON b.id = bt.blog_id
-                LEFT JOIN
-                    tags t ON bt.tag_id = t.id
-                JOIN
-                    sub_categories_blogs sb ON b.id = sb.blog_id
-                JOIN
-                    sub_categories sc ON sb.sub_category_id = sc.id
-                WHERE b.title ILIKE $1
-                GROUP BY
-                    b.id, c.id, sc.id
-                ORDER BY b.created_at DESC
-                LIMIT $2 OFFSET $3"#,
+            r#"SELECT
+                b.id, b.title, b.preview, b.main_image, c.id as category_id, c.name as category_name, b.created_at,
+                b.word_count, b.code_word_count,
+                CASE WHEN $4 THEN COALESCE((
+                    SELECT json_agg(t ORDER BY t.name) FROM (
+                        SELECT DISTINCT t.id, t.name, t.color FROM tags_blogs tb
+                        JOIN tags t ON t.id = tb.tag_id
+                        WHERE tb.blog_id = b.id
+                    ) t
+                ), '[]') END as "tags: Json<Vec<HeadlessTag>>",
+                CASE WHEN $5 THEN COALESCE((
+                    SELECT json_agg(sc ORDER BY sc.name) FROM (
+                        SELECT DISTINCT sc.id, sc.name FROM sub_categories_blogs scb
+                        JOIN sub_categories sc ON sc.id = scb.sub_category_id
+                        WHERE scb.blog_id = b.id
+                    ) sc
+                ), '[]') END as "sub_categories: Json<Vec<HeadlessSubCategory>>"
+            FROM blogs b
+            JOIN categories c ON c.id = b.category_id
+            WHERE b.title ILIKE $1
+            ORDER BY b.created_at DESC
+            LIMIT $2 OFFSET $3"#,
             format!("%{}%", search),
             limit,
-            offset
+            offset,
+            include.tags,
+            include.sub_categories
         )
         .fetch_all(self.pool.as_ref())
         .await?
         .into_iter()
-        .map(BlogPreview::from)
+        .map(|data| BlogPreview::new(data, &self.reading_speed))
         .collect::<Vec<_>>();
 
         Ok(blogs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_the_keys_of_everything_included() {
+        let blog = BlogPreview {
+            id: Uuid::nil(),
+            title: String::new(),
+            preview: String::new(),
+            main_image: None,
+            created_at: DateTime::default(),
+            reading_time: 0,
+            category: category::Category {
+                id: Uuid::nil(),
+                name: String::new(),
+            },
+            tags: Some(vec![]),
+            sub_categories: Some(vec![]),
+        };
+
+        let serde_json::Value::Object(json) = serde_json::to_value(blog).unwrap() else {
+            panic!("blogs are objects");
+        };
+
+        assert_eq!(json.len(), BlogPreview::FIELDS.len());
+        assert!(json
+            .keys()
+            .all(|key| BlogPreview::FIELDS.contains(&key.as_str())));
+    }
+}
@@ -0,0 +1,32 @@
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::attachments::{self, Filename},
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+sync_service!(GetAttachment; pool: Data<Pool>);
+
+impl GetAttachment {
+    /// Key of the attachment in the blob store, `None` when the blog has none by that name
+    pub async fn run(
+        &self,
+        blog_id: Uuid,
+        filename: &Filename,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let name: &str = filename.as_ref();
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                SELECT 1 FROM blog_attachments WHERE blog_id = $1 AND filename = $2
+            ) as "exists!""#,
+            blog_id,
+            name
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(exists.then(|| attachments::key(blog_id, filename)))
+    }
+}
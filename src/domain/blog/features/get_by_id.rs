@@ -1,27 +1,43 @@
+use std::sync::Arc;
+
 use actix_web::web::Data;
-use sqlx::query_as;
-use tokio::join;
+use sqlx::{query_as, types::Json};
+use tokio::try_join;
 use uuid::Uuid;
 
 use crate::{
     domain::{
+        blog::{
+            include::Include,
+            stats::{BlogStats, ReadingSpeed},
+            toc::TocEntry,
+        },
         blog_grouping::{
             category, get_one_category, get_sub_categories_by_blog, get_tags_by_blog, sub_category,
             tag,
         },
         comment::{self, models::CommentByBlog},
+        read_cache::{ReadCache, Versioned},
     },
-    persistence::db::{DateTime, Executor, Pool, Slice},
+    persistence::db::{DateTime, Executor, Pool, Slice, Version},
     server::service::sync_service,
 };
 
 sync_service!(GetById;
     pool: Data<Pool>,
+    cache: Data<ReadCache>,
+    embedded_comments: Data<EmbeddedComments>,
+    reading_speed: Data<ReadingSpeed>,
     get_category: get_one_category::GetOneCategory,
     get_tags: get_tags_by_blog::GetTagsByBlog,
     get_sub_categories: get_sub_categories_by_blog::GetSubCategorysByBlog
 );
 
+/// Latest comments embedded in a blog, older ones are paged through its comments
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedComments(pub u32);
+
+/// Blog along with what was included, the rest is left out of its json
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BlogById {
@@ -30,10 +46,36 @@ pub struct BlogById {
     pub content: String,
     pub description: String,
     pub created_at: DateTime,
-    pub comments: Vec<CommentByBlog>,
+    /// Minutes
+    pub reading_time: i32,
+    pub stats: BlogStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<Vec<CommentByBlog>>,
     pub category: category::Category,
-    pub tags: Vec<tag::Tag>,
-    pub sub_categories: Vec<sub_category::SubCategory>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<tag::Tag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_categories: Option<Vec<sub_category::SubCategory>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toc: Option<Vec<TocEntry>>,
+}
+
+impl BlogById {
+    /// Keys of its json, those of what may be included too
+    pub const FIELDS: &'static [&'static str] = &[
+        "id",
+        "title",
+        "content",
+        "description",
+        "createdAt",
+        "readingTime",
+        "stats",
+        "comments",
+        "category",
+        "tags",
+        "subCategories",
+        "toc",
+    ];
 }
 
 struct RawBlogById {
@@ -43,32 +85,82 @@ struct RawBlogById {
     pub description: String,
     pub created_at: DateTime,
     pub category_id: Uuid,
+    pub toc: Option<Json<Vec<TocEntry>>>,
+    pub word_count: i32,
+    pub code_word_count: i32,
+    pub image_count: i32,
+    pub code_block_count: i32,
+    pub link_count: i32,
 }
 
 impl GetById {
-    pub async fn run(&self, id: Uuid) -> Result<Option<BlogById>, sqlx::Error> {
-        let blog = get_by_id(self.pool.get_ref(), id);
-        let comments = comment::by_blog(
-            self.pool.get_ref(),
-            id,
-            Slice {
-                limit: 20,
-                offset: 0,
-            },
-        );
-        let tags = self.get_tags.run(id);
-        let sub_categories = self.get_sub_categories.run(id);
+    pub async fn run(
+        &self,
+        id: Uuid,
+        include: Include,
+    ) -> Result<Option<Arc<Versioned<BlogById>>>, sqlx::Error> {
+        let key = (id, include);
+        if let Some(blog) = self.cache.blogs.get(&key) {
+            return Ok(Some(blog));
+        }
 
-        let (blog, comments, tags, sub_categories) = join!(blog, comments, tags, sub_categories);
+        let epoch = self.cache.blogs.epoch();
 
-        let Some(blog) = blog? else {
+        // Taking the version first, a change while loading leaves it older than the blog, so
+        // clients revalidate their copy instead of keeping it
+        let Some(version) = self.version(id).await? else {
             return Ok(None);
         };
 
-        let tags = tags?;
-        let sub_categories = sub_categories?;
+        let Some(value) = self.load(id, include).await? else {
+            return Ok(None);
+        };
 
-        let comments = comments.unwrap_or_else(|_| vec![]);
+        let blog = Arc::new(Versioned { version, value });
+        self.cache.blogs.insert(key, blog.clone(), epoch);
+
+        Ok(Some(blog))
+    }
+
+    /// Only the queries of what was included are run
+    async fn load(&self, id: Uuid, include: Include) -> Result<Option<BlogById>, sqlx::Error> {
+        let blog = get_by_id(self.pool.get_ref(), id, include.toc);
+        let comments = async {
+            if !include.comments {
+                return Ok(None);
+            }
+
+            let slice = Slice {
+                limit: self.embedded_comments.0 as i64,
+                offset: 0,
+            };
+
+            // Comments are secondary, a blog is still shown when they fail
+            let comments = comment::by_blog(self.pool.get_ref(), id, slice)
+                .await
+                .unwrap_or_else(|_| vec![]);
+
+            Ok(Some(comments))
+        };
+        let tags = async {
+            match include.tags {
+                true => self.get_tags.run(id).await.map(Some),
+                false => Ok(None),
+            }
+        };
+        let sub_categories = async {
+            match include.sub_categories {
+                true => self.get_sub_categories.run(id).await.map(Some),
+                false => Ok(None),
+            }
+        };
+
+        let (blog, comments, tags, sub_categories) =
+            try_join!(blog, comments, tags, sub_categories)?;
+
+        let Some(blog) = blog else {
+            return Ok(None);
+        };
 
         let Some(category) = self.get_category.run(blog.category_id).await? else {
             return Ok(None);
@@ -80,22 +172,102 @@ impl GetById {
             content: blog.content,
             description: blog.description,
             created_at: blog.created_at,
-            comments: comments.into_iter().map(Into::into).collect(),
+            reading_time: self
+                .reading_speed
+                .reading_time(blog.word_count, blog.code_word_count),
+            stats: BlogStats {
+                words: blog.word_count,
+                code_words: blog.code_word_count,
+                images: blog.image_count,
+                code_blocks: blog.code_block_count,
+                links: blog.link_count,
+            },
+            comments: comments.map(|comments| comments.into_iter().map(Into::into).collect()),
             category,
             tags,
             sub_categories,
+            toc: blog.toc.map(|toc| toc.0),
         };
 
         Ok(Some(blog))
     }
+
+    /// Version of the blog along with its category, tags and sub categories, comments touch the
+    /// activity of the blog
+    async fn version(&self, id: Uuid) -> Result<Option<Version>, sqlx::Error> {
+        query_as!(
+            Version,
+            r#"SELECT
+                GREATEST(
+                    b.activity_at,
+                    c.updated_at,
+                    (SELECT max(t.updated_at) FROM tags t
+                        JOIN tags_blogs tb ON tb.tag_id = t.id WHERE tb.blog_id = b.id),
+                    (SELECT max(sc.updated_at) FROM sub_categories sc
+                        JOIN sub_categories_blogs scb ON scb.sub_category_id = sc.id
+                        WHERE scb.blog_id = b.id)
+                ) as "updated_at!",
+                1::bigint as "rows!"
+            FROM blogs b
+            JOIN categories c ON c.id = b.category_id
+            WHERE b.id = $1"#,
+            id
+        )
+        .fetch_optional(self.pool.get_ref())
+        .await
+    }
 }
 
-async fn get_by_id(pool: impl Executor<'_>, id: Uuid) -> Result<Option<RawBlogById>, sqlx::Error> {
+async fn get_by_id(
+    pool: impl Executor<'_>,
+    id: Uuid,
+    with_toc: bool,
+) -> Result<Option<RawBlogById>, sqlx::Error> {
     query_as!(
         RawBlogById,
-        "SELECT id, title, html as content, description, category_id, created_at FROM blogs WHERE id = $1",
-        id
+        r#"SELECT
+            id, title, html as content, description, category_id, created_at,
+            CASE WHEN $2 THEN toc END as "toc: Json<Vec<TocEntry>>",
+            word_count, code_word_count, image_count, code_block_count, link_count
+        FROM blogs WHERE id = $1"#,
+        id,
+        with_toc
     )
     .fetch_optional(pool)
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_the_keys_of_everything_included() {
+        let blog = BlogById {
+            id: Uuid::nil(),
+            title: String::new(),
+            content: String::new(),
+            description: String::new(),
+            created_at: DateTime::default(),
+            reading_time: 0,
+            stats: BlogStats::default(),
+            comments: Some(vec![]),
+            category: category::Category {
+                id: Uuid::nil(),
+                name: String::new(),
+            },
+            tags: Some(vec![]),
+            sub_categories: Some(vec![]),
+            toc: Some(vec![]),
+        };
+
+        let serde_json::Value::Object(json) = serde_json::to_value(blog).unwrap() else {
+            panic!("blogs are objects");
+        };
+
+        assert_eq!(json.len(), BlogById::FIELDS.len());
+        assert!(json
+            .keys()
+            .all(|key| BlogById::FIELDS.contains(&key.as_str())));
+    }
+}
@@ -1,14 +1,208 @@
+use actix_web::web::Data;
 use uuid::Uuid;
 
 use crate::{
-    domain::blog::images::{Filename, ImagePathBuf, ImagePathFactory},
+    domain::blog::images::{
+        keys,
+        variants::{self, Format},
+        Filename,
+    },
+    persistence::{
+        blobs::{self, BlobStore},
+        db::Pool,
+    },
     server::service::sync_service,
 };
 
-sync_service!(GetImage; img_path_factory: ImagePathFactory);
+sync_service!(GetImage; pool: Data<Pool>, blobs: Data<dyn BlobStore>);
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("could not look up image: {0}")]
+    Storage(#[from] blobs::Error),
+}
 
 impl GetImage {
-    pub fn run(&self, id: Uuid, filename: &Filename) -> ImagePathBuf {
-        self.img_path_factory.create_path(id, filename)
+    /// Key of the image the blog names `filename`, see [`GetImage::object`]. Images uploaded
+    /// before uploads were stored by content are still stored by their filename
+    pub async fn run(
+        &self,
+        id: Uuid,
+        filename: &Filename,
+        width: Option<u32>,
+        accepted: &[Format],
+    ) -> Result<Option<String>, Error> {
+        let name: &str = filename.as_ref().as_ref();
+        let object = sqlx::query!(
+            r#"SELECT o.hash || '.' || o.extension as "object!", o.variants
+            FROM blog_images i
+            JOIN image_objects o ON o.hash = i.hash
+            WHERE i.blog_id = $1 AND i.filename = $2"#,
+            id,
+            name
+        )
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        if let Some(object) = object {
+            if let Ok(filename) = Filename::new(&object.object) {
+                return Ok(Some(
+                    self.stored(filename, object.variants, width, accepted)
+                        .await?,
+                ));
+            }
+        }
+
+        let original = keys::upload(id, filename);
+        if !self.blobs.exists(&original).await? {
+            return Ok(None);
+        }
+
+        let extension = variants::extension_of(filename);
+        if !variants::has_variants(extension) {
+            return Ok(Some(original));
+        }
+
+        let stored = self.list(&keys::upload_variants(id, filename)).await?;
+
+        Ok(Some(
+            pick_variant(&stored, extension, width, accepted)
+                .map(|(width, extension)| keys::upload_variant(id, filename, width, extension))
+                .unwrap_or(original),
+        ))
+    }
+
+    /// Key of the narrowest variant of the object at least `width` wide, the full size without
+    /// one. Each variant is looked up in the `accepted` formats first, then in the format of
+    /// the object. `None` when the object is not stored
+    pub async fn object(
+        &self,
+        object: &Filename,
+        width: Option<u32>,
+        accepted: &[Format],
+    ) -> Result<Option<String>, Error> {
+        let name: &str = object.as_ref().as_ref();
+        // Objects are named by their hash and extension
+        let Some((hash, extension)) = name.rsplit_once('.') else {
+            return Ok(None);
+        };
+
+        let variants = sqlx::query_scalar!(
+            "SELECT variants FROM image_objects WHERE hash = $1 AND extension = $2",
+            hash,
+            extension
+        )
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        match variants {
+            Some(variants) => Ok(Some(self.stored(object, variants, width, accepted).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Objects are stored along their row, which records their variants unless stored before.
+    /// Those are listed from the store
+    async fn stored(
+        &self,
+        object: &Filename,
+        variants: Option<Vec<String>>,
+        width: Option<u32>,
+        accepted: &[Format],
+    ) -> Result<String, Error> {
+        let extension = variants::extension_of(object);
+        if !variants::has_variants(extension) {
+            return Ok(keys::object(object));
+        }
+
+        let stored = match variants {
+            Some(variants) => variants,
+            None => self.list(&keys::object_variants(object)).await?,
+        };
+
+        Ok(pick_variant(&stored, extension, width, accepted)
+            .map(|(width, extension)| keys::object_variant(object, width, extension))
+            .unwrap_or_else(|| keys::object(object)))
+    }
+
+    /// Filenames of the blobs in the variants dir
+    async fn list(&self, dir: &str) -> Result<Vec<String>, Error> {
+        Ok(self
+            .blobs
+            .list(dir)
+            .await?
+            .into_iter()
+            .filter_map(|blob| {
+                blob.key
+                    .rsplit_once('/')
+                    .map(|(_, filename)| filename.to_owned())
+            })
+            .collect())
+    }
+}
+
+/// Width and extension of the narrowest variant at least `width` wide among the `stored`
+/// filenames, `None` for the original. Images uploaded before variants existed have none
+fn pick_variant<'a>(
+    stored: &[String],
+    extension: &'a str,
+    width: Option<u32>,
+    accepted: &'a [Format],
+) -> Option<(Option<u32>, &'a str)> {
+    let widths = variants::WIDTHS
+        .into_iter()
+        .filter(|&variant| width.is_some_and(|width| variant >= width))
+        .map(Some)
+        .chain([None]);
+
+    for width in widths {
+        let formats = accepted
+            .iter()
+            .map(|format| format.extension())
+            .chain([extension]);
+
+        for format in formats {
+            if width.is_none() && format == extension {
+                return None;
+            }
+
+            if stored.contains(&variants::variant_filename(width, format)) {
+                return Some((width, format));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_narrowest_stored_variant() {
+        let stored = ["480.png", "960.png", "960.webp", "full.webp"].map(str::to_owned);
+        let webp = [Format::Webp];
+
+        assert_eq!(
+            pick_variant(&stored, "png", Some(400), &webp),
+            Some((Some(480), "png"))
+        );
+        assert_eq!(
+            pick_variant(&stored, "png", Some(500), &webp),
+            Some((Some(960), "webp"))
+        );
+        assert_eq!(
+            pick_variant(&stored, "png", Some(500), &[]),
+            Some((Some(960), "png"))
+        );
+        assert_eq!(
+            pick_variant(&stored, "png", Some(1000), &webp),
+            Some((None, "webp"))
+        );
+        assert_eq!(pick_variant(&stored, "png", None, &[]), None);
+        assert_eq!(pick_variant(&[], "png", Some(400), &webp), None);
     }
 }
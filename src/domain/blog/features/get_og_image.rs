@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+
+use actix_web::web::{self, Data};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{images::ImagePathFactory, og_image::OgCard},
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+/// Directory inside the blog uploads where the generated images are cached
+const OG_DIR: &str = "og";
+
+sync_service!(GetOgImage; pool: Data<Pool>, img_path_factory: ImagePathFactory);
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("could not render image: {0}")]
+    Render(#[from] image::ImageError),
+    #[error("could not store image: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not generate image: {0}")]
+    Blocking(#[from] actix_web::error::BlockingError),
+}
+
+struct CardData {
+    title: String,
+    category: String,
+    colors: Vec<String>,
+}
+
+impl GetOgImage {
+    /// Path of the preview image of the blog, it is generated again whenever its title,
+    /// category or tags change
+    pub async fn run(&self, blog_id: Uuid) -> Result<Option<PathBuf>, Error> {
+        let data = sqlx::query_as!(
+            CardData,
+            r#"SELECT
+                b.title, c.name as category,
+                ARRAY(
+                    SELECT t.color FROM tags_blogs tb JOIN tags t ON t.id = tb.tag_id
+                    WHERE tb.blog_id = b.id ORDER BY t.name
+                ) as "colors!"
+            FROM blogs b
+            JOIN categories c ON c.id = b.category_id
+            WHERE b.id = $1"#,
+            blog_id
+        )
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        let Some(data) = data else {
+            return Ok(None);
+        };
+
+        let dir = self.img_path_factory.blog_dir(blog_id).join(OG_DIR);
+
+        // Rendering and the filesystem block, so they do not run on the async runtime
+        let path = web::block(move || {
+            let card = OgCard {
+                title: &data.title,
+                category: &data.category,
+                colors: &data.colors,
+            };
+
+            let path = dir.join(format!("{}.png", card.fingerprint()));
+            if path.exists() {
+                return Ok(path);
+            }
+
+            let png = card.render()?;
+            std::fs::create_dir_all(&dir)?;
+
+            // Written aside under a name of its own first, so concurrent requests never serve
+            // half written files nor remove each other's
+            let partial = dir.join(format!("{}.{}.partial", card.fingerprint(), Uuid::new_v4()));
+            std::fs::write(&partial, png)?;
+            if let Err(e) = std::fs::rename(&partial, &path) {
+                let _ = std::fs::remove_file(&partial);
+                return Err(e.into());
+            }
+
+            remove_previous(&dir, &path);
+
+            Ok::<_, Error>(path)
+        })
+        .await??;
+
+        Ok(Some(path))
+    }
+}
+
+/// Removes the images of previous titles, categories and tags from `dir`, they are not served
+/// anymore. Failing to only leaves them for the next change
+fn remove_previous(dir: &Path, current: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let is_png = path.extension().is_some_and(|extension| extension == "png");
+
+        if is_png && path != current {
+            if let Err(e) = std::fs::remove_file(&path) {
+                eprintln!("Could not remove previous preview {:?}: {}", path, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_previous_images() {
+        let dir = std::env::temp_dir().join(format!("og-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in ["old.png", "new.png", "new.1.partial"] {
+            std::fs::write(dir.join(file), file).unwrap();
+        }
+
+        remove_previous(&dir, &dir.join("new.png"));
+
+        let mut left: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(left, ["new.1.partial", "new.png"]);
+    }
+}
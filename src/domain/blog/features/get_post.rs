@@ -0,0 +1,48 @@
+use actix_web::web::Data;
+use sqlx::query_as;
+use uuid::Uuid;
+
+use crate::{
+    persistence::db::{DateTime, Pool},
+    server::service::sync_service,
+};
+
+sync_service!(GetPost; pool: Data<Pool>);
+
+/// Data shown on the public page of a blog
+pub struct Post {
+    pub id: Uuid,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub html: String,
+    pub main_image: Option<String>,
+    pub created_at: DateTime,
+    pub category_name: String,
+    pub tags: Vec<String>,
+}
+
+impl GetPost {
+    /// Finds the blog by its id, or by its slug when `key` is not an id
+    pub async fn run(&self, key: &str) -> Result<Option<Post>, sqlx::Error> {
+        let id = Uuid::parse_str(key).ok();
+
+        query_as!(
+            Post,
+            r#"SELECT
+                b.id, b.slug, b.title, b.description, b.html, b.main_image, b.created_at,
+                c.name as category_name,
+                ARRAY(
+                    SELECT t.name FROM tags_blogs tb JOIN tags t ON t.id = tb.tag_id
+                    WHERE tb.blog_id = b.id ORDER BY t.name
+                ) as "tags!"
+            FROM blogs b
+            JOIN categories c ON c.id = b.category_id
+            WHERE b.id = $1 OR ($1::uuid IS NULL AND b.slug = $2)"#,
+            id,
+            key
+        )
+        .fetch_optional(self.pool.as_ref())
+        .await
+    }
+}
@@ -0,0 +1,64 @@
+use actix_web::web::Data;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    persistence::db::{DateTime, Pool},
+    server::service::sync_service,
+};
+
+sync_service!(ListAttachments; pool: Data<Pool>);
+
+#[derive(Debug)]
+pub enum Error {
+    NotFound,
+    Database,
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BlogAttachment {
+    pub filename: String,
+    /// Bytes of the file
+    pub size: u64,
+    pub uploaded_at: DateTime,
+}
+
+impl ListAttachments {
+    pub async fn run(&self, blog_id: Uuid) -> Result<Vec<BlogAttachment>, Error> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM blogs WHERE id = $1) as "exists!""#,
+            blog_id
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        if !exists {
+            return Err(Error::NotFound);
+        }
+
+        let attachments = sqlx::query!(
+            r#"SELECT filename, size, uploaded_at FROM blog_attachments
+            WHERE blog_id = $1
+            ORDER BY filename"#,
+            blog_id
+        )
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(attachments
+            .into_iter()
+            .map(|attachment| BlogAttachment {
+                filename: attachment.filename,
+                size: attachment.size as u64,
+                uploaded_at: attachment.uploaded_at,
+            })
+            .collect())
+    }
+}
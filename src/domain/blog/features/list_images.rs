@@ -0,0 +1,120 @@
+use std::collections::{HashMap, HashSet};
+
+use actix_web::web::Data;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::images::{keys, Filename},
+    persistence::{
+        blobs::{self, BlobStore},
+        db::Pool,
+    },
+    server::service::sync_service,
+};
+
+sync_service!(ListImages; pool: Data<Pool>, blobs: Data<dyn BlobStore>);
+
+#[derive(Debug)]
+pub enum Error {
+    NotFound,
+    Database,
+    Storage,
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+impl From<blobs::Error> for Error {
+    fn from(_: blobs::Error) -> Self {
+        Self::Storage
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BlogImage {
+    pub filename: String,
+    /// Bytes of the image as stored
+    pub size: u64,
+    /// Unknown for images uploaded before dimensions were recorded
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Whether the current markdown shows it
+    pub referenced: bool,
+}
+
+impl ListImages {
+    /// Images the blog names, stored by content or uploaded before by filename. Variants are
+    /// not listed
+    pub async fn run(&self, blog_id: Uuid) -> Result<Vec<BlogImage>, Error> {
+        let referenced = sqlx::query_scalar!("SELECT images FROM blogs WHERE id = $1", blog_id)
+            .fetch_optional(self.pool.as_ref())
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        let aliases = sqlx::query!(
+            r#"SELECT i.filename, i.width, i.height, o.size as "size?"
+            FROM blog_images i
+            LEFT JOIN image_objects o ON o.hash = i.hash
+            WHERE i.blog_id = $1"#,
+            blog_id
+        )
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        let mut images: HashMap<String, BlogImage> = HashMap::new();
+        let mut legacy = HashSet::new();
+
+        for alias in aliases {
+            let image = BlogImage {
+                filename: alias.filename.clone(),
+                size: alias.size.unwrap_or_default() as u64,
+                width: Some(alias.width as u32),
+                height: Some(alias.height as u32),
+                referenced: referenced.contains(&alias.filename),
+            };
+
+            if alias.size.is_none() {
+                legacy.insert(alias.filename.clone());
+            }
+            images.insert(alias.filename, image);
+        }
+
+        for upload in self.blobs.list(&keys::uploads(blog_id)).await? {
+            let Some((_, filename)) = upload.key.rsplit_once('/') else {
+                continue;
+            };
+
+            if Filename::new(filename).is_err() {
+                continue;
+            }
+
+            match images.get_mut(filename) {
+                // Only uploads stored by filename are sized by their blob
+                Some(image) if legacy.contains(filename) => image.size = upload.size,
+                Some(_) => {}
+                None => {
+                    images.insert(
+                        filename.to_owned(),
+                        BlogImage {
+                            size: upload.size,
+                            width: None,
+                            height: None,
+                            referenced: referenced.iter().any(|image| image == filename),
+                            filename: filename.to_owned(),
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut images: Vec<_> = images.into_values().collect();
+        images.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+        Ok(images)
+    }
+}
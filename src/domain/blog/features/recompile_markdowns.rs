@@ -4,15 +4,76 @@ use markdown_parse::content::ContentBuf;
 use par_stream::ParStreamExt;
 use uuid::Uuid;
 
-use crate::{persistence::db::Pool, server::service::sync_service};
+use crate::{
+    domain::{
+        blog::{images::metadata, ImgHostInjectorFactory},
+        job::{JobKind, JobPayload, JobProgress},
+    },
+    persistence::db::Pool,
+    server::service::sync_service,
+};
 
-use super::set_content::SetContent;
+use super::{create_one::compile_content, set_content::SetContent};
 
-sync_service!(RecompileMarkdowns; pool: Data<Pool>, set_content: SetContent);
+sync_service!(RecompileMarkdowns;
+    pool: Data<Pool>,
+    set_content: SetContent,
+    injector_factory: ImgHostInjectorFactory
+);
+
+impl RecompileMarkdowns {
+    pub fn new(
+        pool: Data<Pool>,
+        set_content: SetContent,
+        injector_factory: ImgHostInjectorFactory,
+    ) -> Self {
+        Self {
+            pool,
+            set_content,
+            injector_factory,
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Payload {
+    pub dry_run: bool,
+}
+
+impl JobPayload for Payload {
+    const KIND: JobKind = JobKind::RecompileMarkdowns;
+}
+
+/// Max amount of blogs compiled at the same time
+const MAX_CONCURRENT_COMPILES: usize = 4;
 
 struct BlogContent {
     pub id: Uuid,
     pub content: String,
+    pub html: String,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub compiler_version: i32,
+    pub dry_run: bool,
+    /// Blogs whose html changed (or would change on a dry run)
+    pub changed: Vec<Uuid>,
+    pub failed: Vec<Uuid>,
+    /// Blogs that could not be read, reported as job item errors under [`FETCH_ERROR_KEY`]
+    pub fetch_failed: u32,
+}
+
+/// Key of the item errors of blogs that could not be read, so their id is unknown
+const FETCH_ERROR_KEY: &str = "fetch";
+
+enum Outcome {
+    Unchanged,
+    Changed(Uuid),
+    Failed(Uuid, String),
+    FetchFailed(String),
 }
 
 mod static_pool {
@@ -42,7 +103,7 @@ mod static_pool {
             self.0.as_ref().describe(sql)
         }
 
-        fn fetch_many<'e, 'q: 'e, E: 'q>(
+        fn fetch_many<'e, 'q: 'e, E>(
             self,
             query: E,
         ) -> futures_util::stream::BoxStream<
@@ -57,12 +118,12 @@ mod static_pool {
         >
         where
             'static: 'e,
-            E: sqlx::Execute<'q, Self::Database>,
+            E: 'q + sqlx::Execute<'q, Self::Database>,
         {
             self.0.as_ref().fetch_many(query)
         }
 
-        fn fetch_optional<'e, 'q: 'e, E: 'q>(
+        fn fetch_optional<'e, 'q: 'e, E>(
             self,
             query: E,
         ) -> futures_util::future::BoxFuture<
@@ -71,7 +132,7 @@ mod static_pool {
         >
         where
             'static: 'e,
-            E: sqlx::Execute<'q, Self::Database>,
+            E: 'q + sqlx::Execute<'q, Self::Database>,
         {
             self.0.as_ref().fetch_optional(query)
         }
@@ -93,33 +154,144 @@ mod static_pool {
 }
 
 impl RecompileMarkdowns {
-    pub async fn run(self) -> Result<(), sqlx::Error> {
-        let blogs = sqlx::query_as!(BlogContent, "SELECT id, content FROM blogs")
-            .fetch(static_pool::StaticPool::from(self.pool));
+    /// Recompiles every blog stamped with an older [`markdown_parse::COMPILER_VERSION`].
+    /// On a dry run nothing is written, the report only lists the blogs whose html would change.
+    pub async fn run(self, dry_run: bool, progress: JobProgress) -> Result<Report, sqlx::Error> {
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM blogs WHERE compiler_version < $1"#,
+            markdown_parse::COMPILER_VERSION
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        progress.set_total(total as i32).await?;
+
+        let blogs = sqlx::query_as!(
+            BlogContent,
+            "SELECT id, content, html FROM blogs WHERE compiler_version < $1",
+            markdown_parse::COMPILER_VERSION
+        )
+        .fetch(static_pool::StaticPool::from(self.pool.clone()));
 
-        blogs
-            .par_then(None, move |blog| {
+        let outcomes = blogs
+            .par_then(MAX_CONCURRENT_COMPILES, move |blog| {
+                let pool = self.pool.clone();
                 let set_content = self.set_content.clone();
+                let injector_factory = self.injector_factory.clone();
+                let progress = progress.clone();
 
                 async move {
-                    let Ok(BlogContent { id, content }) = blog else {
-                        return;
+                    let outcome = match blog {
+                        Ok(blog) => {
+                            recompile(blog, dry_run, &pool, set_content, &injector_factory).await
+                        }
+                        Err(e) => Outcome::FetchFailed(format!("{:?}", e)),
                     };
 
-                    // Is always valid because it is stored
-                    let content = ContentBuf::from_boxed_unchecked(content.into_boxed_str());
+                    let error = match &outcome {
+                        Outcome::Failed(id, message) => Some((id.to_string(), message)),
+                        Outcome::FetchFailed(message) => {
+                            Some((FETCH_ERROR_KEY.to_owned(), message))
+                        }
+                        _ => None,
+                    };
+
+                    if let Some((key, message)) = error {
+                        if let Err(e) = progress.item_error(&key, message).await {
+                            eprintln!("Could not report recompile error: {:?}", e);
+                        }
+                    }
 
-                    if let Err(e) = set_content
-                        .run(id, &content, /* Force to recompile preview */ None)
-                        .await
-                    {
-                        eprintln!("Got error while recompiling markdown: {:?}", e);
+                    if let Err(e) = progress.step().await {
+                        eprintln!("Could not report recompile progress: {:?}", e);
                     }
+
+                    outcome
                 }
             })
             .collect::<Vec<_>>()
             .await;
 
-        Ok(())
+        let mut report = Report {
+            compiler_version: markdown_parse::COMPILER_VERSION,
+            dry_run,
+            ..Default::default()
+        };
+
+        for outcome in outcomes {
+            match outcome {
+                Outcome::Unchanged => {}
+                Outcome::Changed(id) => report.changed.push(id),
+                Outcome::Failed(id, _) => report.failed.push(id),
+                Outcome::FetchFailed(_) => report.fetch_failed += 1,
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+async fn recompile(
+    BlogContent { id, content, html }: BlogContent,
+    dry_run: bool,
+    pool: &Pool,
+    set_content: SetContent,
+    injector_factory: &ImgHostInjectorFactory,
+) -> Outcome {
+    // Is always valid because it is stored
+    let content = ContentBuf::from_boxed_unchecked(content.into_boxed_str());
+
+    let images_metadata = match metadata::load(pool, id).await {
+        Ok(images_metadata) => images_metadata,
+        Err(e) => return Outcome::Failed(id, format!("{:?}", e)),
+    };
+
+    let compiled = match compile_content(&content, injector_factory.create(id, images_metadata)) {
+        Ok(compiled) => compiled,
+        Err(e) => return Outcome::Failed(id, e.to_string()),
+    };
+
+    let changed = compiled.html_content != html;
+    if dry_run {
+        return if changed {
+            Outcome::Changed(id)
+        } else {
+            Outcome::Unchanged
+        };
+    }
+
+    let result = if changed {
+        set_content
+            .save(
+                id, &content, compiled, /* Force to recompile preview */ None,
+            )
+            .await
+    } else {
+        // The html is the same, though what is derived from it may be new to this version
+        let stats = compiled.stats;
+        sqlx::query!(
+            r#"UPDATE blogs SET
+                compiler_version = $1, toc = $2, word_count = $3, code_word_count = $4,
+                image_count = $5, code_block_count = $6, link_count = $7
+            WHERE id = $8"#,
+            markdown_parse::COMPILER_VERSION,
+            sqlx::types::Json(&compiled.toc) as _,
+            stats.words,
+            stats.code_words,
+            stats.images,
+            stats.code_blocks,
+            stats.links,
+            id
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(Into::into)
+    };
+
+    match result {
+        Ok(_) if changed => Outcome::Changed(id),
+        Ok(_) => Outcome::Unchanged,
+        Err(e) => Outcome::Failed(id, format!("{:?}", e)),
     }
 }
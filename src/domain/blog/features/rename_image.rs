@@ -0,0 +1,308 @@
+use actix_web::web::Data;
+use markdown_parse::content::ContentBuf;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog::{
+            images::{keys, metadata, variants, Filename},
+            ImgHostInjectorFactory,
+        },
+        event::{self, DomainEvent},
+        read_cache::{Invalidation, ReadCache},
+    },
+    persistence::{
+        blobs::{self, BlobStore},
+        db::{self, Pool},
+    },
+    server::service::sync_service,
+};
+
+use super::create_one::{compile_content, BlogCompile};
+
+sync_service!(RenameImage;
+    pool: Data<Pool>,
+    blobs: Data<dyn BlobStore>,
+    injector_factory: ImgHostInjectorFactory,
+    cache: Data<ReadCache>
+);
+
+#[derive(Debug)]
+pub enum Error {
+    NotFound,
+    /// Another image already has the name
+    Conflict,
+    /// The extension tells the format of the image, so it is kept
+    ExtensionChanged,
+    Parse(markdown_parse::Error),
+    Database,
+    Storage,
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+impl From<blobs::Error> for Error {
+    fn from(_: blobs::Error) -> Self {
+        Self::Storage
+    }
+}
+
+impl From<markdown_parse::Error> for Error {
+    fn from(e: markdown_parse::Error) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl RenameImage {
+    /// Renames the image the blog names, with the variants of uploads stored by filename. The
+    /// markdown showing it is rewritten to the new name and compiled again. Nothing of another
+    /// image is replaced, even one named `to` meanwhile
+    pub async fn run(&self, blog_id: Uuid, from: &Filename, to: &Filename) -> Result<(), Error> {
+        if variants::extension_of(from) != variants::extension_of(to) {
+            return Err(Error::ExtensionChanged);
+        }
+
+        // Uploads stored by filename are copied, those stored by content only have their alias
+        let legacy = self.blobs.exists(&keys::upload(blog_id, from)).await?;
+        if !legacy && !self.has_alias(blog_id, from).await? {
+            return Err(Error::NotFound);
+        }
+        if self.has_alias(blog_id, to).await? {
+            return Err(Error::Conflict);
+        }
+
+        if !legacy {
+            return self.rename_references(blog_id, from, to).await;
+        }
+
+        let copies = self.copies(blog_id, from, to).await?;
+        let copied = copy_all(self.blobs.as_ref(), &copies).await?;
+
+        let result = self.rename_references(blog_id, from, to).await;
+
+        // Whichever name is left unused goes
+        let unused = match result {
+            Ok(()) => copies.iter().map(|(from, _)| from).collect::<Vec<_>>(),
+            Err(_) => copied.iter().collect(),
+        };
+        for key in unused {
+            if let Err(e) = self.blobs.delete(key).await {
+                eprintln!("Could not delete {} once renamed: {:?}", key, e);
+            }
+        }
+
+        result
+    }
+
+    /// Keys the upload and its variants are copied from and to
+    async fn copies(
+        &self,
+        blog_id: Uuid,
+        from: &Filename,
+        to: &Filename,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let mut copies = vec![(keys::upload(blog_id, from), keys::upload(blog_id, to))];
+
+        let to_variants = keys::upload_variants(blog_id, to);
+        for variant in self
+            .blobs
+            .list(&keys::upload_variants(blog_id, from))
+            .await?
+        {
+            if let Some((_, filename)) = variant.key.rsplit_once('/') {
+                let to = format!("{}/{}", to_variants, filename);
+                copies.push((variant.key, to));
+            }
+        }
+
+        Ok(copies)
+    }
+
+    async fn has_alias(&self, blog_id: Uuid, filename: &Filename) -> Result<bool, Error> {
+        let filename: &str = filename.as_ref().as_ref();
+
+        let alias = sqlx::query_scalar!(
+            r#"SELECT 1 as "exists!" FROM blog_images WHERE blog_id = $1 AND filename = $2"#,
+            blog_id,
+            filename
+        )
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(alias.is_some())
+    }
+
+    /// The blog is locked until committed, so content set meanwhile is not overwritten, and
+    /// the alias named `to` meanwhile is told by its key
+    async fn rename_references(
+        &self,
+        blog_id: Uuid,
+        from: &Filename,
+        to: &Filename,
+    ) -> Result<(), Error> {
+        let from: &str = from.as_ref().as_ref();
+        let to: &str = to.as_ref().as_ref();
+
+        let mut tx = self.pool.begin().await?;
+
+        let content = sqlx::query_scalar!(
+            "SELECT content FROM blogs WHERE id = $1 FOR UPDATE",
+            blog_id
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+        sqlx::query!(
+            "UPDATE blog_images SET filename = $1 WHERE blog_id = $2 AND filename = $3",
+            to,
+            blog_id,
+            from
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| {
+            if db::is_unique_violation(&e) {
+                Error::Conflict
+            } else {
+                e.into()
+            }
+        })?;
+
+        let Some(renamed) = markdown_parse::rename_image(&content, from, to) else {
+            return Ok(tx.commit().await?);
+        };
+
+        // Only image urls changed, so the content is still valid
+        let renamed = ContentBuf::from_boxed_unchecked(renamed.into_boxed_str());
+        let images_metadata = metadata::load(&mut tx, blog_id).await?;
+
+        let BlogCompile {
+            html_content,
+            images,
+            main_image,
+            ..
+        } = compile_content(
+            &renamed,
+            self.injector_factory.create(blog_id, images_metadata),
+        )?;
+
+        sqlx::query!(
+            r#"UPDATE blogs SET content = $1, html = $2, images = $3, main_image = $4
+            WHERE id = $5"#,
+            renamed.as_ref(),
+            html_content,
+            images.as_slice(),
+            main_image,
+            blog_id
+        )
+        .execute(&mut tx)
+        .await?;
+
+        event::record(&mut tx, &DomainEvent::BlogContentSet { blog_id }).await?;
+
+        self.cache
+            .commit(tx, Invalidation::BlogChanged { blog_id })
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Copies every blob unless its target is taken, returning the targets copied to. Nothing is
+/// left copied when one fails
+async fn copy_all(
+    blobs: &dyn BlobStore,
+    copies: &[(String, String)],
+) -> Result<Vec<String>, Error> {
+    let mut copied = vec![];
+
+    for (from, to) in copies {
+        match copy(blobs, from, to).await {
+            Ok(()) => copied.push(to.clone()),
+            Err(e) => {
+                for key in &copied {
+                    if let Err(e) = blobs.delete(key).await {
+                        eprintln!("Could not delete the copy {}: {:?}", key, e);
+                    }
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Never replaces `to`, stores do not move blobs
+async fn copy(blobs: &dyn BlobStore, from: &str, to: &str) -> Result<(), Error> {
+    let content = blobs.get(from).await?.ok_or(Error::NotFound)?;
+    let extension = to.rsplit_once('.').map_or("", |(_, extension)| extension);
+    let content_type = actix_files::file_extension_to_mime(extension);
+
+    match blobs
+        .put_if_absent(to, content.to_vec(), content_type.as_ref())
+        .await?
+    {
+        true => Ok(()),
+        false => Err(Error::Conflict),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persistence::blobs::FsStore;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn never_replaces_the_target() {
+        let dir = std::env::temp_dir().join(format!("rename-{}", Uuid::new_v4()));
+        let blobs = FsStore::new(&dir);
+        for key in ["a.png", "b.png", "variants/a.png/480.png"] {
+            blobs.put(key, key.into(), "image/png").await.unwrap();
+        }
+
+        let copies = |to: &str| {
+            vec![
+                ("a.png".to_owned(), format!("{}.png", to)),
+                (
+                    "variants/a.png/480.png".to_owned(),
+                    format!("variants/{}.png/480.png", to),
+                ),
+            ]
+        };
+
+        assert!(matches!(
+            copy_all(&blobs, &copies("b")).await,
+            Err(Error::Conflict)
+        ));
+        assert_eq!(
+            blobs.get("b.png").await.unwrap().unwrap().as_ref(),
+            b"b.png"
+        );
+
+        blobs
+            .put("variants/c.png/480.png", vec![], "image/png")
+            .await
+            .unwrap();
+        assert!(matches!(
+            copy_all(&blobs, &copies("c")).await,
+            Err(Error::Conflict)
+        ));
+        assert!(!blobs.exists("c.png").await.unwrap());
+
+        let copied = copy_all(&blobs, &copies("d")).await.unwrap();
+        assert_eq!(copied, ["d.png", "variants/d.png/480.png"]);
+        assert_eq!(
+            blobs.get("d.png").await.unwrap().unwrap().as_ref(),
+            b"a.png"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
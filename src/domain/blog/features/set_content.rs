@@ -1,21 +1,46 @@
 use actix_web::web::Data;
 use markdown_parse::{content::ContentBuf, preview::PreviewBuf};
-use sqlx::query;
+use sqlx::{query, types::Json};
 use uuid::Uuid;
 
 use crate::{
-    domain::blog::ImgHostInjectorFactory, persistence::db::Pool, server::service::sync_service,
+    domain::{
+        blog::{images::metadata, ImgHostInjectorFactory},
+        event::{self, DomainEvent},
+        read_cache::{Invalidation, ReadCache},
+    },
+    persistence::db::Pool,
+    server::service::sync_service,
 };
 
 use super::create_one::{compile_content, BlogCompile};
 
-sync_service!(SetContent; pool: Data<Pool>, injector_factory: ImgHostInjectorFactory);
+sync_service!(SetContent;
+    pool: Data<Pool>,
+    injector_factory: ImgHostInjectorFactory,
+    cache: Data<ReadCache>
+);
+
+impl SetContent {
+    pub fn new(
+        pool: Data<Pool>,
+        injector_factory: ImgHostInjectorFactory,
+        cache: Data<ReadCache>,
+    ) -> Self {
+        Self {
+            pool,
+            injector_factory,
+            cache,
+        }
+    }
+}
 
 impl Clone for SetContent {
     fn clone(&self) -> Self {
         Self {
             pool: self.pool.clone(),
             injector_factory: self.injector_factory.clone(),
+            cache: self.cache.clone(),
         }
     }
 }
@@ -46,13 +71,30 @@ impl SetContent {
         content: &ContentBuf,
         preview: Option<&PreviewBuf>,
     ) -> Result<(), Error> {
-        let injector = self.injector_factory.create(blog_id);
+        let images_metadata = metadata::load(self.pool.as_ref(), blog_id).await?;
+        let injector = self.injector_factory.create(blog_id, images_metadata);
+        let compiled = compile_content(content, injector)?;
+
+        self.save(blog_id, content, compiled, preview).await
+    }
+
+    /// Sets the content as already compiled, for callers that compiled it to tell whether
+    /// anything changed
+    pub async fn save(
+        self,
+        blog_id: Uuid,
+        content: &ContentBuf,
+        compiled: BlogCompile,
+        preview: Option<&PreviewBuf>,
+    ) -> Result<(), Error> {
         let BlogCompile {
             title,
             html_content,
             images,
             main_image,
-        } = compile_content(content, injector)?;
+            toc,
+            stats,
+        } = compiled;
 
         let markdown_parse::PreviewParse {
             preview,
@@ -68,8 +110,15 @@ impl SetContent {
             }
         };
 
+        let mut tx = self.pool.begin().await?;
+
         let _ = query!(
-            "UPDATE blogs SET title = $1, content = $2, html = $3, preview = $4, description = $5, main_image = $6, images = $7 WHERE id = $8",
+            r#"UPDATE blogs SET
+                title = $1, content = $2, html = $3, preview = $4, description = $5,
+                main_image = $6, images = $7, compiler_version = $8, toc = $9,
+                word_count = $10, code_word_count = $11, image_count = $12,
+                code_block_count = $13, link_count = $14
+            WHERE id = $15"#,
             title,
             content.as_ref(),
             html_content,
@@ -77,11 +126,24 @@ impl SetContent {
             description,
             main_image,
             images.as_slice(),
+            markdown_parse::COMPILER_VERSION,
+            Json(&toc) as _,
+            stats.words,
+            stats.code_words,
+            stats.images,
+            stats.code_blocks,
+            stats.links,
             blog_id
         )
-        .execute(self.pool.as_ref())
+        .execute(&mut tx)
         .await?;
 
+        event::record(&mut tx, &DomainEvent::BlogContentSet { blog_id }).await?;
+
+        self.cache
+            .commit(tx, Invalidation::BlogMoved { blog_id })
+            .await?;
+
         Ok(())
     }
 }
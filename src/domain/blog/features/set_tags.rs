@@ -3,12 +3,15 @@ use sqlx::query;
 use uuid::Uuid;
 
 use crate::{
-    domain::blog_grouping,
+    domain::{
+        blog_grouping,
+        read_cache::{Invalidation, ReadCache},
+    },
     persistence::db::{Pool, Transaction},
     server::service::sync_service,
 };
 
-sync_service!(SetTags; pool: Data<Pool>);
+sync_service!(SetTags; pool: Data<Pool>, cache: Data<ReadCache>);
 
 pub async fn set_tags(
     tx: &mut Transaction<'_>,
@@ -41,7 +44,9 @@ impl SetTags {
         let mut tx = self.pool.begin().await?;
         set_tags(&mut tx, blog_id, tags).await?;
 
-        tx.commit().await?;
+        self.cache
+            .commit(tx, Invalidation::BlogChanged { blog_id })
+            .await?;
 
         Ok(())
     }
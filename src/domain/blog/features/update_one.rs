@@ -2,13 +2,18 @@ use actix_web::web::Data;
 use markdown_parse::{
     content::ContentBuf, preview::PreviewBuf, BlogParse, CowStr, ImageUrlInjector,
 };
-use sqlx::query;
+use sqlx::{query, types::Json};
 use uuid::Uuid;
 
 use crate::{
     domain::{
-        blog::{value_objects::sub_categories::SubCategories, ImgHostInjectorFactory},
+        blog::{
+            images::metadata, stats::BlogStats, toc::TocEntry,
+            value_objects::sub_categories::SubCategories, ImgHostInjectorFactory,
+        },
         blog_grouping,
+        event::{self, DomainEvent},
+        read_cache::{Invalidation, ReadCache},
     },
     persistence::db::Pool,
     server::service::sync_service,
@@ -16,7 +21,11 @@ use crate::{
 
 use super::set_tags;
 
-sync_service!(UpdateOne; pool: Data<Pool>, injector_factory: ImgHostInjectorFactory);
+sync_service!(UpdateOne;
+    pool: Data<Pool>,
+    injector_factory: ImgHostInjectorFactory,
+    cache: Data<ReadCache>
+);
 
 pub enum Error {
     Parse(markdown_parse::Error),
@@ -47,12 +56,15 @@ impl UpdateOne {
         tags: Vec<Uuid>,
         sub_categories: SubCategories,
     ) -> Result<(), Error> {
-        let injector = self.injector_factory.create(id);
+        let images_metadata = metadata::load(self.pool.as_ref(), id).await?;
+        let injector = self.injector_factory.create(id, images_metadata);
 
         let BlogParse {
             title,
             content: html_content,
             images, // TODO
+            toc,
+            stats,
         } = markdown_parse::parse(content.as_ref(), &injector)?;
 
         let markdown_parse::PreviewParse {
@@ -69,6 +81,7 @@ impl UpdateOne {
             }
         };
 
+        let stats = BlogStats::from(stats);
         let images = images.into_inner();
         let main_image = images.first().map(|image| {
             let mut cow = CowStr::Borrowed(image);
@@ -89,8 +102,15 @@ impl UpdateOne {
                     preview = $5,
                     description = $6,
                     main_image = $7,
-                    images = $8
-                WHERE id = $9"#,
+                    images = $8,
+                    compiler_version = $9,
+                    toc = $10,
+                    word_count = $11,
+                    code_word_count = $12,
+                    image_count = $13,
+                    code_block_count = $14,
+                    link_count = $15
+                WHERE id = $16"#,
             title,
             content.as_ref(),
             &html_content,
@@ -99,6 +119,13 @@ impl UpdateOne {
             description.as_str(),
             main_image,
             &images,
+            markdown_parse::COMPILER_VERSION,
+            Json(toc.into_iter().map(TocEntry::from).collect::<Vec<_>>()) as _,
+            stats.words,
+            stats.code_words,
+            stats.images,
+            stats.code_blocks,
+            stats.links,
             id,
         )
         .execute(&mut tx)
@@ -116,7 +143,11 @@ impl UpdateOne {
 
         set_tags::set_tags(&mut tx, id, tags).await?;
 
-        tx.commit().await?;
+        event::record(&mut tx, &DomainEvent::BlogUpdated { blog_id: id }).await?;
+
+        self.cache
+            .commit(tx, Invalidation::BlogMoved { blog_id: id })
+            .await?;
 
         Ok(())
     }
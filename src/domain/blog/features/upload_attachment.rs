@@ -0,0 +1,95 @@
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::attachments::{self, Filename},
+    persistence::{
+        blobs::{self, BlobStore, LocalBlobStream},
+        db::{self, Pool},
+    },
+    server::service::sync_service,
+};
+
+sync_service!(UploadAttachment; pool: Data<Pool>, blobs: Data<dyn BlobStore>);
+
+#[derive(Debug)]
+pub enum Error {
+    NotFound,
+    /// The content ended in an error of its own, nothing was stored
+    Interrupted,
+    Save,
+    Database,
+}
+
+impl From<blobs::Error> for Error {
+    fn from(e: blobs::Error) -> Self {
+        match e {
+            blobs::Error::Interrupted => Self::Interrupted,
+            e => {
+                eprintln!("Could not store attachment: {}", e);
+                Self::Save
+            }
+        }
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(_: sqlx::Error) -> Self {
+        Self::Database
+    }
+}
+
+impl UploadAttachment {
+    /// Stores the file as it is uploaded, replacing the attachment of the blog with the same
+    /// filename once it is received whole
+    pub async fn run(
+        &self,
+        blog_id: Uuid,
+        filename: &Filename,
+        content: LocalBlobStream<'_>,
+    ) -> Result<(), Error> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM blogs WHERE id = $1) as "exists!""#,
+            blog_id
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        if !exists {
+            return Err(Error::NotFound);
+        }
+
+        let key = attachments::key(blog_id, filename);
+        let content_type = actix_files::file_extension_to_mime(filename.extension());
+
+        let size = self
+            .blobs
+            .put_stream(&key, content, content_type.as_ref())
+            .await? as i64;
+
+        let name: &str = filename.as_ref();
+        let result = sqlx::query!(
+            r#"INSERT INTO blog_attachments(blog_id, filename, size)
+            VALUES($1, $2, $3)
+            ON CONFLICT (blog_id, filename) DO UPDATE SET
+                size = EXCLUDED.size, uploaded_at = now()"#,
+            blog_id,
+            name,
+            size
+        )
+        .execute(self.pool.as_ref())
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            // Deleted meanwhile, after its files were
+            Err(e) if db::is_foreign_key_violation(&e) => {
+                if let Err(e) = self.blobs.delete(&key).await {
+                    eprintln!("Could not delete attachment of deleted blog: {}", e);
+                }
+                Err(Error::NotFound)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
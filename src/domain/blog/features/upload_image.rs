@@ -1,35 +1,475 @@
-use image::imageops::FilterType;
+use std::io::Cursor;
 
-use crate::{domain::blog::images::ImagePathBuf, server::service::sync_service};
+use actix_web::web::{self, Data};
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageOutputFormat};
+use markdown_parse::{content::ContentBuf, ImageMetadata};
+use uuid::Uuid;
 
-sync_service!(UploadImage;);
+use crate::{
+    domain::{
+        blog::{
+            images::{
+                avif, exif, keys, metadata,
+                objects::{self, Object},
+                svg,
+                variants::{self, Format},
+                verbatim, webp, Filename,
+            },
+            ImgHostInjectorFactory,
+        },
+        read_cache::{Invalidation, ReadCache},
+    },
+    persistence::{
+        blobs::{self, BlobStore},
+        db::{self, Pool},
+    },
+    server::service::sync_service,
+};
 
+use super::create_one::compile_content;
+
+sync_service!(UploadImage;
+    blobs: Data<dyn BlobStore>,
+    pool: Data<Pool>,
+    injector_factory: ImgHostInjectorFactory,
+    cache: Data<ReadCache>
+);
+
+#[derive(Debug)]
 pub enum Error {
     Decode,
     Save,
+    NotFound,
+    Database,
+}
+
+impl From<std::io::Error> for Error {
+    fn from(_: std::io::Error) -> Self {
+        Self::Save
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(_: image::ImageError) -> Self {
+        Self::Save
+    }
+}
+
+impl From<blobs::Error> for Error {
+    fn from(e: blobs::Error) -> Self {
+        eprintln!("Could not store image: {}", e);
+        Self::Save
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(e: sqlx::Error) -> Self {
+        // Only the reference to the blog can be violated
+        if db::is_foreign_key_violation(&e) {
+            Self::NotFound
+        } else {
+            Self::Database
+        }
+    }
 }
 
-const IMAGE_HEIGHT: u32 = 9 * 50;
-const IMAGE_WIDTH: u32 = 16 * 50;
+/// Larger images are shrunk to fit, keeping their aspect ratio
+const MAX_IMAGE_SIZE: u32 = 2560;
+
+/// Bytes uploads may take, counted as they are received
+#[derive(Debug, Clone, Copy)]
+pub struct UploadLimits {
+    pub file: usize,
+    /// Of every file sent at once
+    pub request: usize,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        Self {
+            file: 10 * 1024 * 1024,
+            request: 50 * 1024 * 1024,
+        }
+    }
+}
 
 impl UploadImage {
-    pub fn run(&self, image_path: &ImagePathBuf, content: &[u8]) -> Result<(), Error> {
-        if image_path.create_ancestors().is_err() {
-            return Err(Error::Save);
+    /// Stores the image by the hash of its content, unless an identical upload already did,
+    /// and names it `filename` in the blog, recompiling the blog when it already shows it
+    pub async fn run(
+        &self,
+        blog_id: Uuid,
+        filename: &Filename,
+        content: Vec<u8>,
+    ) -> Result<(), Error> {
+        // Nothing is stored for a blog that does not exist, the reference to it still tells
+        // whether it was removed meanwhile
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM blogs WHERE id = $1) as "exists!""#,
+            blog_id
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        if !exists {
+            return Err(Error::NotFound);
         }
 
-        let Ok(mut image) = image::load_from_memory(content) else {
-            return Err(Error::Decode);
-        };
+        let object = self.store(filename, content).await?;
+
+        let mut tx = self.pool.begin().await?;
+        objects::insert(&mut tx, &object).await?;
+        metadata::save(&mut tx, blog_id, filename, &object).await?;
+        tx.commit().await?;
+
+        self.recompile(blog_id, filename).await
+    }
 
-        if image.width() > IMAGE_WIDTH || image.height() > IMAGE_HEIGHT {
-            image = image.resize(IMAGE_WIDTH, IMAGE_HEIGHT, FilterType::Triangle);
+    /// Stores the image by the hash of its content, unless an identical upload already did,
+    /// without naming it in any blog. Objects no blog names are removed by the orphaned image
+    /// collection once their grace passed, so one found here is marked as just stored, and it
+    /// is inserted again along with the alias naming it
+    pub async fn store(&self, filename: &Filename, content: Vec<u8>) -> Result<Object, Error> {
+        let hash = objects::hash(&content);
+
+        if let Some(object) = objects::find(self.pool.as_ref(), &hash).await? {
+            // Under the lock the collection either moved its blobs already or leaves them
+            let mut tx = self.pool.begin().await?;
+            objects::lock(&mut tx, &hash).await?;
+
+            if objects::touch(&mut tx, &hash).await? && self.is_stored(&object).await? {
+                tx.commit().await?;
+                return Ok(object);
+            }
         }
 
-        if image.save(image_path).is_err() {
-            return Err(Error::Save);
+        let extension = variants::extension_of(filename).to_owned();
+
+        // Encoding is slow, so it does not run on the async runtime
+        let (object, blobs) = web::block(move || encode(hash, extension, &content))
+            .await
+            .map_err(|_| Error::Save)??;
+
+        // Blobs are stored under the lock with their row, so the collection never moves them as
+        // orphans of a row it just pruned. The object goes last, so once it exists so do its
+        // variants
+        let mut tx = self.pool.begin().await?;
+        objects::lock(&mut tx, &object.hash).await?;
+
+        for blob in blobs {
+            self.blobs
+                .put(&blob.key, blob.content, blob.content_type.as_ref())
+                .await?;
         }
 
+        objects::insert(&mut tx, &object).await?;
+        tx.commit().await?;
+
+        Ok(object)
+    }
+
+    async fn is_stored(&self, object: &Object) -> Result<bool, Error> {
+        let filename = object.filename();
+        let Ok(filename) = Filename::new(&filename) else {
+            return Ok(false);
+        };
+
+        Ok(self.blobs.exists(&keys::object(filename)).await?)
+    }
+
+    /// Points the blog to the object now named `filename`, if it shows it
+    async fn recompile(&self, blog_id: Uuid, filename: &Filename) -> Result<(), Error> {
+        let filename: &str = filename.as_ref().as_ref();
+        let content = sqlx::query_scalar!(
+            "SELECT content FROM blogs WHERE id = $1 AND $2 = ANY(images)",
+            blog_id,
+            filename
+        )
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        let Some(content) = content else {
+            return Ok(());
+        };
+
+        // Is always valid because it is stored
+        let content = ContentBuf::from_boxed_unchecked(content.into_boxed_str());
+        let stored_images = metadata::load(self.pool.as_ref(), blog_id).await?;
+
+        let Ok(compiled) = compile_content(
+            &content,
+            self.injector_factory.create(blog_id, stored_images),
+        ) else {
+            return Ok(());
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        // Unless the content was set meanwhile, which compiled it with the new image
+        sqlx::query!(
+            "UPDATE blogs SET html = $1, main_image = $2 WHERE id = $3 AND content = $4",
+            compiled.html_content,
+            compiled.main_image,
+            blog_id,
+            content.as_ref()
+        )
+        .execute(&mut tx)
+        .await?;
+
+        self.cache
+            .commit(tx, Invalidation::BlogChanged { blog_id })
+            .await?;
+
         Ok(())
     }
 }
+
+struct Blob {
+    key: String,
+    content: Vec<u8>,
+    content_type: mime::Mime,
+}
+
+/// Encodes the object along with its narrower variants, and the alternate formats of each that
+/// turn out smaller. The object is the last blob
+fn encode(hash: String, extension: String, content: &[u8]) -> Result<(Object, Vec<Blob>), Error> {
+    let filename = objects::filename(&hash, &extension);
+    // Made of a hash and a valid extension
+    let filename = Filename::new(&filename).map_err(|_| Error::Save)?;
+
+    if !variants::has_variants(&extension) {
+        return store_as_uploaded(filename, hash, extension, content);
+    }
+
+    let format = ImageFormat::from_extension(&extension).ok_or(Error::Save)?;
+
+    let Ok(image) = image::load_from_memory(content) else {
+        return Err(Error::Decode);
+    };
+    let mut image = exif::orient(content, image);
+
+    if image.width() > MAX_IMAGE_SIZE || image.height() > MAX_IMAGE_SIZE {
+        image = image.resize(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE, FilterType::Triangle);
+    }
+
+    let alternates = Format::alternates_of(&extension);
+    let lossless = format == ImageFormat::Png;
+    let content_type = actix_files::file_extension_to_mime(&extension);
+    let mut blobs = vec![];
+
+    for width in variants::WIDTHS {
+        if width >= image.width() {
+            break;
+        }
+
+        let variant = image.resize(width, u32::MAX, FilterType::Triangle);
+        let encoded = encode_as(&variant, format)?;
+
+        blobs.extend(encode_alternates(
+            &variant,
+            filename,
+            Some(width),
+            alternates,
+            lossless,
+            encoded.len(),
+        ));
+        blobs.push(Blob {
+            key: keys::object_variant(filename, Some(width), &extension),
+            content: encoded,
+            content_type: content_type.clone(),
+        });
+    }
+
+    let encoded = encode_as(&image, format)?;
+    blobs.extend(encode_alternates(
+        &image,
+        filename,
+        None,
+        alternates,
+        lossless,
+        encoded.len(),
+    ));
+
+    let variants_dir = format!("{}/", keys::object_variants(filename));
+    let object = Object {
+        size: encoded.len() as i64,
+        metadata: metadata::measure(&image)?,
+        hash,
+        extension,
+        variants: Some(
+            blobs
+                .iter()
+                .filter_map(|blob| blob.key.strip_prefix(&variants_dir))
+                .map(str::to_owned)
+                .collect(),
+        ),
+    };
+
+    blobs.push(Blob {
+        key: keys::object(filename),
+        content: encoded,
+        content_type,
+    });
+
+    Ok((object, blobs))
+}
+
+/// Formats without variants are not encoded, only stripped of what they should not carry: the
+/// metadata of gifs and webps, and anything of svgs that is not drawing. The size of animations
+/// is that of their first frame, and svgs get no placeholder
+fn store_as_uploaded(
+    filename: &Filename,
+    hash: String,
+    extension: String,
+    content: &[u8],
+) -> Result<(Object, Vec<Blob>), Error> {
+    let (stored, metadata) = match extension.as_str() {
+        "svg" => {
+            let svg = svg::sanitize(content).ok_or(Error::Decode)?;
+            let metadata = ImageMetadata {
+                width: svg.width,
+                height: svg.height,
+                placeholder: None,
+            };
+
+            (svg.content.into_bytes(), metadata)
+        }
+        _ => {
+            let stripped = match extension.as_str() {
+                "gif" => verbatim::strip_gif(content),
+                _ => verbatim::strip_webp(content),
+            }
+            .ok_or(Error::Decode)?;
+
+            let Ok(image) = image::load_from_memory(&stripped) else {
+                return Err(Error::Decode);
+            };
+
+            (stripped, metadata::measure(&image)?)
+        }
+    };
+
+    let object = Object {
+        size: stored.len() as i64,
+        metadata,
+        hash,
+        extension: extension.clone(),
+        variants: Some(vec![]),
+    };
+
+    let blob = Blob {
+        key: keys::object(filename),
+        content: stored,
+        content_type: actix_files::file_extension_to_mime(&extension),
+    };
+
+    Ok((object, vec![blob]))
+}
+
+fn encode_as(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, Error> {
+    let mut encoded = Cursor::new(vec![]);
+    image.write_to(&mut encoded, ImageOutputFormat::from(format))?;
+
+    Ok(encoded.into_inner())
+}
+
+/// Alternate formats of a variant smaller than `encoded_len`, the variant as stored. They are
+/// lossy unless the variant is `lossless`
+fn encode_alternates(
+    image: &DynamicImage,
+    object: &Filename,
+    width: Option<u32>,
+    alternates: &[Format],
+    lossless: bool,
+    encoded_len: usize,
+) -> Vec<Blob> {
+    alternates
+        .iter()
+        .filter_map(|&format| {
+            let content = match format {
+                // Never listed for lossless images
+                Format::Avif if lossless => None,
+                Format::Avif => avif::encode_lossy(&image.to_rgb8()),
+                Format::Webp if lossless => webp::encode_lossless(&image.to_rgba8()),
+                Format::Webp => webp::encode_lossy(&image.to_rgb8()),
+            }?;
+
+            (content.len() < encoded_len).then(|| Blob {
+                key: keys::object_variant(object, width, format.extension()),
+                content,
+                content_type: format.mime(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_photos_and_strips_their_exif() {
+        let content = exif::jpeg_with_orientation(4, 2, 6);
+        assert!(content.windows(4).any(|bytes| bytes == b"Exif"));
+
+        let (object, blobs) = encode("d0e1".to_owned(), "jpg".to_owned(), &content).unwrap();
+        let stored = blobs.last().unwrap();
+
+        assert_eq!(stored.key, "objects/d0e1.jpg");
+        assert_eq!((object.metadata.width, object.metadata.height), (2, 4));
+        assert!(!stored.content.windows(4).any(|bytes| bytes == b"Exif"));
+    }
+
+    #[test]
+    fn stores_photos_in_avif_and_webp_too() {
+        // Just wider than a variant, AV1 encoding is slow
+        let photo = DynamicImage::ImageRgb8(image::RgbImage::from_fn(500, 60, |x, y| {
+            image::Rgb([(x / 3) as u8, (y / 2) as u8, ((x + y) / 5) as u8])
+        }));
+        let content = encode_as(&photo, ImageFormat::Jpeg).unwrap();
+
+        let (object, blobs) = encode("d0e1".to_owned(), "jpg".to_owned(), &content).unwrap();
+
+        assert!(blobs
+            .iter()
+            .any(|blob| blob.key == "objects/variants/d0e1.jpg/480.avif"
+                && blob.content_type.essence_str() == "image/avif"));
+        assert!(blobs
+            .iter()
+            .any(|blob| blob.key == "objects/variants/d0e1.jpg/480.webp"));
+        assert!(blobs
+            .iter()
+            .any(|blob| blob.key == "objects/variants/d0e1.jpg/full.webp"));
+
+        let mut variants = object.variants.unwrap();
+        variants.sort();
+        assert_eq!(
+            variants,
+            ["480.avif", "480.jpg", "480.webp", "full.avif", "full.webp"]
+        );
+    }
+
+    #[test]
+    fn stores_svgs_sanitized_without_variants() {
+        let content = br#"<svg xmlns="http://www.w3.org/2000/svg" width="3000" height="20"><script>alert(1)</script><rect width="3000" height="20"/></svg>"#;
+
+        let (object, blobs) = encode("d0e1".to_owned(), "svg".to_owned(), content).unwrap();
+
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(blobs[0].key, "objects/d0e1.svg");
+        assert_eq!(blobs[0].content_type.essence_str(), "image/svg+xml");
+        assert!(!String::from_utf8_lossy(&blobs[0].content).contains("script"));
+        assert_eq!((object.metadata.width, object.metadata.height), (3000, 20));
+        assert!(object.metadata.placeholder.is_none());
+    }
+
+    #[test]
+    fn refuses_content_of_another_format() {
+        let content = exif::jpeg_with_orientation(4, 2, 1);
+
+        assert!(matches!(
+            encode("d0e1".to_owned(), "gif".to_owned(), &content),
+            Err(Error::Decode)
+        ));
+    }
+}
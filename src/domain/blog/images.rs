@@ -1,45 +1,39 @@
+pub mod avif;
 mod create_path;
+pub mod exif;
+pub mod keys;
+pub mod legacy;
+pub mod metadata;
+pub mod objects;
+pub mod svg;
+pub mod variants;
+pub mod verbatim;
+pub mod webp;
 
 use crate::{persistence::public::PublicDir, server::service::sync_service};
 use actix_web::web::Data;
 
 pub use filename::Filename;
-pub use path::ImagePathBuf;
 
-const BLOG_IMAGES_DIR: &str = "blogs";
-
-pub const ALLOWED_FILETYPES: [mime::Mime; 2] = [mime::IMAGE_PNG, mime::IMAGE_JPEG];
-const ALLOWED_MIME_NAMES: [&'static str; 3] = ["png", "jpg", "jpeg"];
+/// Holds the uploads of every blog, by the id of the blog
+pub const BLOG_IMAGES_DIR: &str = "blogs";
+/// Where orphaned uploads wait before being deleted, next to the blog uploads
+pub const QUARANTINE_DIR: &str = "quarantine";
+/// Holds every upload once by the hash of its content, next to the blog uploads
+pub const OBJECTS_DIR: &str = "objects";
+
+/// Essences of the content types uploads may have
+pub const ALLOWED_FILETYPES: [&str; 5] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/svg+xml",
+];
+const ALLOWED_MIME_NAMES: [&'static str; 6] = ["png", "jpg", "jpeg", "gif", "webp", "svg"];
 
 sync_service!(ImagePathFactory; images_dir: Data<PublicDir>);
 
-mod path {
-    use std::path::{Path, PathBuf};
-
-    use crate::persistence::images;
-
-    use super::filename::Filename;
-
-    pub struct ImagePathBuf(images::ImagePathBuf);
-
-    impl ImagePathBuf {
-        pub fn create_ancestors(&self) -> std::io::Result<()> {
-            self.0.create_ancestors()
-        }
-    }
-
-    pub fn new(parent: PathBuf, filename: &Filename) -> ImagePathBuf {
-        let inner = images::ImagePathBuf::new(parent, filename.as_ref());
-        ImagePathBuf(inner)
-    }
-
-    impl AsRef<Path> for ImagePathBuf {
-        fn as_ref(&self) -> &Path {
-            self.0.as_ref()
-        }
-    }
-}
-
 pub mod filename {
     use super::ALLOWED_MIME_NAMES;
 
@@ -92,5 +86,12 @@ pub mod filename {
             let foo = "filename.jpeg";
             Filename::new(foo).unwrap();
         }
+
+        #[test]
+        fn accepts_animations_and_vectors() {
+            for foo in ["demo.gif", "demo.webp", "diagram.svg"] {
+                Filename::new(foo).unwrap();
+            }
+        }
     }
 }
@@ -5,16 +5,12 @@ use std::{
 
 use uuid::Uuid;
 
-use super::{
-    filename::Filename,
-    path::{new, ImagePathBuf},
-    ImagePathFactory, BLOG_IMAGES_DIR,
-};
+use super::{ImagePathFactory, BLOG_IMAGES_DIR};
 
 impl ImagePathFactory {
-    pub fn create_path(&self, blog_id: Uuid, filename: &Filename) -> ImagePathBuf {
-        let dir_path = create_dir_path(self.images_dir.as_ref().as_ref(), blog_id);
-        new(dir_path, filename)
+    /// Directory holding the uploads of the blog
+    pub fn blog_dir(&self, blog_id: Uuid) -> PathBuf {
+        create_dir_path(self.images_dir.as_ref().as_ref(), blog_id)
     }
 }
 
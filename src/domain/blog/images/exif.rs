@@ -0,0 +1,81 @@
+use std::io::Cursor;
+
+use exif::{In, Reader, Tag};
+use image::DynamicImage;
+
+/// Turns the image as the EXIF orientation of the upload says the camera was held, so it no
+/// longer depends on it. Encoding never writes EXIF back, so the orientation along with the
+/// rest of the metadata, like where the photo was taken, is dropped once stored
+pub fn orient(content: &[u8], image: DynamicImage) -> DynamicImage {
+    match orientation(content) {
+        Some(2) => image.fliph(),
+        Some(3) => image.rotate180(),
+        Some(4) => image.flipv(),
+        Some(5) => image.rotate90().fliph(),
+        Some(6) => image.rotate90(),
+        Some(7) => image.rotate270().fliph(),
+        Some(8) => image.rotate270(),
+        _ => image,
+    }
+}
+
+fn orientation(content: &[u8]) -> Option<u32> {
+    let exif = Reader::new()
+        .read_from_container(&mut Cursor::new(content))
+        .ok()?;
+
+    exif.get_field(Tag::Orientation, In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// Jpeg of `width` by `height` with an EXIF orientation
+#[cfg(test)]
+pub fn jpeg_with_orientation(width: u32, height: u32, orientation: u8) -> Vec<u8> {
+    let mut jpeg = Cursor::new(vec![]);
+    DynamicImage::ImageRgb8(image::RgbImage::new(width, height))
+        .write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90))
+        .unwrap();
+    let jpeg = jpeg.into_inner();
+
+    // Big endian TIFF with a single IFD holding the orientation
+    let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0".to_vec();
+    exif.extend([orientation, 0, 0, 0, 0, 0, 0]);
+
+    let mut app1 = vec![0xff, 0xe1];
+    app1.extend(((exif.len() + 2) as u16).to_be_bytes());
+    app1.extend(exif);
+
+    // Right after the start of image marker
+    [&jpeg[..2], &app1, &jpeg[2..]].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_by_orientation() {
+        let content = jpeg_with_orientation(4, 2, 6);
+        let image = image::load_from_memory(&content).unwrap();
+
+        assert_eq!(orientation(&content), Some(6));
+
+        let image = orient(&content, image);
+        assert_eq!((image.width(), image.height()), (2, 4));
+    }
+
+    #[test]
+    fn leaves_images_without_orientation() {
+        let mut png = Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(image::RgbImage::new(4, 2))
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+        let png = png.into_inner();
+
+        let image = orient(&png, image::load_from_memory(&png).unwrap());
+
+        assert_eq!(orientation(&png), None);
+        assert_eq!((image.width(), image.height()), (4, 2));
+    }
+}
@@ -0,0 +1,88 @@
+//! Keys of uploads in the blob store, laid out as they are in `STATIC_DIR`
+
+use uuid::Uuid;
+
+use super::{
+    variants::{variant_filename, VARIANTS_DIR},
+    Filename, BLOG_IMAGES_DIR, OBJECTS_DIR,
+};
+
+pub fn object(object: &Filename) -> String {
+    format!("{}/{}", OBJECTS_DIR, object.as_ref())
+}
+
+pub fn object_variants(object: &Filename) -> String {
+    format!("{}/{}/{}", OBJECTS_DIR, VARIANTS_DIR, object.as_ref())
+}
+
+/// `width` is `None` for the full size
+pub fn object_variant(object: &Filename, width: Option<u32>, extension: &str) -> String {
+    format!(
+        "{}/{}",
+        object_variants(object),
+        variant_filename(width, extension)
+    )
+}
+
+/// Holds the uploads of the blog stored before uploads were stored by content
+pub fn uploads(blog_id: Uuid) -> String {
+    format!("{}/{}", BLOG_IMAGES_DIR, blog_id)
+}
+
+/// Uploaded before uploads were stored by content
+pub fn upload(blog_id: Uuid, filename: &Filename) -> String {
+    format!("{}/{}", uploads(blog_id), filename.as_ref())
+}
+
+pub fn upload_variants(blog_id: Uuid, filename: &Filename) -> String {
+    format!(
+        "{}/{}/{}",
+        uploads(blog_id),
+        VARIANTS_DIR,
+        filename.as_ref()
+    )
+}
+
+pub fn upload_variant(
+    blog_id: Uuid,
+    filename: &Filename,
+    width: Option<u32>,
+    extension: &str,
+) -> String {
+    format!(
+        "{}/{}",
+        upload_variants(blog_id, filename),
+        variant_filename(width, extension)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{domain::blog::images::ImagePathFactory, persistence::public::PublicDir};
+
+    use super::*;
+
+    #[test]
+    fn mirrors_the_static_dir() {
+        let factory = ImagePathFactory {
+            images_dir: PublicDir::new_data("static"),
+        };
+        let blog_id = Uuid::nil();
+        let filename = Filename::new("photo.png").unwrap();
+
+        assert_eq!(
+            Path::new("static").join(upload(blog_id, filename)),
+            factory.blog_dir(blog_id).join("photo.png")
+        );
+        assert_eq!(
+            Path::new("static").join(upload_variant(blog_id, filename, Some(480), "webp")),
+            factory.blog_dir(blog_id).join("variants/photo.png/480.webp")
+        );
+        assert_eq!(
+            object_variant(filename, None, "webp"),
+            "objects/variants/photo.png/full.webp"
+        );
+    }
+}
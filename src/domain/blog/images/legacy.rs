@@ -0,0 +1,129 @@
+//! Uploads stored by filename in `STATIC_DIR` before uploads were stored by content
+
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use actix_web::web;
+
+use crate::persistence::blobs::{self, BlobStore};
+
+use super::BLOG_IMAGES_DIR;
+
+/// Copies the uploads of every blog kept in `static_dir` to the store, with their variants,
+/// under the keys they have there. Those already stored are left as they are, so it only
+/// copies what instances sharing the store did not. Returns how many it copied
+pub async fn copy_to_store(
+    static_dir: PathBuf,
+    blobs: &dyn BlobStore,
+) -> Result<usize, blobs::Error> {
+    let uploads =
+        blocking(move || files(&static_dir.join(BLOG_IMAGES_DIR), BLOG_IMAGES_DIR)).await?;
+    let mut copied = 0;
+
+    for (key, path) in uploads {
+        if blobs.exists(&key).await? {
+            continue;
+        }
+
+        let content = blocking(move || std::fs::read(path)).await?;
+        let extension = key.rsplit_once('.').map_or("", |(_, extension)| extension);
+        let content_type = actix_files::file_extension_to_mime(extension);
+
+        if blobs
+            .put_if_absent(&key, content, content_type.as_ref())
+            .await?
+        {
+            copied += 1;
+        }
+    }
+
+    Ok(copied)
+}
+
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> std::io::Result<T> + Send + 'static,
+) -> Result<T, blobs::Error> {
+    Ok(web::block(work)
+        .await
+        .map_err(|_| blobs::Error::Blocking)??)
+}
+
+/// Files anywhere inside `dir` by their key, `dir` being under `prefix`
+fn files(dir: &Path, prefix: &str) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    let mut files = vec![];
+    for entry in entries {
+        let entry = entry?;
+        // Keys are valid utf-8, files that are not were never uploaded
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+
+        let key = format!("{}/{}", prefix, name);
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            files.extend(self::files(&entry.path(), &key)?);
+        } else if file_type.is_file() {
+            files.push((key, entry.path()));
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::persistence::blobs::FsStore;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn copies_what_the_store_lacks() {
+        let static_dir = std::env::temp_dir().join(format!("legacy-{}", Uuid::new_v4()));
+        let store_dir = std::env::temp_dir().join(format!("legacy-{}", Uuid::new_v4()));
+        let store = FsStore::new(&store_dir);
+
+        for key in [
+            "blogs/b/a.png",
+            "blogs/b/variants/a.png/480.webp",
+            "blogs/b/c.gif",
+        ] {
+            let path = static_dir.join(key);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, key).unwrap();
+        }
+        store
+            .put("blogs/b/c.gif", b"kept".to_vec(), "image/gif")
+            .await
+            .unwrap();
+
+        assert_eq!(copy_to_store(static_dir.clone(), &store).await.unwrap(), 2);
+        assert_eq!(
+            store
+                .get("blogs/b/variants/a.png/480.webp")
+                .await
+                .unwrap()
+                .unwrap()
+                .as_ref(),
+            b"blogs/b/variants/a.png/480.webp"
+        );
+        assert_eq!(
+            store.get("blogs/b/c.gif").await.unwrap().unwrap().as_ref(),
+            b"kept"
+        );
+        assert_eq!(copy_to_store(static_dir.clone(), &store).await.unwrap(), 0);
+
+        std::fs::remove_dir_all(static_dir).unwrap();
+        std::fs::remove_dir_all(store_dir).unwrap();
+    }
+}
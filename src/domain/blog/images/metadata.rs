@@ -0,0 +1,129 @@
+use std::{collections::HashMap, io::Cursor};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{DynamicImage, ImageOutputFormat};
+use markdown_parse::ImageMetadata;
+use uuid::Uuid;
+
+use crate::persistence::db::Executor;
+
+use super::{objects::Object, Filename};
+
+/// Largest side of a placeholder, it is blurred by the browser anyway
+const PLACEHOLDER_SIZE: u32 = 16;
+
+/// Uploaded image of a blog
+#[derive(Debug, Clone)]
+pub struct StoredImage {
+    pub metadata: ImageMetadata,
+    /// Filename of the object holding it, `None` for images uploaded before they were stored
+    /// by content, which are still stored by their own filename
+    pub object: Option<String>,
+    /// Filenames in the variants dir of the object, `None` when unknown
+    pub variants: Option<Vec<String>>,
+}
+
+/// Images of a blog by filename
+pub type StoredImages = HashMap<String, StoredImage>;
+
+/// Size of the image as stored, with a tiny png of it as a data url
+pub fn measure(image: &DynamicImage) -> Result<ImageMetadata, image::ImageError> {
+    let mut png = Cursor::new(vec![]);
+    image
+        .thumbnail(PLACEHOLDER_SIZE, PLACEHOLDER_SIZE)
+        .write_to(&mut png, ImageOutputFormat::Png)?;
+
+    Ok(ImageMetadata {
+        width: image.width(),
+        height: image.height(),
+        placeholder: Some(format!(
+            "data:image/png;base64,{}",
+            STANDARD.encode(png.into_inner())
+        )),
+    })
+}
+
+/// Points the alias of the blog to the object, taking its metadata
+pub async fn save(
+    executor: impl Executor<'_>,
+    blog_id: Uuid,
+    filename: &Filename,
+    object: &Object,
+) -> Result<(), sqlx::Error> {
+    let filename: &str = filename.as_ref().as_ref();
+    let metadata = &object.metadata;
+
+    sqlx::query!(
+        r#"INSERT INTO blog_images(blog_id, filename, width, height, placeholder, hash)
+        VALUES($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (blog_id, filename) DO UPDATE SET
+            width = EXCLUDED.width, height = EXCLUDED.height, placeholder = EXCLUDED.placeholder,
+            hash = EXCLUDED.hash, uploaded_at = now()"#,
+        blog_id,
+        filename,
+        metadata.width as i32,
+        metadata.height as i32,
+        metadata.placeholder.as_deref().unwrap_or_default(),
+        object.hash
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn load(executor: impl Executor<'_>, blog_id: Uuid) -> Result<StoredImages, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT i.filename, i.width, i.height, i.placeholder, o.hash || '.' || o.extension as object,
+            o.variants
+        FROM blog_images i
+        LEFT JOIN image_objects o ON o.hash = i.hash
+        WHERE i.blog_id = $1"#,
+        blog_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let metadata = ImageMetadata {
+                width: row.width as u32,
+                height: row.height as u32,
+                placeholder: Some(row.placeholder).filter(|placeholder| !placeholder.is_empty()),
+            };
+
+            let image = StoredImage {
+                metadata,
+                object: row.object,
+                variants: row.variants,
+            };
+
+            (row.filename, image)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbImage;
+
+    use super::*;
+
+    #[test]
+    fn measures_with_a_tiny_placeholder() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(640, 480));
+
+        let metadata = measure(&image).unwrap();
+
+        assert_eq!((metadata.width, metadata.height), (640, 480));
+
+        let placeholder = metadata.placeholder.unwrap();
+        let png = STANDARD
+            .decode(placeholder.strip_prefix("data:image/png;base64,").unwrap())
+            .unwrap();
+        let thumbnail = image::load_from_memory(&png).unwrap();
+
+        assert_eq!((thumbnail.width(), thumbnail.height()), (16, 12));
+    }
+}
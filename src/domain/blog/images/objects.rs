@@ -0,0 +1,117 @@
+use markdown_parse::ImageMetadata;
+use sha2::{Digest, Sha256};
+
+use crate::persistence::db::Executor;
+
+/// Upload stored by the hash of its content, so its url never changes what it shows
+#[derive(Debug, Clone)]
+pub struct Object {
+    pub hash: String,
+    /// Of the filename it was first uploaded as, which tells its format
+    pub extension: String,
+    /// Bytes as stored, after being shrunk
+    pub size: i64,
+    pub metadata: ImageMetadata,
+    /// Filenames in its variants dir, `None` when stored before they were recorded
+    pub variants: Option<Vec<String>>,
+}
+
+impl Object {
+    /// `{hash}.{extension}`, as it is stored and served
+    pub fn filename(&self) -> String {
+        filename(&self.hash, &self.extension)
+    }
+}
+
+pub fn filename(hash: &str, extension: &str) -> String {
+    format!("{}.{}", hash, extension)
+}
+
+/// Hex sha256 of the uploaded bytes, identical uploads are only stored once
+pub fn hash(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+pub async fn find(executor: impl Executor<'_>, hash: &str) -> Result<Option<Object>, sqlx::Error> {
+    let object = sqlx::query!(
+        r#"SELECT hash, extension, size, width, height, placeholder, variants
+        FROM image_objects WHERE hash = $1"#,
+        hash
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(object.map(|object| Object {
+        hash: object.hash,
+        extension: object.extension,
+        size: object.size,
+        metadata: ImageMetadata {
+            width: object.width as u32,
+            height: object.height as u32,
+            placeholder: Some(object.placeholder).filter(|placeholder| !placeholder.is_empty()),
+        },
+        variants: object.variants,
+    }))
+}
+
+/// Holds off, until the transaction ends, others storing the object of `hash` and the orphaned
+/// image collection moving its blobs, so blobs are never stored for a row about to be pruned
+pub async fn lock(executor: impl Executor<'_>, hash: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"SELECT 1 as "locked!" FROM pg_advisory_xact_lock(hashtext($1))"#,
+        hash
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks the object as just stored, so the orphaned image collection leaves it alone for its
+/// grace. Whether the object still has a row
+pub async fn touch(executor: impl Executor<'_>, hash: &str) -> Result<bool, sqlx::Error> {
+    let touched = sqlx::query!(
+        "UPDATE image_objects SET created_at = now() WHERE hash = $1",
+        hash
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(touched.rows_affected() > 0)
+}
+
+/// Stores the object, or marks it as just stored when an identical upload stored it meanwhile
+pub async fn insert(executor: impl Executor<'_>, object: &Object) -> Result<(), sqlx::Error> {
+    let metadata = &object.metadata;
+
+    sqlx::query!(
+        r#"INSERT INTO image_objects(hash, extension, size, width, height, placeholder, variants)
+        VALUES($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (hash) DO UPDATE SET created_at = now()"#,
+        object.hash,
+        object.extension,
+        object.size,
+        metadata.width as i32,
+        metadata.height as i32,
+        metadata.placeholder.as_deref().unwrap_or_default(),
+        object.variants.as_deref()
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_content() {
+        assert_eq!(
+            hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(filename(&hash(b""), "png").len(), 64 + ".png".len());
+    }
+}
@@ -0,0 +1,552 @@
+//! Sanitizer for svg uploads, keeping only the elements and attributes that draw.
+//!
+//! Anything else is dropped along with its content: scripts, event handlers, animations, which
+//! can rewrite links, and references to anything outside the image itself.
+
+use std::borrow::Cow;
+
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+const ELEMENTS: &[&str] = &[
+    "svg",
+    "g",
+    "defs",
+    "symbol",
+    "use",
+    "a",
+    "title",
+    "desc",
+    "style",
+    "path",
+    "rect",
+    "circle",
+    "ellipse",
+    "line",
+    "polyline",
+    "polygon",
+    "text",
+    "tspan",
+    "textPath",
+    "linearGradient",
+    "radialGradient",
+    "stop",
+    "clipPath",
+    "mask",
+    "pattern",
+    "marker",
+    "filter",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+];
+
+const ATTRIBUTES: &[&str] = &[
+    "id",
+    "class",
+    "style",
+    "type",
+    "role",
+    "aria-label",
+    "version",
+    "transform",
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+    "fx",
+    "fy",
+    "fr",
+    "dx",
+    "dy",
+    "d",
+    "points",
+    "pathLength",
+    "width",
+    "height",
+    "viewBox",
+    "preserveAspectRatio",
+    "href",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "stroke",
+    "stroke-width",
+    "stroke-opacity",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "opacity",
+    "color",
+    "display",
+    "visibility",
+    "overflow",
+    "paint-order",
+    "vector-effect",
+    "shape-rendering",
+    "text-rendering",
+    "image-rendering",
+    "clip-path",
+    "clip-rule",
+    "clipPathUnits",
+    "mask",
+    "maskUnits",
+    "maskContentUnits",
+    "marker-start",
+    "marker-mid",
+    "marker-end",
+    "markerWidth",
+    "markerHeight",
+    "markerUnits",
+    "refX",
+    "refY",
+    "orient",
+    "gradientUnits",
+    "gradientTransform",
+    "spreadMethod",
+    "offset",
+    "stop-color",
+    "stop-opacity",
+    "patternUnits",
+    "patternContentUnits",
+    "patternTransform",
+    "font-family",
+    "font-size",
+    "font-style",
+    "font-weight",
+    "font-variant",
+    "text-anchor",
+    "text-decoration",
+    "dominant-baseline",
+    "alignment-baseline",
+    "baseline-shift",
+    "letter-spacing",
+    "word-spacing",
+    "writing-mode",
+    "textLength",
+    "lengthAdjust",
+    "startOffset",
+    "rotate",
+    "filter",
+    "filterUnits",
+    "primitiveUnits",
+    "color-interpolation-filters",
+    "in",
+    "in2",
+    "result",
+    "mode",
+    "values",
+    "operator",
+    "k1",
+    "k2",
+    "k3",
+    "k4",
+    "stdDeviation",
+    "edgeMode",
+    "radius",
+    "flood-color",
+    "flood-opacity",
+    "tableValues",
+    "slope",
+    "intercept",
+    "amplitude",
+    "exponent",
+];
+
+/// Sanitized svg with the size it is shown at
+#[derive(Debug)]
+pub struct Svg {
+    pub content: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// `None` when the upload is not an svg, or its size cannot be told from its `width`, `height`
+/// or `viewBox`
+pub fn sanitize(content: &[u8]) -> Option<Svg> {
+    let content = std::str::from_utf8(content).ok()?;
+
+    let mut sanitized = String::new();
+    // Elements kept that are still open
+    let mut open: Vec<&str> = vec![];
+    // Depth inside a dropped element
+    let mut dropped = 0;
+    let mut root = Root::default();
+    let mut in_root_tag = false;
+
+    for token in Tokenizer::from(content) {
+        match token.ok()? {
+            Token::ElementStart { prefix, local, .. } => {
+                let local = local.as_str();
+
+                if dropped > 0 || !prefix.is_empty() || !ELEMENTS.contains(&local) {
+                    dropped += 1;
+                    continue;
+                }
+
+                if !root.seen {
+                    if local != "svg" {
+                        return None;
+                    }
+                    root.seen = true;
+                    in_root_tag = true;
+                } else if open.is_empty() {
+                    // A single root
+                    return None;
+                }
+
+                sanitized.push('<');
+                sanitized.push_str(local);
+                open.push(local);
+            }
+            Token::Attribute {
+                prefix,
+                local,
+                value,
+                ..
+            } => {
+                if dropped > 0 {
+                    continue;
+                }
+
+                let (prefix, local) = (prefix.as_str(), local.as_str());
+                let value = unescape(value.as_str())?;
+
+                if in_root_tag && prefix.is_empty() {
+                    root.measure(local, &value);
+                }
+
+                if !is_allowed(prefix, local, &value) {
+                    continue;
+                }
+
+                sanitized.push(' ');
+                if !prefix.is_empty() {
+                    sanitized.push_str(prefix);
+                    sanitized.push(':');
+                }
+                sanitized.push_str(local);
+                sanitized.push_str("=\"");
+                sanitized.push_str(&escape(&value, true));
+                sanitized.push('"');
+            }
+            Token::ElementEnd { end, .. } => {
+                if dropped > 0 {
+                    if !matches!(end, ElementEnd::Open) {
+                        dropped -= 1;
+                    }
+                    continue;
+                }
+
+                if in_root_tag {
+                    if !root.has_namespace {
+                        sanitized.push_str(" xmlns=\"");
+                        sanitized.push_str(SVG_NAMESPACE);
+                        sanitized.push('"');
+                    }
+                    in_root_tag = false;
+                }
+
+                match end {
+                    ElementEnd::Open => sanitized.push('>'),
+                    ElementEnd::Empty => {
+                        sanitized.push_str("/>");
+                        open.pop();
+                    }
+                    ElementEnd::Close(_, _) => {
+                        let local = open.pop()?;
+                        sanitized.push_str("</");
+                        sanitized.push_str(local);
+                        sanitized.push('>');
+                    }
+                }
+            }
+            Token::Text { text } => {
+                let text = unescape(text.as_str())?;
+                push_text(&mut sanitized, &open, dropped, &text);
+            }
+            Token::Cdata { text, .. } => {
+                push_text(&mut sanitized, &open, dropped, text.as_str());
+            }
+            // Comments, processing instructions and doctypes, whose entities are never expanded
+            _ => {}
+        }
+    }
+
+    if !root.seen || !open.is_empty() {
+        return None;
+    }
+
+    let (width, height) = root.size()?;
+
+    Some(Svg {
+        content: sanitized,
+        width,
+        height,
+    })
+}
+
+/// What the root tells of the image
+#[derive(Default)]
+struct Root {
+    seen: bool,
+    has_namespace: bool,
+    width: Option<f64>,
+    height: Option<f64>,
+    view_box: Option<(f64, f64)>,
+}
+
+impl Root {
+    fn measure(&mut self, attribute: &str, value: &str) {
+        match attribute {
+            "xmlns" => self.has_namespace = value == SVG_NAMESPACE,
+            "width" => self.width = length(value),
+            "height" => self.height = length(value),
+            "viewBox" => {
+                let numbers = value
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|number| !number.is_empty())
+                    .map(|number| number.parse::<f64>().ok())
+                    .collect::<Option<Vec<_>>>();
+
+                self.view_box = match numbers.as_deref() {
+                    Some(&[_, _, width, height]) if width > 0.0 && height > 0.0 => {
+                        Some((width, height))
+                    }
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// Missing sides follow the aspect ratio of the `viewBox`
+    fn size(&self) -> Option<(u32, u32)> {
+        let (width, height) = match (self.width, self.height, self.view_box) {
+            (Some(width), Some(height), _) => (width, height),
+            (Some(width), None, Some((vw, vh))) => (width, width * vh / vw),
+            (None, Some(height), Some((vw, vh))) => (height * vw / vh, height),
+            (_, _, Some(view_box)) => view_box,
+            _ => return None,
+        };
+
+        let side = |length: f64| (length.round() as u32).max(1);
+        Some((side(width), side(height)))
+    }
+}
+
+/// Pixels of an absolute length, relative ones like percentages are unknown
+fn length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let value = value.strip_suffix("px").unwrap_or(value);
+
+    value.parse().ok().filter(|length: &f64| *length > 0.0)
+}
+
+fn is_allowed(prefix: &str, local: &str, value: &str) -> bool {
+    let allowed = match (prefix, local) {
+        ("", "xmlns") => value == SVG_NAMESPACE,
+        ("", local) => ATTRIBUTES.contains(&local),
+        ("xmlns", "xlink") => value == XLINK_NAMESPACE,
+        ("xlink", "href") => true,
+        ("xml", "space" | "lang") => true,
+        _ => false,
+    };
+
+    allowed && (local != "href" || value.starts_with('#')) && is_self_contained(value)
+}
+
+/// Whether css, or an attribute parsed as it, only references fragments of the image. Escapes
+/// are refused since they could spell any of the functions checked
+fn is_self_contained(css: &str) -> bool {
+    let css = css.to_ascii_lowercase();
+
+    if css.contains('\\') || css.contains("@import") || css.contains("javascript:") {
+        return false;
+    }
+
+    css.match_indices("url(").all(|(at, _)| {
+        css[at + "url(".len()..]
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+            .starts_with('#')
+    })
+}
+
+/// Text of kept elements, styles that reach outside the image are left empty
+fn push_text(sanitized: &mut String, open: &[&str], dropped: usize, text: &str) {
+    let Some(&parent) = open.last() else {
+        return;
+    };
+
+    if dropped > 0 || (parent == "style" && !is_self_contained(text)) {
+        return;
+    }
+
+    sanitized.push_str(&escape(text, false));
+}
+
+/// Resolves character references and the predefined entities, `None` for any other
+fn unescape(text: &str) -> Option<Cow<'_, str>> {
+    if !text.contains('&') {
+        return Some(Cow::Borrowed(text));
+    }
+
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(at) = rest.find('&') {
+        unescaped.push_str(&rest[..at]);
+        let (entity, after) = rest[at + 1..].split_once(';')?;
+
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+
+        unescaped.push(c);
+        rest = after;
+    }
+
+    unescaped.push_str(rest);
+    Some(Cow::Owned(unescaped))
+}
+
+fn escape(text: &str, is_attribute: bool) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if is_attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sanitized(svg: &str) -> String {
+        sanitize(svg.as_bytes()).unwrap().content
+    }
+
+    #[test]
+    fn keeps_drawings() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><defs><linearGradient id="g"><stop offset="0" stop-color="red"/></linearGradient></defs><rect width="10" height="10" fill="url(#g)"/><text x="1" y="5">a &lt; b</text></svg>"##;
+
+        assert_eq!(sanitized(svg), svg);
+    }
+
+    #[test]
+    fn strips_scripts_and_handlers() {
+        let svg = sanitized(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10" onload="alert(1)">
+            <script>alert(2)</script>
+            <foreignObject><div>html</div></foreignObject>
+            <a href="javascript:alert(3)"><circle r="4" onclick="alert(4)"/></a>
+            <set attributeName="href" to="javascript:alert(5)"/>
+            </svg>"#,
+        );
+
+        assert!(!svg.contains("alert"));
+        assert!(!svg.contains("html"));
+        assert!(svg.contains("<a><circle r=\"4\"/></a>"));
+    }
+
+    #[test]
+    fn strips_external_references() {
+        let svg = sanitized(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="10" height="10">
+            <style>@import url(https://evil.example/x.css);</style>
+            <style>rect { fill: url( "#local" ) }</style>
+            <use xlink:href="https://evil.example/sprite.svg#icon"/>
+            <use href="#icon"/>
+            <image href="https://evil.example/pixel.png"/>
+            <rect fill="url(https://evil.example/paint)" style="fill: u\rl(//evil.example)"/>
+            <path d="M0 0" fill="&#x75;rl(https://evil.example/paint)"/>
+            </svg>"##,
+        );
+
+        assert!(!svg.contains("evil"));
+        assert!(svg.contains(r##"rect { fill: url( "#local" ) }"##));
+        assert!(svg.contains(r##"<use href="#icon"/>"##));
+    }
+
+    #[test]
+    fn drops_unknown_namespaces() {
+        let svg = sanitized(
+            r#"<?xml version="1.0"?><!-- editor --><svg width="10" height="10" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" inkscape:version="1"><inkscape:grid><rect/></inkscape:grid><g/></svg>"#,
+        );
+
+        assert_eq!(
+            svg,
+            r#"<svg width="10" height="10" xmlns="http://www.w3.org/2000/svg"><g/></svg>"#
+        );
+    }
+
+    #[test]
+    fn measures_by_size_or_view_box() {
+        let size = |svg: &str| {
+            let svg = sanitize(svg.as_bytes()).unwrap();
+            (svg.width, svg.height)
+        };
+
+        assert_eq!(size(r#"<svg width="120px" height="80"/>"#), (120, 80));
+        assert_eq!(size(r#"<svg viewBox="0 0 300 150"/>"#), (300, 150));
+        assert_eq!(
+            size(r#"<svg width="600" viewBox="0,0,300,150"/>"#),
+            (600, 300)
+        );
+        assert_eq!(size(r#"<svg width="100%" viewBox="0 0 30 15"/>"#), (30, 15));
+        assert!(sanitize(br#"<svg width="100%"/>"#).is_none());
+    }
+
+    #[test]
+    fn rejects_what_is_not_an_svg() {
+        assert!(sanitize(b"<html><svg width=\"1\" height=\"1\"/></html>").is_none());
+        assert!(sanitize(b"<svg width=\"1\" height=\"1\">").is_none());
+        assert!(sanitize(b"<svg width=\"1\" height=\"1\">&custom;</svg>").is_none());
+        assert!(sanitize(b"\xff\xd8\xff").is_none());
+    }
+}
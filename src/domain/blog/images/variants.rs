@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use super::filename::Filename;
+
+/// Directory inside the blog uploads where the variants of each image are stored
+pub const VARIANTS_DIR: &str = "variants";
+
+/// Widths images are also stored at, so phones do not load the full size
+pub const WIDTHS: [u32; 3] = [480, 960, 1600];
+
+/// Formats an image is also stored in, besides its own. Each is only kept when smaller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Lossy, so only photos get it
+    Avif,
+    /// Lossless for pngs and lossy for photos
+    Webp,
+}
+
+impl Format {
+    /// From the most preferred, as browsers take the first `<source>` they can show
+    pub const ALL: [Format; 2] = [Format::Avif, Format::Webp];
+
+    /// Formats stored for images of the given extension, from the most preferred
+    pub fn alternates_of(extension: &str) -> &'static [Format] {
+        match extension {
+            "jpg" | "jpeg" => &Self::ALL,
+            "png" => &[Format::Webp],
+            _ => &[],
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Avif => "avif",
+            Self::Webp => "webp",
+        }
+    }
+
+    pub fn mime(self) -> mime::Mime {
+        match self {
+            Self::Avif => "image/avif".parse().unwrap(),
+            Self::Webp => "image/webp".parse().unwrap(),
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+    }
+}
+
+/// Whether images of the given extension are stored at [`WIDTHS`] and in alternate formats.
+/// Gifs and webps are stored as uploaded to keep their animation, and svgs scale on their own
+pub fn has_variants(extension: &str) -> bool {
+    matches!(extension, "png" | "jpg" | "jpeg")
+}
+
+/// Extension of an image as uploaded
+pub fn extension_of(filename: &Filename) -> &str {
+    let path: &Path = filename.as_ref().as_ref();
+    // Filenames are validated to have one
+    path.extension().and_then(|ext| ext.to_str()).unwrap_or("")
+}
+
+/// File of a variant in the variants dir, `width` is `None` for the full size
+pub fn variant_filename(width: Option<u32>, extension: &str) -> String {
+    match width {
+        Some(width) => format!("{}.{}", width, extension),
+        None => format!("full.{}", extension),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stills_get_webp_and_photos_avif() {
+        assert_eq!(Format::alternates_of("png"), &[Format::Webp]);
+        assert_eq!(Format::alternates_of("jpeg"), &[Format::Avif, Format::Webp]);
+        assert!(Format::alternates_of("gif").is_empty());
+        assert_eq!(Format::from_extension("avif"), Some(Format::Avif));
+    }
+
+    #[test]
+    fn only_raster_stills_get_variants() {
+        assert!(has_variants("jpeg"));
+        assert!(!has_variants("gif"));
+        assert!(!has_variants("svg"));
+    }
+
+    #[test]
+    fn names_variants_by_width() {
+        assert_eq!(variant_filename(Some(480), "webp"), "480.webp");
+        assert_eq!(variant_filename(None, "webp"), "full.webp");
+    }
+}
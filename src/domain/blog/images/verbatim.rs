@@ -0,0 +1,209 @@
+//! Gifs and webps are stored as uploaded, since encoding them again would keep a single frame
+//! of their animation. Only the metadata they carry is removed, like comments or EXIF.
+
+/// Extensions of the blocks kept, the loop count of animations and the color profile
+const GIF_APPLICATIONS: [&[u8]; 3] = [b"NETSCAPE2.0", b"ANIMEXTS1.0", b"ICCRGBG1012"];
+
+const GIF_EXTENSION: u8 = 0x21;
+const GIF_IMAGE: u8 = 0x2c;
+const GIF_TRAILER: u8 = 0x3b;
+const GIF_COMMENT: u8 = 0xfe;
+const GIF_APPLICATION: u8 = 0xff;
+
+/// Flags of the extended header telling EXIF and XMP chunks follow
+const WEBP_METADATA_FLAGS: u8 = 0x08 | 0x04;
+
+/// The gif without comments nor application data besides [`GIF_APPLICATIONS`], `None` when it
+/// is not a valid gif
+pub fn strip_gif(content: &[u8]) -> Option<Vec<u8>> {
+    if !content.starts_with(b"GIF87a") && !content.starts_with(b"GIF89a") {
+        return None;
+    }
+
+    // Header, screen descriptor and global color table
+    let mut at = 13 + color_table_len(*content.get(10)?);
+    let mut stripped = content.get(..at)?.to_vec();
+
+    loop {
+        match *content.get(at)? {
+            GIF_EXTENSION => {
+                let label = *content.get(at + 1)?;
+                let end = sub_blocks_end(content, at + 2)?;
+
+                let keep = match label {
+                    GIF_COMMENT => false,
+                    GIF_APPLICATION => {
+                        let identifier = content.get(at + 3..at + 14)?;
+                        GIF_APPLICATIONS.contains(&identifier)
+                    }
+                    _ => true,
+                };
+
+                if keep {
+                    stripped.extend_from_slice(&content[at..end]);
+                }
+                at = end;
+            }
+            GIF_IMAGE => {
+                // Descriptor, local color table and the minimum code size of the data
+                let data = at + 10 + color_table_len(*content.get(at + 9)?) + 1;
+                let end = sub_blocks_end(content, data)?;
+
+                stripped.extend_from_slice(&content[at..end]);
+                at = end;
+            }
+            GIF_TRAILER => {
+                stripped.push(GIF_TRAILER);
+                return Some(stripped);
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn color_table_len(flags: u8) -> usize {
+    match flags & 0x80 {
+        0 => 0,
+        _ => 3 << ((flags & 0x07) + 1),
+    }
+}
+
+/// Where the sub-blocks starting at `at` end, after the empty one closing them
+fn sub_blocks_end(content: &[u8], mut at: usize) -> Option<usize> {
+    loop {
+        let len = *content.get(at)? as usize;
+        at += 1 + len;
+
+        if len == 0 {
+            return (at <= content.len()).then_some(at);
+        }
+    }
+}
+
+/// The webp without its EXIF and XMP chunks, `None` when it is not a valid webp
+pub fn strip_webp(content: &[u8]) -> Option<Vec<u8>> {
+    if content.get(..4)? != b"RIFF" || content.get(8..12)? != b"WEBP" {
+        return None;
+    }
+
+    let mut stripped = b"RIFF\0\0\0\0WEBP".to_vec();
+    let mut at = 12;
+
+    while at < content.len() {
+        let fourcc = content.get(at..at + 4)?;
+        let len = u32::from_le_bytes(content.get(at + 4..at + 8)?.try_into().ok()?) as usize;
+        if content.len() < at + 8 + len {
+            return None;
+        }
+
+        // Chunks are padded to an even length
+        let end = (at + 8 + len + len % 2).min(content.len());
+
+        match fourcc {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let mut chunk = content[at..end].to_vec();
+                *chunk.get_mut(8)? &= !WEBP_METADATA_FLAGS;
+                stripped.extend(chunk);
+            }
+            _ => stripped.extend_from_slice(&content[at..end]),
+        }
+
+        at = end;
+    }
+
+    let riff_len = (stripped.len() - 8) as u32;
+    stripped[4..8].copy_from_slice(&riff_len.to_le_bytes());
+
+    Some(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{
+        codecs::gif::{GifDecoder, GifEncoder, Repeat},
+        AnimationDecoder, Delay, Frame, RgbaImage,
+    };
+
+    use super::*;
+    use crate::domain::blog::images::webp;
+
+    fn animated_gif() -> Vec<u8> {
+        let mut gif = vec![];
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            encoder.set_repeat(Repeat::Infinite).unwrap();
+            for shade in [0, 255] {
+                let frame = RgbaImage::from_pixel(4, 2, image::Rgba([shade, 0, 0, 255]));
+                encoder
+                    .encode_frame(Frame::from_parts(
+                        frame,
+                        0,
+                        0,
+                        Delay::from_numer_denom_ms(100, 1),
+                    ))
+                    .unwrap();
+            }
+        }
+        gif
+    }
+
+    #[test]
+    fn strips_gif_comments_keeping_animation() {
+        let gif = animated_gif();
+        let at = 13 + color_table_len(gif[10]);
+
+        let mut commented = gif[..at].to_vec();
+        commented.extend(b"\x21\xfe\x0ataken here\0");
+        commented.extend(b"\x21\xff\x0bXMP DataXMP\x03xmp\0");
+        commented.extend(&gif[at..]);
+
+        let stripped = strip_gif(&commented).unwrap();
+
+        assert_eq!(stripped, gif);
+        assert!(stripped.windows(11).any(|bytes| bytes == b"NETSCAPE2.0"));
+
+        let frames = GifDecoder::new(Cursor::new(stripped))
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+    }
+
+    #[test]
+    fn rejects_truncated_gifs() {
+        let gif = animated_gif();
+
+        assert!(strip_gif(&gif[..gif.len() - 4]).is_none());
+        assert!(strip_gif(b"\x89PNG").is_none());
+    }
+
+    #[test]
+    fn strips_webp_metadata() {
+        let image = RgbaImage::from_pixel(4, 2, image::Rgba([9, 8, 7, 255]));
+        let simple = webp::encode_lossless(&image).unwrap();
+
+        let mut vp8x = b"VP8X\x0a\0\0\0".to_vec();
+        vp8x.push(WEBP_METADATA_FLAGS);
+        vp8x.extend([0, 0, 0, 3, 0, 0, 1, 0, 0]);
+
+        let mut extended = b"RIFF\0\0\0\0WEBP".to_vec();
+        extended.extend(vp8x);
+        extended.extend(&simple[12..]);
+        extended.extend(b"EXIF\x03\0\0\0gps\0");
+        let riff_len = (extended.len() - 8) as u32;
+        extended[4..8].copy_from_slice(&riff_len.to_le_bytes());
+
+        let stripped = strip_webp(&extended).unwrap();
+
+        assert_eq!(stripped.len(), extended.len() - 12);
+        assert_eq!(stripped[20] & WEBP_METADATA_FLAGS, 0);
+        assert!(!stripped.windows(4).any(|bytes| bytes == b"EXIF"));
+
+        let decoded = image::load_from_memory(&stripped).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 2));
+    }
+}
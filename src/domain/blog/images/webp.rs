@@ -0,0 +1,87 @@
+//! WebP encoding by libwebp, the image crate only decodes them
+
+use ::webp::{Encoder, WebPConfig};
+use image::{RgbImage, RgbaImage};
+
+/// Widest and tallest image libwebp encodes
+const MAX_SIZE: u32 = 16383;
+
+/// Quality photos are encoded at, they were lossy already
+const LOSSY_QUALITY: f32 = 80.0;
+
+/// Encodes the image exactly, `None` when it is too large for the format
+pub fn encode_lossless(image: &RgbaImage) -> Option<Vec<u8>> {
+    let mut config = config(image.width(), image.height())?;
+    config.lossless = 1;
+    // Transparent pixels keep their colour, so decoding gives the same image back
+    config.exact = 1;
+
+    encode(
+        Encoder::from_rgba(image, image.width(), image.height()),
+        &config,
+    )
+}
+
+/// Encodes a photo, `None` when it is too large for the format
+pub fn encode_lossy(image: &RgbImage) -> Option<Vec<u8>> {
+    let mut config = config(image.width(), image.height())?;
+    config.quality = LOSSY_QUALITY;
+
+    encode(
+        Encoder::from_rgb(image, image.width(), image.height()),
+        &config,
+    )
+}
+
+fn config(width: u32, height: u32) -> Option<WebPConfig> {
+    if width == 0 || height == 0 || width > MAX_SIZE || height > MAX_SIZE {
+        return None;
+    }
+
+    WebPConfig::new().ok()
+}
+
+fn encode(encoder: Encoder, config: &WebPConfig) -> Option<Vec<u8>> {
+    encoder
+        .encode_advanced(config)
+        .ok()
+        .map(|encoded| encoded.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageFormat, Rgb, Rgba};
+
+    use super::*;
+
+    #[test]
+    fn encodes_losslessly_with_alpha() {
+        let image = RgbaImage::from_fn(123, 77, |x, y| {
+            Rgba([(x * 2) as u8, (y * 3) as u8, (x + y) as u8, (x * y) as u8])
+        });
+
+        let webp = encode_lossless(&image).unwrap();
+        let decoded = image::load_from_memory_with_format(&webp, ImageFormat::WebP)
+            .unwrap()
+            .to_rgba8();
+
+        assert_eq!(decoded.dimensions(), image.dimensions());
+        assert!(decoded.pixels().eq(image.pixels()));
+    }
+
+    #[test]
+    fn encodes_photos_lossy() {
+        let image = RgbImage::from_fn(64, 48, |x, y| Rgb([(x * 4) as u8, (y * 5) as u8, 90]));
+
+        let webp = encode_lossy(&image).unwrap();
+        let decoded = image::load_from_memory_with_format(&webp, ImageFormat::WebP).unwrap();
+
+        assert_eq!((decoded.width(), decoded.height()), (64, 48));
+    }
+
+    #[test]
+    fn rejects_sizes_out_of_the_format() {
+        assert!(encode_lossless(&RgbaImage::new(0, 10)).is_none());
+        assert!(encode_lossy(&RgbImage::new(MAX_SIZE + 1, 1)).is_none());
+    }
+}
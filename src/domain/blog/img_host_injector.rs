@@ -1,33 +1,143 @@
 use actix_web::web::Data;
-use markdown_parse::ImageUrlInjector;
+use markdown_parse::{ImageMetadata, ImageSources, ImageUrlInjector, LinkUrlInjector};
 use uuid::Uuid;
 
-use super::images::Filename;
+use super::{
+    attachments,
+    images::{
+        keys,
+        metadata::{StoredImage, StoredImages},
+        variants::{self, variant_filename, Format},
+        Filename,
+    },
+};
 
-use crate::{domain::server::ServerAddress, server::service::sync_service};
+use crate::{
+    domain::server::ServerAddress, persistence::blobs::BlobStore, server::service::sync_service,
+};
 
-sync_service!(ImgHostInjectorFactory; server_address: Data<ServerAddress>);
+sync_service!(ImgHostInjectorFactory;
+    server_address: Data<ServerAddress>,
+    blobs: Data<dyn BlobStore>
+);
 
 impl Clone for ImgHostInjectorFactory {
     fn clone(&self) -> Self {
         Self {
             server_address: self.server_address.clone(),
+            blobs: self.blobs.clone(),
         }
     }
 }
 
 impl ImgHostInjectorFactory {
-    pub fn create(&self, blog_id: Uuid) -> ImgHostInjector {
+    pub fn new(server_address: Data<ServerAddress>, blobs: Data<dyn BlobStore>) -> Self {
+        Self {
+            server_address,
+            blobs,
+        }
+    }
+
+    /// `images` are the uploaded images of the blog, as loaded by [`super::images::metadata::load`]
+    pub fn create(&self, blog_id: Uuid, images: StoredImages) -> ImgHostInjector {
         ImgHostInjector {
             server_address: &self.server_address,
+            blobs: self.blobs.as_ref(),
             blog_id,
+            images,
         }
     }
 }
 
+impl<'a> ImgHostInjector<'a> {
+    /// Sources of the object stored under `key`, only of the variants known to be stored as
+    /// nothing checks the rest exists
+    fn stored_sources(&self, key: &str, extension: &str) -> Option<ImageSources> {
+        let (object, image) = self.images.values().find_map(|image| {
+            let object = Filename::new(image.object.as_deref()?).ok()?;
+            (keys::object(object) == key).then_some((object, image))
+        })?;
+
+        image
+            .variants
+            .as_ref()
+            .filter(|stored| !stored.is_empty())?;
+        let public = |key: String| self.blobs.public_url(&key).unwrap_or(key);
+
+        Some(image_sources(
+            Some(image),
+            extension,
+            |width, format| match width.is_none() && format == extension {
+                true => public(keys::object(object)),
+                false => public(keys::object_variant(object, width, format)),
+            },
+        ))
+    }
+
+    /// Image shown from `url` as injected, by its object or by its filename in the blog
+    fn image_of(&self, url: &str) -> Option<&StoredImage> {
+        let name = url.rsplit('/').next()?;
+
+        self.images.get(name).or_else(|| {
+            self.images
+                .values()
+                .find(|image| image.object.as_deref() == Some(name))
+        })
+    }
+}
+
+/// Srcsets of the image in its own format and then each alternate, `url` giving where a variant
+/// of some width, or `None` for the full size, is in each format. Variants are listed when known
+/// to be stored, or else every width narrower than the image, as it is encoded. The full size is
+/// only listed when its width is known, and alternates missing every variant are left out
+fn image_sources(
+    image: Option<&StoredImage>,
+    extension: &str,
+    url: impl Fn(Option<u32>, &str) -> String,
+) -> ImageSources {
+    let stored = image.and_then(|image| image.variants.as_deref());
+    let width = image.map(|image| image.metadata.width);
+
+    let srcset = |format: &str| {
+        let is_stored =
+            |width| stored.is_none_or(|stored| stored.contains(&variant_filename(width, format)));
+
+        let narrower = variants::WIDTHS
+            .into_iter()
+            .filter(|&variant| width.is_none_or(|width| variant < width))
+            .filter(|&variant| is_stored(Some(variant)))
+            .map(|variant| (Some(variant), variant));
+
+        let full = width
+            .filter(|_| format == extension || is_stored(None))
+            .map(|width| (None, width));
+
+        narrower
+            .chain(full)
+            .map(|(variant, width)| format!("{} {}w", url(variant, format), width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    ImageSources {
+        sizes: SIZES.to_owned(),
+        srcset: srcset(extension),
+        alternates: Format::alternates_of(extension)
+            .iter()
+            .map(|&format| (format.mime().to_string(), srcset(format.extension())))
+            .filter(|(_, srcset)| !srcset.is_empty())
+            .collect(),
+    }
+}
+
+/// Width images are shown at in a blog
+const SIZES: &str = "(max-width: 800px) 100vw, 800px";
+
 pub struct ImgHostInjector<'a> {
     server_address: &'a ServerAddress,
+    blobs: &'a dyn BlobStore,
     blog_id: Uuid,
+    images: StoredImages,
 }
 
 impl<'a> ImageUrlInjector for ImgHostInjector<'a> {
@@ -35,9 +145,70 @@ impl<'a> ImageUrlInjector for ImgHostInjector<'a> {
         Filename::new(url).is_ok()
     }
 
+    /// Uploads stored by content get their immutable url, straight from the store when it is
+    /// publicly readable. The rest are served by filename
     fn inject(&self, url: &mut markdown_parse::CowStr<'_>) {
+        let object = self
+            .images
+            .get(url.as_ref())
+            .and_then(|image| image.object.as_deref())
+            .and_then(|object| Filename::new(object).ok());
+
+        let modified = match object {
+            Some(object) => self
+                .blobs
+                .public_url(&keys::object(object))
+                .unwrap_or_else(|| format!("{}/images/{}", self.server_address, object.as_ref())),
+            None => format!(
+                "{}/blogs/{}/public/{}",
+                self.server_address, self.blog_id, url
+            ),
+        };
+        *url = modified.into();
+    }
+
+    /// Variants narrower than the image in each format, along with its full size. Images served
+    /// straight from the store are offered by the variants stored of them, and so are those
+    /// served by the server when known, the server falling back to wider ones otherwise
+    fn sources(&self, url: &str) -> Option<ImageSources> {
+        let extension = url.rsplit_once('.').map_or("", |(_, extension)| extension);
+
+        if !variants::has_variants(extension) {
+            return None;
+        }
+
+        let store = self.blobs.public_url("");
+        if let Some(key) = store.as_deref().and_then(|store| url.strip_prefix(store)) {
+            return self.stored_sources(key, extension);
+        }
+
+        Some(image_sources(
+            self.image_of(url),
+            extension,
+            |width, format| match (width, format == extension) {
+                (Some(width), true) => format!("{}?w={}", url, width),
+                (Some(width), false) => format!("{}?w={}&format={}", url, width, format),
+                (None, true) => url.to_owned(),
+                (None, false) => format!("{}?format={}", url, format),
+            },
+        ))
+    }
+
+    fn metadata(&self, url: &str) -> Option<ImageMetadata> {
+        self.images.get(url).map(|image| image.metadata.clone())
+    }
+}
+
+/// Links to the bare filename of an attachment point to its download, whether or not it is
+/// uploaded yet, as images do
+impl<'a> LinkUrlInjector for ImgHostInjector<'a> {
+    fn is_valid_link(&self, url: &str) -> bool {
+        attachments::Filename::new(url).is_ok()
+    }
+
+    fn inject_link(&self, url: &mut markdown_parse::CowStr<'_>) {
         let modified = format!(
-            "{}/blogs/{}/public/{}",
+            "{}/blogs/{}/attachments/{}/",
             self.server_address, self.blog_id, url
         );
         *url = modified.into();
@@ -46,21 +217,34 @@ impl<'a> ImageUrlInjector for ImgHostInjector<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
+    use crate::persistence::blobs::{FsStore, S3Config, S3Store};
     use markdown_parse::BlogParse;
 
+    fn factory() -> ImgHostInjectorFactory {
+        let blobs: Arc<dyn BlobStore> = Arc::new(FsStore::new("static"));
+        ImgHostInjectorFactory::new(
+            ServerAddress::new_arc("http://localhost:3000").into(),
+            blobs.into(),
+        )
+    }
+
     #[test]
     fn injects_url() {
-        let factory = ImgHostInjectorFactory {
-            server_address: ServerAddress::new_arc("http://localhost:3000").into(),
-        };
+        let factory = factory();
 
         let content = r#"# Hello my brodas
 ![image](wosi.jpg)"#;
 
         let BlogParse {
             images, content, ..
-        } = markdown_parse::parse(content, &factory.create(uuid::Uuid::nil())).unwrap();
+        } = markdown_parse::parse(
+            content,
+            &factory.create(uuid::Uuid::nil(), StoredImages::new()),
+        )
+        .unwrap();
 
         assert!(images.into_inner().iter().any(|image| image == "wosi.jpg"));
         assert!(content.contains(
@@ -69,18 +253,206 @@ mod tests {
     }
 
     #[test]
-    fn only_collects_valid_images() {
-        let factory = ImgHostInjectorFactory {
-            server_address: ServerAddress::new_arc("http://localhost:3000").into(),
+    fn links_attachments() {
+        let factory = factory();
+
+        let content = r#"# Hello my brodas
+Get [the paper](paper.pdf), [the data](https://example.com/data.csv) or [a photo](wosi.jpg)"#;
+
+        let BlogParse { content, .. } = markdown_parse::parse(
+            content,
+            &factory.create(uuid::Uuid::nil(), StoredImages::new()),
+        )
+        .unwrap();
+
+        assert!(content.contains(
+            "http://localhost:3000/blogs/00000000-0000-0000-0000-000000000000/attachments/paper.pdf/"
+        ));
+        assert!(content.contains("\"https://example.com/data.csv\""));
+        assert!(content.contains("\"wosi.jpg\""));
+    }
+
+    #[test]
+    fn offers_variants() {
+        let factory = factory();
+
+        let sources = factory
+            .create(uuid::Uuid::nil(), StoredImages::new())
+            .sources("/wosi.png")
+            .unwrap();
+
+        assert_eq!(
+            sources.srcset,
+            "/wosi.png?w=480 480w, /wosi.png?w=960 960w, /wosi.png?w=1600 1600w"
+        );
+        assert_eq!(sources.alternates[0].0, "image/webp");
+        assert!(sources.alternates[0]
+            .1
+            .starts_with("/wosi.png?w=480&format=webp 480w"));
+
+        let sources = factory
+            .create(uuid::Uuid::nil(), StoredImages::new())
+            .sources("/wosi.jpg")
+            .unwrap();
+
+        assert_eq!(sources.alternates[0].0, "image/avif");
+        assert_eq!(sources.alternates[1].0, "image/webp");
+
+        assert!(factory
+            .create(uuid::Uuid::nil(), StoredImages::new())
+            .sources("/demo.gif")
+            .is_none());
+    }
+
+    #[test]
+    fn points_to_uploaded_objects() {
+        let factory = factory();
+        let images = StoredImages::from([(
+            "wosi.jpg".to_owned(),
+            StoredImage {
+                metadata: ImageMetadata {
+                    width: 800,
+                    height: 600,
+                    placeholder: None,
+                },
+                object: Some("d0e1.jpg".to_owned()),
+                variants: None,
+            },
+        )]);
+
+        let content = r#"# Hello my brodas
+![image](wosi.jpg)"#;
+
+        let BlogParse { content, .. } =
+            markdown_parse::parse(content, &factory.create(uuid::Uuid::nil(), images)).unwrap();
+
+        assert!(content.contains(r#"src="http://localhost:3000/images/d0e1.jpg""#));
+        assert!(content.contains(r#"width="800" height="600" loading="lazy""#));
+    }
+
+    #[test]
+    fn points_to_public_stores() {
+        let blobs: Arc<dyn BlobStore> = Arc::new(
+            S3Store::new(S3Config {
+                endpoint: "http://localhost:9000".to_owned(),
+                bucket: "blog".to_owned(),
+                region: "us-east-1".to_owned(),
+                access_key: "key".to_owned(),
+                secret_key: "secret".to_owned(),
+                public_url: Some("https://cdn.example.com/".to_owned()),
+                presign: None,
+            })
+            .unwrap(),
+        );
+        let factory = ImgHostInjectorFactory::new(
+            ServerAddress::new_arc("http://localhost:3000").into(),
+            blobs.into(),
+        );
+        let images = StoredImages::from([(
+            "wosi.png".to_owned(),
+            StoredImage {
+                metadata: ImageMetadata {
+                    width: 800,
+                    height: 600,
+                    placeholder: None,
+                },
+                object: Some("d0e1.png".to_owned()),
+                variants: Some(vec!["480.png".to_owned(), "full.webp".to_owned()]),
+            },
+        )]);
+
+        let injector = factory.create(uuid::Uuid::nil(), images);
+        let BlogParse { content, .. } =
+            markdown_parse::parse("# Hi\n![image](wosi.png)", &injector).unwrap();
+
+        assert!(content.contains(r#"src="https://cdn.example.com/objects/d0e1.png""#));
+
+        let sources = injector
+            .sources("https://cdn.example.com/objects/d0e1.png")
+            .unwrap();
+        assert_eq!(
+            sources.srcset,
+            "https://cdn.example.com/objects/variants/d0e1.png/480.png 480w, https://cdn.example.com/objects/d0e1.png 800w"
+        );
+        assert_eq!(
+            sources.alternates,
+            [(
+                "image/webp".to_owned(),
+                "https://cdn.example.com/objects/variants/d0e1.png/full.webp 800w".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn offers_what_narrower_images_have() {
+        let factory = factory();
+        let image = |object: Option<&str>, variants: Option<&[&str]>| StoredImage {
+            metadata: ImageMetadata {
+                width: 640,
+                height: 480,
+                placeholder: None,
+            },
+            object: object.map(str::to_owned),
+            variants: variants.map(|variants| variants.iter().map(|v| v.to_string()).collect()),
         };
+        let images = StoredImages::from([
+            (
+                "wosi.jpg".to_owned(),
+                image(
+                    Some("d0e1.jpg"),
+                    Some(&["480.jpg", "480.webp", "full.avif"]),
+                ),
+            ),
+            ("old.png".to_owned(), image(None, None)),
+        ]);
+        let injector = factory.create(uuid::Uuid::nil(), images);
+
+        let sources = injector
+            .sources("http://localhost:3000/images/d0e1.jpg")
+            .unwrap();
+        assert_eq!(
+            sources.srcset,
+            "http://localhost:3000/images/d0e1.jpg?w=480 480w, http://localhost:3000/images/d0e1.jpg 640w"
+        );
+        assert_eq!(
+            sources.alternates,
+            [
+                (
+                    "image/avif".to_owned(),
+                    "http://localhost:3000/images/d0e1.jpg?format=avif 640w".to_owned()
+                ),
+                (
+                    "image/webp".to_owned(),
+                    "http://localhost:3000/images/d0e1.jpg?w=480&format=webp 480w".to_owned()
+                )
+            ]
+        );
+
+        let sources = injector.sources("/blogs/x/public/old.png").unwrap();
+        assert_eq!(
+            sources.srcset,
+            "/blogs/x/public/old.png?w=480 480w, /blogs/x/public/old.png 640w"
+        );
+        assert_eq!(
+            sources.alternates[0].1,
+            "/blogs/x/public/old.png?w=480&format=webp 480w, /blogs/x/public/old.png?format=webp 640w"
+        );
+    }
+
+    #[test]
+    fn only_collects_valid_images() {
+        let factory = factory();
 
         let markdown = r#"# Hello guorld 
 ![image](image.png)
 Hello
 ![bruda](./bruda.png)"#;
 
-        let markdown_parse::BlogParse { images, .. } =
-            markdown_parse::parse(markdown, &factory.create(uuid::Uuid::nil())).unwrap();
+        let markdown_parse::BlogParse { images, .. } = markdown_parse::parse(
+            markdown,
+            &factory.create(uuid::Uuid::nil(), StoredImages::new()),
+        )
+        .unwrap();
 
         assert_eq!(images.into_inner(), vec!["image.png".to_string()]);
     }
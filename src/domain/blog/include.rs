@@ -0,0 +1,125 @@
+/// Related data embedded along with blogs, each one costs its own query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Include {
+    pub comments: bool,
+    pub tags: bool,
+    pub sub_categories: bool,
+    pub toc: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown include {0}")]
+pub struct UnknownInclude(pub String);
+
+impl Include {
+    pub const NONE: Self = Self {
+        comments: false,
+        tags: false,
+        sub_categories: false,
+        toc: false,
+    };
+
+    pub const ALL: Self = Self {
+        comments: true,
+        tags: true,
+        sub_categories: true,
+        toc: true,
+    };
+
+    /// Embedded in a blog when nothing is requested, as it always was
+    pub const BLOG: Self = Self {
+        toc: false,
+        ..Self::ALL
+    };
+
+    /// Embedded in the blogs listing when nothing is requested, which can not embed more
+    pub const LISTING: Self = Self {
+        comments: false,
+        tags: true,
+        sub_categories: true,
+        toc: false,
+    };
+
+    /// Parses comma separated names, as `comments,tags,subCategories,toc`, only those of
+    /// `allowed` are accepted
+    pub fn parse(names: &str, allowed: Self) -> Result<Self, UnknownInclude> {
+        let mut include = Self::NONE;
+
+        for name in names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let (requested, is_allowed) = match name {
+                "comments" => (&mut include.comments, allowed.comments),
+                "tags" => (&mut include.tags, allowed.tags),
+                "subCategories" => (&mut include.sub_categories, allowed.sub_categories),
+                "toc" => (&mut include.toc, allowed.toc),
+                _ => return Err(UnknownInclude(name.to_owned())),
+            };
+
+            if !is_allowed {
+                return Err(UnknownInclude(name.to_owned()));
+            }
+
+            *requested = true;
+        }
+
+        Ok(include)
+    }
+
+    /// Leaves out what is not kept by its name, as when it is not among the requested fields
+    pub fn retain(self, keep: impl Fn(&str) -> bool) -> Self {
+        Self {
+            comments: self.comments && keep("comments"),
+            tags: self.tags && keep("tags"),
+            sub_categories: self.sub_categories && keep("subCategories"),
+            toc: self.toc && keep("toc"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_requested_names() {
+        let include = Include::parse("tags, toc", Include::ALL).unwrap();
+
+        assert_eq!(
+            include,
+            Include {
+                tags: true,
+                toc: true,
+                ..Include::NONE
+            }
+        );
+        assert_eq!(Include::parse("", Include::ALL).unwrap(), Include::NONE);
+    }
+
+    #[test]
+    fn retains_by_name() {
+        let include = Include::ALL.retain(|name| name == "subCategories");
+
+        assert_eq!(
+            include,
+            Include {
+                sub_categories: true,
+                ..Include::NONE
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_names_not_allowed() {
+        assert_eq!(
+            Include::parse("tags,comments", Include::LISTING),
+            Err(UnknownInclude("comments".to_owned()))
+        );
+        assert_eq!(
+            Include::parse("authors", Include::ALL),
+            Err(UnknownInclude("authors".to_owned()))
+        );
+    }
+}
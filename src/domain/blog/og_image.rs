@@ -0,0 +1,303 @@
+use std::io::Cursor;
+
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use image::{ImageOutputFormat, Rgb, RgbImage};
+use sha2::{Digest, Sha256};
+
+pub const WIDTH: u32 = 1200;
+pub const HEIGHT: u32 = 630;
+
+/// Bump it whenever the template changes, so cached images are generated again
+const TEMPLATE_VERSION: u32 = 1;
+
+const FONT: &[u8] = include_bytes!("../../../assets/fonts/DejaVuSans-Bold.ttf");
+
+const BACKGROUND: Rgb<u8> = Rgb([17, 24, 39]);
+const TITLE_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+const CATEGORY_COLOR: Rgb<u8> = Rgb([156, 163, 175]);
+/// Band color of blogs without tags
+const ACCENT: Rgb<u8> = Rgb([99, 102, 241]);
+
+const MARGIN: f32 = 80.0;
+const CATEGORY_SIZE: f32 = 36.0;
+const TITLE_SIZE: f32 = 64.0;
+const TITLE_LINE_HEIGHT: f32 = 80.0;
+const TITLE_MAX_LINES: usize = 4;
+const BAND_HEIGHT: u32 = 30;
+
+/// What the preview image of a blog shows
+pub struct OgCard<'a> {
+    pub title: &'a str,
+    pub category: &'a str,
+    /// Tag colors as stored, like `#ff0000`, invalid ones are skipped
+    pub colors: &'a [String],
+}
+
+impl OgCard<'_> {
+    /// Changes whenever the rendered image would change, used to name the cached files
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(TEMPLATE_VERSION.to_le_bytes());
+        hasher.update(self.title.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.category.as_bytes());
+
+        for color in self.colors {
+            hasher.update([0]);
+            hasher.update(color.as_bytes());
+        }
+
+        hex::encode(&hasher.finalize()[..8])
+    }
+
+    /// Renders the card as a PNG
+    pub fn render(&self) -> Result<Vec<u8>, image::ImageError> {
+        // The font is bundled, so it is known to be valid
+        let font = FontRef::try_from_slice(FONT).unwrap();
+        let mut canvas = RgbImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+
+        let max_width = WIDTH as f32 - MARGIN * 2.0;
+
+        draw_text(
+            &mut canvas,
+            &font,
+            CATEGORY_SIZE,
+            CATEGORY_COLOR,
+            (MARGIN, MARGIN + CATEGORY_SIZE),
+            &fit(&font, CATEGORY_SIZE, self.category, max_width),
+        );
+
+        let lines = wrap(&font, TITLE_SIZE, self.title, max_width, TITLE_MAX_LINES);
+        for (i, line) in lines.iter().enumerate() {
+            let baseline = MARGIN + CATEGORY_SIZE + 40.0 + TITLE_LINE_HEIGHT * (i + 1) as f32;
+            draw_text(
+                &mut canvas,
+                &font,
+                TITLE_SIZE,
+                TITLE_COLOR,
+                (MARGIN, baseline),
+                line,
+            );
+        }
+
+        draw_band(&mut canvas, self.colors);
+
+        let mut png = vec![];
+        canvas.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+
+        Ok(png)
+    }
+}
+
+/// Splits the bottom band evenly between the tag colors
+fn draw_band(canvas: &mut RgbImage, colors: &[String]) {
+    let mut colors = colors
+        .iter()
+        .filter_map(|color| parse_color(color))
+        .collect::<Vec<_>>();
+
+    if colors.is_empty() {
+        colors.push(ACCENT);
+    }
+
+    let width = WIDTH / colors.len() as u32;
+    for x in 0..WIDTH {
+        let color = colors[((x / width.max(1)) as usize).min(colors.len() - 1)];
+
+        for y in HEIGHT - BAND_HEIGHT..HEIGHT {
+            canvas.put_pixel(x, y, color);
+        }
+    }
+}
+
+fn draw_text(
+    canvas: &mut RgbImage,
+    font: &FontRef,
+    size: f32,
+    color: Rgb<u8>,
+    (x, baseline): (f32, f32),
+    text: &str,
+) {
+    let scale = PxScale::from(size);
+    let scaled = font.as_scaled(scale);
+
+    let mut caret = x;
+    let mut previous = None;
+
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+
+        let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i64 + gx as i64;
+            let py = bounds.min.y as i64 + gy as i64;
+
+            if px < 0 || py < 0 || px >= WIDTH as i64 || py >= HEIGHT as i64 {
+                return;
+            }
+
+            let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+            for channel in 0..3 {
+                let background = pixel[channel] as f32;
+                let foreground = color[channel] as f32;
+                pixel[channel] = (background + (foreground - background) * coverage) as u8;
+            }
+        });
+    }
+}
+
+fn text_width(font: &FontRef, size: f32, text: &str) -> f32 {
+    let scaled = font.as_scaled(PxScale::from(size));
+
+    let mut width = 0.0;
+    let mut previous = None;
+
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            width += scaled.kern(previous, id);
+        }
+
+        width += scaled.h_advance(id);
+        previous = Some(id);
+    }
+
+    width
+}
+
+/// Shortens the text with an ellipsis until it fits in `max_width`
+fn fit(font: &FontRef, size: f32, text: &str, max_width: f32) -> String {
+    if text_width(font, size, text) <= max_width {
+        return text.to_owned();
+    }
+
+    let mut fitted = text.to_owned();
+    while !fitted.is_empty() && text_width(font, size, &format!("{}…", fitted)) > max_width {
+        fitted.pop();
+    }
+
+    format!("{}…", fitted.trim_end())
+}
+
+/// Breaks the text into lines no wider than `max_width`, the last line is shortened when the
+/// text does not fit in `max_lines`
+fn wrap(font: &FontRef, size: f32, text: &str, max_width: f32, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for word in text.split_whitespace() {
+        let full = lines.len() == max_lines;
+        let Some(line) = lines.last_mut() else {
+            lines.push(word.to_owned());
+            continue;
+        };
+
+        let candidate = format!("{} {}", line, word);
+        if text_width(font, size, &candidate) <= max_width {
+            *line = candidate;
+            continue;
+        }
+
+        if full {
+            // The candidate does not fit, so it always ends with an ellipsis
+            *line = fit(font, size, &candidate, max_width);
+            break;
+        }
+
+        lines.push(word.to_owned());
+    }
+
+    lines
+        .into_iter()
+        .map(|line| fit(font, size, &line, max_width))
+        .collect()
+}
+
+/// Parses `#rgb` and `#rrggbb` colors, the `#` is optional
+fn parse_color(color: &str) -> Option<Rgb<u8>> {
+    let hex = color.trim().trim_start_matches('#');
+
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+
+    match hex.len() {
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, c) in hex.chars().enumerate() {
+                let digit = channel(&c.to_string())?;
+                rgb[i] = digit * 17;
+            }
+
+            Some(Rgb(rgb))
+        }
+        6 => Some(Rgb([
+            channel(hex.get(0..2)?)?,
+            channel(hex.get(2..4)?)?,
+            channel(hex.get(4..6)?)?,
+        ])),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_png_of_og_size() {
+        let colors = ["#ff0000".to_owned(), "0f0".to_owned()];
+        let card = OgCard {
+            title: "A title long enough to be wrapped in several lines of the preview image",
+            category: "Code",
+            colors: &colors,
+        };
+
+        let png = card.render().unwrap();
+        let image = image::load_from_memory(&png).unwrap();
+
+        assert_eq!((image.width(), image.height()), (WIDTH, HEIGHT));
+        assert_eq!(image.to_rgb8().get_pixel(0, HEIGHT - 1), &Rgb([255, 0, 0]));
+    }
+
+    #[test]
+    fn wraps_long_titles_with_ellipsis() {
+        let font = FontRef::try_from_slice(FONT).unwrap();
+        let title = "word ".repeat(200);
+
+        let lines = wrap(&font, TITLE_SIZE, &title, 1040.0, TITLE_MAX_LINES);
+
+        assert_eq!(lines.len(), TITLE_MAX_LINES);
+        assert!(lines.last().unwrap().ends_with('…'));
+        assert!(lines
+            .iter()
+            .all(|line| text_width(&font, TITLE_SIZE, line) <= 1040.0));
+    }
+
+    #[test]
+    fn fingerprint_changes_with_title() {
+        let card = |title| OgCard {
+            title,
+            category: "Code",
+            colors: &[],
+        };
+
+        assert_eq!(card("a").fingerprint(), card("a").fingerprint());
+        assert_ne!(card("a").fingerprint(), card("b").fingerprint());
+    }
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(parse_color("#ff8000"), Some(Rgb([255, 128, 0])));
+        assert_eq!(parse_color("fff"), Some(Rgb([255, 255, 255])));
+        assert_eq!(parse_color("red"), None);
+    }
+}
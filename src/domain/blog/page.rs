@@ -0,0 +1,161 @@
+use chrono::{SecondsFormat, TimeZone, Utc};
+use leptos::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::features::get_post::Post;
+
+/// Where the `markdown-hydrate` bundle is served, see [`super::Config`]
+const PKG_PATH: &str = "/blogs/pkg";
+
+/// Absolute url of the page of a blog
+pub fn post_url(address: &str, slug: &str) -> String {
+    format!("{}/posts/{}/", address, slug)
+}
+
+/// Absolute url of the generated preview image of a blog
+pub fn og_image_url(address: &str, id: Uuid) -> String {
+    format!("{}/blogs/{}/og.png", address, id)
+}
+
+/// Renders the whole html document of a blog, islands in its html are hydrated by the
+/// `markdown-hydrate` bundle
+pub fn render(post: &Post, address: &str) -> String {
+    let canonical = post_url(address, &post.slug);
+    let published = Utc
+        .from_utc_datetime(&post.created_at)
+        .to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    // Blogs without images are shared with their generated preview
+    let image = post
+        .main_image
+        .clone()
+        .unwrap_or_else(|| og_image_url(address, post.id));
+
+    let json_ld = json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": post.title,
+        "description": post.description,
+        "datePublished": published,
+        "url": canonical,
+        "mainEntityOfPage": canonical,
+        "articleSection": post.category_name,
+        "keywords": post.tags,
+        "image": image,
+    });
+
+    // A closing tag inside the json would end the script early
+    let json_ld = json_ld.to_string().replace("</", "<\\/");
+
+    let hydrate = format!(
+        r#"import * as bundle from "{pkg}/module.js";
+bundle.default().then(() => {{
+    bundle.init();
+    for (const el of document.querySelectorAll("leptos-island")) {{
+        bundle["_island_" + el.dataset.component]?.(el);
+    }}
+    bundle.hydrate();
+}});"#,
+        pkg = PKG_PATH
+    );
+
+    let title = post.title.clone();
+    let description = post.description.clone();
+    let html = post.html.clone();
+
+    let document = leptos::ssr::render_to_string(move || {
+        view! {
+            <html lang="en">
+                <head>
+                    <meta charset="utf-8"/>
+                    <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                    <title>{title.clone()}</title>
+                    <meta name="description" content=description.clone()/>
+                    <link rel="canonical" href=canonical.clone()/>
+
+                    <meta property="og:type" content="article"/>
+                    <meta property="og:title" content=title.clone()/>
+                    <meta property="og:description" content=description.clone()/>
+                    <meta property="og:url" content=canonical/>
+                    <meta property="article:published_time" content=published/>
+                    <meta property="og:image" content=image.clone()/>
+
+                    <meta name="twitter:card" content="summary_large_image"/>
+                    <meta name="twitter:title" content=title/>
+                    <meta name="twitter:description" content=description/>
+                    <meta name="twitter:image" content=image/>
+
+                    <script type="application/ld+json" inner_html=json_ld></script>
+                    <link rel="modulepreload" href=format!("{}/module.js", PKG_PATH)/>
+                    <script type="module" inner_html=hydrate></script>
+                </head>
+                <body>
+                    <main>
+                        <article inner_html=html></article>
+                    </main>
+                </body>
+            </html>
+        }
+    });
+
+    format!("<!DOCTYPE html>{}", document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_to_generated_image() {
+        let post = Post {
+            main_image: None,
+            ..post()
+        };
+
+        let html = render(&post, "http://localhost:3000");
+
+        assert!(html.contains(&format!(
+            r#"<meta property="og:image" content="http://localhost:3000/blogs/{}/og.png"/>"#,
+            Uuid::nil()
+        )));
+    }
+
+    fn post() -> Post {
+        Post {
+            id: Uuid::nil(),
+            slug: "hello-world".into(),
+            title: "Hello world".into(),
+            description: "A \"quoted\" description".into(),
+            html: "<h1>Hello world</h1><p>Hi</p>".into(),
+            main_image: Some("http://localhost:3000/blogs/x/public/a.png".into()),
+            created_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+                .unwrap()
+                .and_hms_opt(3, 4, 5)
+                .unwrap(),
+            category_name: "Code".into(),
+            tags: vec!["</script>".into()],
+        }
+    }
+
+    #[test]
+    fn renders_seo_metadata() {
+        let html = render(&post(), "http://localhost:3000");
+
+        assert!(html.starts_with("<!DOCTYPE html><html"));
+        assert!(html.contains("<title>Hello world</title>"));
+        assert!(html.contains(r#"href="http://localhost:3000/posts/hello-world/""#));
+        assert!(html.contains(r#"content="A &quot;quoted&quot; description""#));
+        assert!(html.contains(r#"property="og:image""#));
+        assert!(html.contains("<article><h1>Hello world</h1><p>Hi</p></article>"));
+    }
+
+    #[test]
+    fn escapes_json_ld() {
+        let html = render(&post(), "http://localhost:3000");
+
+        assert!(html.contains(r#""@type":"BlogPosting""#));
+        assert!(html.contains(r#"<\/script>"#));
+        assert!(!html.contains(r#"["</script>"]"#));
+    }
+}
@@ -0,0 +1,78 @@
+use uuid::Uuid;
+
+/// Lowercase ascii words of the title joined by dashes, accents are dropped from the letters
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+
+    for c in title.chars().flat_map(char::to_lowercase) {
+        let c = match c {
+            'á' | 'à' | 'ä' | 'â' => 'a',
+            'é' | 'è' | 'ë' | 'ê' => 'e',
+            'í' | 'ì' | 'ï' | 'î' => 'i',
+            'ó' | 'ò' | 'ö' | 'ô' => 'o',
+            'ú' | 'ù' | 'ü' | 'û' => 'u',
+            'ñ' => 'n',
+            'ç' => 'c',
+            c => c,
+        };
+
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+
+    slug
+}
+
+/// Slugs a new blog takes in order, the first one no other blog uses: its title, then its title
+/// suffixed with the start of its id. Blogs keep their slug once created so links to them do
+/// not break, even when their title changes
+pub fn candidates(blog_id: Uuid, title: &str) -> Vec<String> {
+    let short_id = &blog_id.to_string()[..8];
+
+    let slug = slugify(title);
+    if slug.is_empty() {
+        return vec![short_id.to_owned()];
+    }
+
+    let suffixed = format!("{}-{}", slug, short_id);
+    vec![slug, suffixed]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_words_with_dashes() {
+        assert_eq!(slugify("Hello my brodas"), "hello-my-brodas");
+        assert_eq!(slugify("  Rust & Actix: 101!  "), "rust-actix-101");
+    }
+
+    #[test]
+    fn drops_accents() {
+        assert_eq!(slugify("¿Qué es Ñandú?"), "que-es-nandu");
+    }
+
+    #[test]
+    fn is_empty_without_words() {
+        assert_eq!(slugify("¡¿?!"), "");
+    }
+
+    #[test]
+    fn suffixes_id_as_fallback() {
+        let id = Uuid::parse_str("d0e1f2a3-0000-0000-0000-000000000000").unwrap();
+
+        assert_eq!(
+            candidates(id, "Hello world"),
+            vec!["hello-world", "hello-world-d0e1f2a3"]
+        );
+        assert_eq!(candidates(id, "?!"), vec!["d0e1f2a3"]);
+    }
+}
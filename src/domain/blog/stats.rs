@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+/// What a blog content is made of, as counted when compiled
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlogStats {
+    /// Words of the text, inline code included
+    pub words: i32,
+    /// Words inside code blocks
+    pub code_words: i32,
+    pub images: i32,
+    pub code_blocks: i32,
+    pub links: i32,
+}
+
+impl From<markdown_parse::ContentStats> for BlogStats {
+    fn from(stats: markdown_parse::ContentStats) -> Self {
+        Self {
+            words: stats.words as i32,
+            code_words: stats.code_words as i32,
+            images: stats.images as i32,
+            code_blocks: stats.code_blocks as i32,
+            links: stats.links as i32,
+        }
+    }
+}
+
+/// Words read in a minute, code is read slower than text
+#[derive(Debug, Clone, Copy)]
+pub struct ReadingSpeed {
+    pub words_per_minute: u32,
+    pub code_words_per_minute: u32,
+}
+
+impl Default for ReadingSpeed {
+    fn default() -> Self {
+        Self {
+            words_per_minute: 230,
+            code_words_per_minute: 100,
+        }
+    }
+}
+
+impl ReadingSpeed {
+    /// Whole minutes taken to read the words, never less than one
+    pub fn reading_time(&self, words: i32, code_words: i32) -> i32 {
+        let minutes = words.max(0) as f64 / self.words_per_minute.max(1) as f64
+            + code_words.max(0) as f64 / self.code_words_per_minute.max(1) as f64;
+
+        (minutes.round() as i32).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_code_slower() {
+        let speed = ReadingSpeed {
+            words_per_minute: 200,
+            code_words_per_minute: 100,
+        };
+
+        assert_eq!(speed.reading_time(1400, 0), 7);
+        assert_eq!(speed.reading_time(1000, 200), 7);
+    }
+
+    #[test]
+    fn takes_at_least_a_minute() {
+        assert_eq!(ReadingSpeed::default().reading_time(0, 0), 1);
+    }
+}
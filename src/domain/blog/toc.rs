@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Heading of a blog, linkable as `#id`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TocEntry {
+    pub level: u8,
+    pub id: String,
+    pub text: String,
+}
+
+impl From<markdown_parse::TocEntry> for TocEntry {
+    fn from(entry: markdown_parse::TocEntry) -> Self {
+        Self {
+            level: entry.level,
+            id: entry.id,
+            text: entry.text,
+        }
+    }
+}
@@ -20,12 +20,15 @@ mod db {
     use super::{category::Category, sub_category::SubCategory};
 
     pub async fn get_all_categories(pool: &Pool) -> Result<Vec<Category>, sqlx::Error> {
-        query_as!(Category, "SELECT * FROM categories")
+        query_as!(Category, "SELECT id, name FROM categories")
             .fetch_all(pool)
             .await
     }
 
-    pub async fn create_category(pool: &Pool, name: &str) -> Result<IdSelect, sqlx::Error> {
+    pub async fn create_category(
+        pool: impl Executor<'_>,
+        name: &str,
+    ) -> Result<IdSelect, sqlx::Error> {
         query_as!(
             IdSelect,
             "INSERT INTO categories (name) VALUES ($1) RETURNING id",
@@ -35,14 +38,17 @@ mod db {
         .await
     }
 
-    pub async fn delete_category(pool: &Pool, id: uuid::Uuid) -> Result<QueryResult, sqlx::Error> {
+    pub async fn delete_category(
+        pool: impl Executor<'_>,
+        id: uuid::Uuid,
+    ) -> Result<QueryResult, sqlx::Error> {
         query!("DELETE FROM categories WHERE id = $1", id)
             .execute(pool)
             .await
     }
 
     pub async fn create_subcategory(
-        pool: &Pool,
+        pool: impl Executor<'_>,
         name: &str,
         category_id: uuid::Uuid,
     ) -> Result<IdSelect, sqlx::Error> {
@@ -57,7 +63,7 @@ mod db {
     }
 
     pub async fn delete_subcategory(
-        pool: &Pool,
+        pool: impl Executor<'_>,
         id: uuid::Uuid,
     ) -> Result<QueryResult, sqlx::Error> {
         query!("DELETE FROM sub_categories WHERE id = $1", id)
@@ -66,7 +72,7 @@ mod db {
     }
 
     pub async fn create_tag(
-        pool: &Pool,
+        pool: impl Executor<'_>,
         category_id: uuid::Uuid,
         name: &str,
         color: &str,
@@ -82,7 +88,10 @@ mod db {
         .await
     }
 
-    pub async fn delete_tag(pool: &Pool, id: uuid::Uuid) -> Result<QueryResult, sqlx::Error> {
+    pub async fn delete_tag(
+        pool: impl Executor<'_>,
+        id: uuid::Uuid,
+    ) -> Result<QueryResult, sqlx::Error> {
         query!("DELETE FROM tags WHERE id = $1", id)
             .execute(pool)
             .await
@@ -106,7 +115,7 @@ mod db {
     }
 
     pub async fn get_all_sub_categories(pool: &Pool) -> Result<Vec<SubCategory>, sqlx::Error> {
-        query_as!(SubCategory, "SELECT * FROM sub_categories")
+        query_as!(SubCategory, "SELECT id, name, category_id FROM sub_categories")
             .fetch_all(pool)
             .await
     }
@@ -117,7 +126,7 @@ mod db {
     ) -> Result<Vec<SubCategory>, sqlx::Error> {
         query_as!(
             SubCategory,
-            "SELECT * FROM sub_categories WHERE category_id = $1",
+            "SELECT id, name, category_id FROM sub_categories WHERE category_id = $1",
             category_id
         )
         .fetch_all(pool)
@@ -146,7 +155,7 @@ mod db {
     ) -> Result<Vec<Tag>, sqlx::Error> {
         query_as!(
             Tag,
-            "SELECT * FROM tags WHERE category_id = $1",
+            "SELECT id, name, color, category_id FROM tags WHERE category_id = $1",
             category_id
         )
         .fetch_all(pool)
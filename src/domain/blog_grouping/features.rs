@@ -1,3 +1,6 @@
+pub mod get_categories;
 pub mod get_one_category;
 pub mod get_tags_by_blog;
 pub mod get_sub_categories_by_blog;
+pub mod get_sub_categories_by_category;
+pub mod get_tags_by_category;
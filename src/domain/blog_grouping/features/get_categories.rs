@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+
+use crate::{
+    domain::{
+        blog_grouping::{category::Category, get_all_categories},
+        read_cache::ReadCache,
+    },
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+sync_service!(GetCategories; pool: Data<Pool>, cache: Data<ReadCache>);
+
+impl GetCategories {
+    pub async fn run(&self) -> Result<Arc<Vec<Category>>, sqlx::Error> {
+        if let Some(categories) = self.cache.categories.get(&()) {
+            return Ok(categories);
+        }
+
+        let epoch = self.cache.categories.epoch();
+
+        let categories = Arc::new(get_all_categories(self.pool.get_ref()).await?);
+        self.cache.categories.insert((), categories.clone(), epoch);
+
+        Ok(categories)
+    }
+}
@@ -10,7 +10,7 @@ impl GetOneCategory {
     pub async fn run(&self, category_id: uuid::Uuid) -> Result<Option<Category>, sqlx::Error> {
         sqlx::query_as!(
             Category,
-            "SELECT * FROM categories WHERE id = $1",
+            "SELECT id, name FROM categories WHERE id = $1",
             category_id
         )
         .fetch_optional(self.pool.as_ref())
@@ -11,7 +11,7 @@ impl GetSubCategorysByBlog {
     pub async fn run(&self, blog_id: uuid::Uuid) -> Result<Vec<SubCategory>, sqlx::Error> {
         sqlx::query_as!(
             SubCategory,
-            "SELECT sc.* FROM sub_categories sc JOIN sub_categories_blogs scb ON scb.sub_category_id = sc.id WHERE scb.blog_id = $1",
+            "SELECT sc.id, sc.name, sc.category_id FROM sub_categories sc JOIN sub_categories_blogs scb ON scb.sub_category_id = sc.id WHERE scb.blog_id = $1",
             blog_id
         )
         .fetch_all(self.pool.as_ref())
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog_grouping::{get_sub_categories_by_category, sub_category::SubCategory},
+        read_cache::ReadCache,
+    },
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+sync_service!(GetSubCategoriesByCategory; pool: Data<Pool>, cache: Data<ReadCache>);
+
+impl GetSubCategoriesByCategory {
+    pub async fn run(&self, category_id: Uuid) -> Result<Arc<Vec<SubCategory>>, sqlx::Error> {
+        if let Some(sub_categories) = self.cache.sub_categories.get(&category_id) {
+            return Ok(sub_categories);
+        }
+
+        let epoch = self.cache.sub_categories.epoch();
+
+        let sub_categories =
+            Arc::new(get_sub_categories_by_category(self.pool.get_ref(), category_id).await?);
+        self.cache
+            .sub_categories
+            .insert(category_id, sub_categories.clone(), epoch);
+
+        Ok(sub_categories)
+    }
+}
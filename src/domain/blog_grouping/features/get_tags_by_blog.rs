@@ -10,7 +10,7 @@ impl GetTagsByBlog {
     pub async fn run(&self, blog_id: uuid::Uuid) -> Result<Vec<Tag>, sqlx::Error> {
         sqlx::query_as!(
             Tag,
-            "SELECT t.* FROM tags t JOIN tags_blogs tb ON tb.tag_id = t.id WHERE tb.blog_id = $1",
+            "SELECT t.id, t.name, t.color, t.category_id FROM tags t JOIN tags_blogs tb ON tb.tag_id = t.id WHERE tb.blog_id = $1",
             blog_id
         )
         .fetch_all(self.pool.as_ref())
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog_grouping::{get_tags_by_category, tag::Tag},
+        read_cache::ReadCache,
+    },
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+sync_service!(GetTagsByCategory; pool: Data<Pool>, cache: Data<ReadCache>);
+
+impl GetTagsByCategory {
+    pub async fn run(&self, category_id: Uuid) -> Result<Arc<Vec<Tag>>, sqlx::Error> {
+        if let Some(tags) = self.cache.tags.get(&category_id) {
+            return Ok(tags);
+        }
+
+        let epoch = self.cache.tags.epoch();
+
+        let tags = Arc::new(get_tags_by_category(self.pool.get_ref(), category_id).await?);
+        self.cache.tags.insert(category_id, tags.clone(), epoch);
+
+        Ok(tags)
+    }
+}
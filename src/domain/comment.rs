@@ -6,7 +6,10 @@ mod db {
     use sqlx::query_as;
     use uuid::Uuid;
 
-    use crate::persistence::db::{entities::IdSelect, Pool, Slice};
+    use crate::{
+        domain::event::{self, DomainEvent},
+        persistence::db::{entities::IdSelect, Pool, Slice, Transaction},
+    };
 
     use super::models::{CommentJoinUser, CreateComment};
 
@@ -34,20 +37,30 @@ mod db {
         .await
     }
 
-    pub async fn create<'a>(
-        pool: &'a Pool,
-        req: &'a CreateComment,
+    /// Creates the comment within `tx`, left for the caller to commit
+    pub async fn create(
+        tx: &mut Transaction<'_>,
+        req: &CreateComment,
         agent_id: Uuid,
         blog_id: Uuid,
     ) -> Result<IdSelect, sqlx::Error> {
-        query_as!(
+        let comment = query_as!(
             IdSelect,
             "INSERT INTO comments (account_id, blog_id, content) VALUES ($1, $2, $3) RETURNING id",
             agent_id,
             blog_id,
             req.content
         )
-        .fetch_one(pool)
-        .await
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let event = DomainEvent::CommentCreated {
+            comment_id: comment.id,
+            blog_id,
+            account_id: agent_id,
+        };
+        event::record(tx, &event).await?;
+
+        Ok(comment)
     }
 }
@@ -0,0 +1,135 @@
+mod bus;
+
+pub use bus::{EventBus, Subscriber};
+pub use db::record;
+pub use models::{DomainEvent, EventKind};
+
+mod models {
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+    #[sqlx(type_name = "event_kind", rename_all = "snake_case")]
+    #[serde(rename_all = "camelCase")]
+    pub enum EventKind {
+        BlogCreated,
+        BlogUpdated,
+        BlogContentSet,
+        BlogDeleted,
+        CommentCreated,
+        ReplyCreated,
+        AccountRegistered,
+    }
+
+    impl sqlx::postgres::PgHasArrayType for EventKind {
+        fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+            sqlx::postgres::PgTypeInfo::with_name("_event_kind")
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(
+        tag = "type",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )]
+    pub enum DomainEvent {
+        BlogCreated {
+            blog_id: Uuid,
+        },
+        BlogUpdated {
+            blog_id: Uuid,
+        },
+        BlogContentSet {
+            blog_id: Uuid,
+        },
+        BlogDeleted {
+            blog_id: Uuid,
+        },
+        CommentCreated {
+            comment_id: Uuid,
+            blog_id: Uuid,
+            account_id: Uuid,
+        },
+        ReplyCreated {
+            reply_id: Uuid,
+            comment_id: Uuid,
+            parent_id: Option<Uuid>,
+            account_id: Uuid,
+        },
+        AccountRegistered {
+            account_id: Uuid,
+        },
+    }
+
+    impl DomainEvent {
+        pub fn kind(&self) -> EventKind {
+            match self {
+                Self::BlogCreated { .. } => EventKind::BlogCreated,
+                Self::BlogUpdated { .. } => EventKind::BlogUpdated,
+                Self::BlogContentSet { .. } => EventKind::BlogContentSet,
+                Self::BlogDeleted { .. } => EventKind::BlogDeleted,
+                Self::CommentCreated { .. } => EventKind::CommentCreated,
+                Self::ReplyCreated { .. } => EventKind::ReplyCreated,
+                Self::AccountRegistered { .. } => EventKind::AccountRegistered,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn serializes_with_type_tag() {
+            let event = DomainEvent::BlogCreated {
+                blog_id: Uuid::nil(),
+            };
+
+            let json = serde_json::to_value(&event).unwrap();
+
+            assert_eq!(
+                json,
+                serde_json::json!({
+                    "type": "blogCreated",
+                    "blogId": "00000000-0000-0000-0000-000000000000"
+                })
+            );
+        }
+
+        #[test]
+        fn deserializes_what_it_serializes() {
+            let event = DomainEvent::ReplyCreated {
+                reply_id: Uuid::new_v4(),
+                comment_id: Uuid::new_v4(),
+                parent_id: None,
+                account_id: Uuid::new_v4(),
+            };
+
+            let json = serde_json::to_string(&event).unwrap();
+
+            assert_eq!(serde_json::from_str::<DomainEvent>(&json).unwrap(), event);
+        }
+    }
+}
+
+mod db {
+    use sqlx::types::Json;
+
+    use crate::persistence::db::Transaction;
+
+    use super::models::{DomainEvent, EventKind};
+
+    /// Writes the event into the outbox, it is dispatched once the transaction commits
+    pub async fn record(tx: &mut Transaction<'_>, event: &DomainEvent) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO events (kind, payload) VALUES ($1, $2)",
+            event.kind() as EventKind,
+            Json(event) as _
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use std::{rc::Rc, time::Duration};
+
+use actix_web::web::Data;
+use futures_util::future::LocalBoxFuture;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::persistence::db::Pool;
+
+use super::models::DomainEvent;
+
+/// How long the dispatcher waits before looking for new events once the outbox is drained
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+const BATCH_SIZE: i64 = 50;
+
+/// Events failing this many times are marked dead in the outbox and no longer retried
+const MAX_ATTEMPTS: i32 = 10;
+
+/// How long a claimed event is left to its dispatcher before another one may take it over
+const LEASE: Duration = Duration::from_secs(5 * 60);
+
+/// Reacts to the events stored in the outbox.
+///
+/// Events are delivered at least once, if any subscriber fails the event is delivered again to
+/// every subscriber, so handlers must be idempotent.
+pub trait Subscriber {
+    fn name(&self) -> &'static str;
+
+    /// `id` identifies the event in the outbox, it is the same on every redelivery
+    fn handle<'a>(
+        &'a self,
+        id: Uuid,
+        event: &'a DomainEvent,
+    ) -> LocalBoxFuture<'a, Result<(), String>>;
+}
+
+struct OutboxEvent {
+    seq: i64,
+    id: Uuid,
+    payload: Json<DomainEvent>,
+}
+
+#[derive(Default, Clone)]
+pub struct EventBus {
+    subscribers: Vec<Rc<dyn Subscriber>>,
+}
+
+impl EventBus {
+    pub fn subscribe(mut self, subscriber: impl Subscriber + 'static) -> Self {
+        self.subscribers.push(Rc::new(subscriber));
+        self
+    }
+
+    /// Spawns the task that drains the outbox for as long as the server lives
+    pub fn spawn(self, pool: Data<Pool>) {
+        actix_web::rt::spawn(async move {
+            loop {
+                match self.dispatch_batch(pool.as_ref()).await {
+                    Ok(0) => actix_web::rt::time::sleep(POLL_INTERVAL).await,
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("Could not dispatch events: {:?}", e);
+                        actix_web::rt::time::sleep(POLL_INTERVAL).await;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Returns the amount of events dispatched to every subscriber
+    async fn dispatch_batch(&self, pool: &Pool) -> Result<usize, sqlx::Error> {
+        let mut events = claim(pool).await?;
+        events.sort_by_key(|event| event.seq);
+
+        let mut dispatched = 0;
+        for event in events.iter() {
+            match self.publish(event.id, &event.payload).await {
+                Ok(_) => {
+                    dispatched += 1;
+
+                    sqlx::query!(
+                        r#"UPDATE events
+                        SET dispatched_at = now(), attempts = attempts + 1, locked_until = NULL
+                        WHERE seq = $1"#,
+                        event.seq
+                    )
+                    .execute(pool)
+                    .await?;
+                }
+                Err(e) => {
+                    eprintln!("Could not dispatch event {}: {}", event.id, e);
+
+                    let dead = sqlx::query_scalar!(
+                        r#"UPDATE events
+                        SET
+                            attempts = attempts + 1,
+                            last_error = $1,
+                            locked_until = NULL,
+                            dead_at = CASE WHEN attempts + 1 >= $2 THEN now() END
+                        WHERE seq = $3
+                        RETURNING dead_at IS NOT NULL as "dead!""#,
+                        e,
+                        MAX_ATTEMPTS,
+                        event.seq
+                    )
+                    .fetch_one(pool)
+                    .await?;
+
+                    if dead {
+                        eprintln!(
+                            "Gave up on event {} after {} attempts",
+                            event.id, MAX_ATTEMPTS
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(dispatched)
+    }
+
+    async fn publish(&self, id: Uuid, event: &DomainEvent) -> Result<(), String> {
+        let mut errors = vec![];
+
+        for subscriber in self.subscribers.iter() {
+            if let Err(e) = subscriber.handle(id, event).await {
+                errors.push(format!("{}: {}", subscriber.name(), e));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+
+        Ok(())
+    }
+}
+
+/// Leases the next events to this dispatcher and commits, so no lock is held while subscribers
+/// run. Events whose lease expired were left by a dispatcher that stopped, they are taken again
+async fn claim(pool: &Pool) -> Result<Vec<OutboxEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        OutboxEvent,
+        r#"UPDATE events
+        SET locked_until = now() + make_interval(secs => $1)
+        WHERE seq IN (
+            SELECT seq FROM events
+            WHERE
+                dispatched_at IS NULL
+                AND dead_at IS NULL
+                AND (locked_until IS NULL OR locked_until < now())
+            ORDER BY seq
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING seq, id, payload as "payload: Json<DomainEvent>""#,
+        LEASE.as_secs_f64(),
+        BATCH_SIZE
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,5 @@
+pub mod atom;
+pub mod features;
+pub mod json;
+pub mod models;
+pub mod rss;
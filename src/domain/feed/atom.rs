@@ -0,0 +1,95 @@
+use chrono::{SecondsFormat, TimeZone, Utc};
+
+use crate::shared::xml::escape;
+
+use super::models::Feed;
+
+pub const CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+/// Renders the feed as an Atom 1.0 document
+pub fn render(feed: &Feed) -> String {
+    let updated = feed.last_modified().unwrap_or_else(Utc::now);
+    let self_url = escape(&feed.url("atom.xml"));
+
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(&format!(
+        r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>{}</title><id>{}</id><link href="{}"/><link href="{}" rel="self"/><updated>{}</updated>"#,
+        escape(&feed.title),
+        self_url,
+        escape(&feed.home_url()),
+        self_url,
+        updated.to_rfc3339_opts(SecondsFormat::Secs, true),
+    ));
+
+    for item in feed.items.iter() {
+        let created_at = Utc
+            .from_utc_datetime(&item.created_at)
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        let updated_at = Utc
+            .from_utc_datetime(&item.updated_at)
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        xml.push_str(&format!(
+            r#"<entry><title>{}</title><id>urn:uuid:{}</id><link href="{}"/><published>{}</published><updated>{}</updated><summary>{}</summary><content type="html">{}</content><category term="{}"/></entry>"#,
+            escape(&item.title),
+            item.id,
+            escape(&feed.item_url(item)),
+            created_at,
+            updated_at,
+            escape(&item.description),
+            escape(&item.html),
+            escape(&item.category_name),
+        ));
+    }
+
+    xml.push_str("</feed>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::feed::models::{FeedItem, FeedScope};
+
+    use super::*;
+
+    #[test]
+    fn renders_scoped_self_link() {
+        let id = Uuid::nil();
+        let feed = Feed {
+            title: "Blog - Code".into(),
+            address: "http://localhost:3000".into(),
+            scope: FeedScope::Category(id),
+            limit: 20,
+            offset: 0,
+            items: vec![FeedItem {
+                id,
+                slug: "hello".into(),
+                title: "Title".into(),
+                description: "Description".into(),
+                html: "<p>Hi</p>".into(),
+                main_image: None,
+                created_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+                    .unwrap()
+                    .and_hms_opt(3, 4, 5)
+                    .unwrap(),
+                updated_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+                    .unwrap()
+                    .and_hms_opt(3, 4, 5)
+                    .unwrap(),
+                category_name: "Code".into(),
+                tags: vec![],
+            }],
+        };
+
+        let xml = render(&feed);
+
+        assert!(xml.contains(&format!(
+            r#"<link href="http://localhost:3000/categories/{}/atom.xml" rel="self"/>"#,
+            id
+        )));
+        assert!(xml.contains("<updated>2024-01-02T03:04:05Z</updated>"));
+        assert!(xml.contains(&format!("<id>urn:uuid:{}</id>", id)));
+    }
+}
@@ -0,0 +1,95 @@
+use actix_web::web::Data;
+use sqlx::query_scalar;
+
+use crate::{
+    domain::{
+        feed::models::{Feed, FeedItem, FeedScope},
+        server::ServerAddress,
+    },
+    persistence::db::{Pool, Slice},
+    server::service::sync_service,
+};
+
+/// Amount of blogs in the xml feeds, which are not paginated
+pub const FEED_SIZE: i64 = 20;
+
+/// Most blogs a page of the json feed may ask for
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+const FEED_TITLE: &str = "Blog";
+
+sync_service!(GetFeed; pool: Data<Pool>, server_address: Data<ServerAddress>);
+
+impl GetFeed {
+    /// Returns `None` when the category or tag of the scope does not exist
+    pub async fn run(
+        &self,
+        scope: FeedScope,
+        Slice { limit, offset }: Slice,
+    ) -> Result<Option<Feed>, sqlx::Error> {
+        let (category_id, tag_id) = match scope {
+            FeedScope::All => (None, None),
+            FeedScope::Category(id) => (Some(id), None),
+            FeedScope::Tag(id) => (None, Some(id)),
+        };
+
+        let title = match scope {
+            FeedScope::All => FEED_TITLE.to_owned(),
+            FeedScope::Category(id) => {
+                let name = query_scalar!("SELECT name FROM categories WHERE id = $1", id)
+                    .fetch_optional(self.pool.as_ref())
+                    .await?;
+
+                match name {
+                    Some(name) => format!("{} - {}", FEED_TITLE, name),
+                    None => return Ok(None),
+                }
+            }
+            FeedScope::Tag(id) => {
+                let name = query_scalar!("SELECT name FROM tags WHERE id = $1", id)
+                    .fetch_optional(self.pool.as_ref())
+                    .await?;
+
+                match name {
+                    Some(name) => format!("{} - {}", FEED_TITLE, name),
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        let items = sqlx::query_as!(
+            FeedItem,
+            r#"SELECT
+                b.id, b.slug, b.title, b.description, b.html, b.main_image, b.created_at, b.updated_at,
+                c.name as category_name,
+                ARRAY(
+                    SELECT t.name FROM tags_blogs tb JOIN tags t ON t.id = tb.tag_id
+                    WHERE tb.blog_id = b.id ORDER BY t.name
+                ) as "tags!"
+            FROM blogs b
+            JOIN categories c ON c.id = b.category_id
+            WHERE
+                ($1::uuid IS NULL OR b.category_id = $1)
+                AND ($2::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM tags_blogs tb WHERE tb.blog_id = b.id AND tb.tag_id = $2
+                ))
+            ORDER BY b.created_at DESC
+            LIMIT $3 OFFSET $4"#,
+            category_id,
+            tag_id,
+            limit,
+            offset
+        )
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(Some(Feed {
+            title,
+            address: self.server_address.to_string(),
+            scope,
+            limit,
+            offset,
+            items,
+        }))
+    }
+}
@@ -0,0 +1,143 @@
+use chrono::{SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+
+use super::models::Feed;
+
+pub const CONTENT_TYPE: &str = "application/feed+json; charset=utf-8";
+
+const VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    home_page_url: String,
+    feed_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_url: Option<String>,
+    items: Vec<JsonItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonItem<'a> {
+    id: String,
+    url: String,
+    title: &'a str,
+    content_html: &'a str,
+    summary: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<&'a str>,
+    date_published: String,
+    date_modified: String,
+    tags: &'a [String],
+}
+
+/// Renders the feed as a JSON Feed 1.1 document
+pub fn render(feed: &Feed) -> String {
+    let json_feed = JsonFeed {
+        version: VERSION,
+        title: &feed.title,
+        home_page_url: feed.home_url(),
+        feed_url: feed.url("feed.json"),
+        next_url: feed.next_url("feed.json"),
+        items: feed
+            .items
+            .iter()
+            .map(|item| JsonItem {
+                id: item.id.to_string(),
+                url: feed.item_url(item),
+                title: &item.title,
+                content_html: &item.html,
+                summary: &item.description,
+                image: item.main_image.as_deref(),
+                date_published: Utc
+                    .from_utc_datetime(&item.created_at)
+                    .to_rfc3339_opts(SecondsFormat::Secs, true),
+                date_modified: Utc
+                    .from_utc_datetime(&item.updated_at)
+                    .to_rfc3339_opts(SecondsFormat::Secs, true),
+                tags: &item.tags,
+            })
+            .collect(),
+    };
+
+    // Serializing plain data can not fail
+    serde_json::to_string(&json_feed).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::feed::models::{FeedItem, FeedScope};
+
+    use super::*;
+
+    fn feed(limit: i64) -> Feed {
+        Feed {
+            title: "Blog".into(),
+            address: "http://localhost:3000".into(),
+            scope: FeedScope::Tag(Uuid::nil()),
+            limit,
+            offset: 10,
+            items: vec![FeedItem {
+                id: Uuid::nil(),
+                slug: "hello".into(),
+                title: "Title".into(),
+                description: "Description".into(),
+                html: "<p>Hi</p>".into(),
+                main_image: Some("http://localhost:3000/image.png".into()),
+                created_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+                    .unwrap()
+                    .and_hms_opt(3, 4, 5)
+                    .unwrap(),
+                updated_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+                    .unwrap()
+                    .and_hms_opt(3, 4, 5)
+                    .unwrap(),
+                category_name: "Code".into(),
+                tags: vec!["rust".into()],
+            }],
+        }
+    }
+
+    #[test]
+    fn renders_items() {
+        let json: serde_json::Value = serde_json::from_str(&render(&feed(1))).unwrap();
+        let item = &json["items"][0];
+
+        assert_eq!(json["version"], VERSION);
+        assert_eq!(item["content_html"], "<p>Hi</p>");
+        assert_eq!(item["summary"], "Description");
+        assert_eq!(item["image"], "http://localhost:3000/image.png");
+        assert_eq!(item["date_published"], "2024-01-02T03:04:05Z");
+        assert_eq!(item["tags"], serde_json::json!(["rust"]));
+    }
+
+    #[test]
+    fn links_next_page_when_full() {
+        let json: serde_json::Value = serde_json::from_str(&render(&feed(1))).unwrap();
+
+        assert_eq!(
+            json["next_url"],
+            format!(
+                "http://localhost:3000/tags/{}/feed.json?limit=1&offset=11",
+                Uuid::nil()
+            )
+        );
+    }
+
+    #[test]
+    fn omits_next_page_on_last_page() {
+        let json: serde_json::Value = serde_json::from_str(&render(&feed(2))).unwrap();
+
+        assert!(json.get("next_url").is_none());
+    }
+
+    #[test]
+    fn never_links_to_the_same_page() {
+        let json: serde_json::Value = serde_json::from_str(&render(&feed(0))).unwrap();
+
+        assert!(json.get("next_url").is_none());
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::{domain::blog::page::post_url, persistence::db::DateTime};
+
+/// Blogs that end up in a feed
+#[derive(Debug, Clone, Copy)]
+pub enum FeedScope {
+    All,
+    Category(Uuid),
+    Tag(Uuid),
+}
+
+impl FeedScope {
+    /// Path the feeds of the scope are served under, relative to the server address
+    pub fn path(&self) -> String {
+        match self {
+            Self::All => String::new(),
+            Self::Category(id) => format!("/categories/{}", id),
+            Self::Tag(id) => format!("/tags/{}", id),
+        }
+    }
+}
+
+pub struct Feed {
+    pub title: String,
+    /// Server address the links are built from
+    pub address: String,
+    pub scope: FeedScope,
+    pub limit: i64,
+    pub offset: i64,
+    /// Newest first
+    pub items: Vec<FeedItem>,
+}
+
+pub struct FeedItem {
+    pub id: Uuid,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub html: String,
+    pub main_image: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+    pub category_name: String,
+    pub tags: Vec<String>,
+}
+
+impl Feed {
+    pub fn home_url(&self) -> String {
+        format!("{}/blogs/", self.address)
+    }
+
+    /// Absolute url of the `file` feed of this scope, like `feed.xml`
+    pub fn url(&self, file: &str) -> String {
+        format!("{}{}/{}", self.address, self.scope.path(), file)
+    }
+
+    /// Url of the page after this one, `None` on the last page
+    pub fn next_url(&self, file: &str) -> Option<String> {
+        if self.limit <= 0 || (self.items.len() as i64) < self.limit {
+            return None;
+        }
+
+        Some(format!(
+            "{}?limit={}&offset={}",
+            self.url(file),
+            self.limit,
+            self.offset + self.limit
+        ))
+    }
+
+    pub fn item_url(&self, item: &FeedItem) -> String {
+        post_url(&self.address, &item.slug)
+    }
+
+    /// Date of the latest change of an item, or `None` for empty feeds
+    pub fn last_modified(&self) -> Option<chrono::DateTime<Utc>> {
+        self.items
+            .iter()
+            .map(|item| item.updated_at)
+            .max()
+            .map(|date| Utc.from_utc_datetime(&date))
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::shared::xml::escape;
+
+use super::models::Feed;
+
+pub const CONTENT_TYPE: &str = "application/rss+xml; charset=utf-8";
+
+/// Renders the feed as an RSS 2.0 document
+pub fn render(feed: &Feed) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(
+        r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:content="http://purl.org/rss/1.0/modules/content/"><channel>"#,
+    );
+
+    xml.push_str(&format!(
+        r#"<title>{}</title><link>{}</link><description>{}</description><atom:link href="{}" rel="self" type="application/rss+xml"/>"#,
+        escape(&feed.title),
+        escape(&feed.home_url()),
+        escape(&feed.title),
+        escape(&feed.url("feed.xml")),
+    ));
+
+    if let Some(last_modified) = feed.last_modified() {
+        xml.push_str(&format!(
+            "<lastBuildDate>{}</lastBuildDate>",
+            rfc2822(&last_modified)
+        ));
+    }
+
+    for item in feed.items.iter() {
+        let url = escape(&feed.item_url(item));
+
+        xml.push_str(&format!(
+            r#"<item><title>{}</title><link>{}</link><guid isPermaLink="true">{}</guid><description>{}</description><content:encoded>{}</content:encoded><category>{}</category><pubDate>{}</pubDate></item>"#,
+            escape(&item.title),
+            url,
+            url,
+            escape(&item.description),
+            escape(&item.html),
+            escape(&item.category_name),
+            rfc2822(&Utc.from_utc_datetime(&item.created_at)),
+        ));
+    }
+
+    xml.push_str("</channel></rss>");
+    xml
+}
+
+/// Days are zero padded, which `to_rfc2822` stopped doing
+fn rfc2822(time: &DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S %z").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::feed::models::{FeedItem, FeedScope};
+
+    use super::*;
+
+    #[test]
+    fn renders_items_with_absolute_links() {
+        let id = Uuid::nil();
+        let feed = Feed {
+            title: "Blog".into(),
+            address: "http://localhost:3000".into(),
+            scope: FeedScope::All,
+            limit: 20,
+            offset: 0,
+            items: vec![FeedItem {
+                id,
+                slug: "hello".into(),
+                title: "Rust & actix".into(),
+                description: "About <rust>".into(),
+                html: "<p>Hi</p>".into(),
+                main_image: None,
+                created_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+                    .unwrap()
+                    .and_hms_opt(3, 4, 5)
+                    .unwrap(),
+                updated_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+                    .unwrap()
+                    .and_hms_opt(3, 4, 5)
+                    .unwrap(),
+                category_name: "Code".into(),
+                tags: vec![],
+            }],
+        };
+
+        let xml = render(&feed);
+
+        assert!(xml.contains("<link>http://localhost:3000/posts/hello/</link>"));
+        assert!(xml.contains("<title>Rust &amp; actix</title>"));
+        assert!(xml.contains("<content:encoded>&lt;p&gt;Hi&lt;/p&gt;</content:encoded>"));
+        assert!(xml.contains("<pubDate>Tue, 02 Jan 2024 03:04:05 +0000</pubDate>"));
+        assert!(xml.contains(r#"href="http://localhost:3000/feed.xml""#));
+    }
+}
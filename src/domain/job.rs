@@ -0,0 +1,15 @@
+pub mod features;
+pub mod models;
+mod progress;
+pub mod runner;
+
+pub use models::*;
+pub use progress::JobProgress;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Data a job of a given kind is enqueued with
+pub trait JobPayload: Serialize + DeserializeOwned + Send + Sync {
+    const KIND: JobKind;
+    const MAX_ATTEMPTS: i32 = 3;
+}
@@ -0,0 +1,2 @@
+pub mod enqueue;
+pub mod get_by_id;
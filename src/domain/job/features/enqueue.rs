@@ -0,0 +1,35 @@
+use actix_web::web::Data;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::{
+    domain::job::{JobKind, JobPayload},
+    persistence::db::{entities::IdSelect, Executor, Pool},
+    server::service::sync_service,
+};
+
+sync_service!(EnqueueJob; pool: Data<Pool>);
+
+impl EnqueueJob {
+    pub async fn run<P: JobPayload>(&self, payload: &P) -> Result<Uuid, sqlx::Error> {
+        enqueue(self.pool.as_ref(), payload).await
+    }
+}
+
+/// Enqueues a job with any executor, so it can be part of a bigger transaction
+pub async fn enqueue<P: JobPayload>(
+    executor: impl Executor<'_>,
+    payload: &P,
+) -> Result<Uuid, sqlx::Error> {
+    let IdSelect { id } = sqlx::query_as!(
+        IdSelect,
+        "INSERT INTO jobs (kind, payload, max_attempts) VALUES ($1, $2, $3) RETURNING id",
+        P::KIND as JobKind,
+        Json(payload) as _,
+        P::MAX_ATTEMPTS
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
@@ -0,0 +1,71 @@
+use actix_web::web::Data;
+use tokio::join;
+use uuid::Uuid;
+
+use crate::{
+    domain::job::{JobById, JobItemError, JobKind, JobStatus, Progress},
+    persistence::db::{DateTime, Pool},
+    server::service::sync_service,
+};
+
+sync_service!(GetJobById; pool: Data<Pool>);
+
+struct RawJob {
+    id: Uuid,
+    kind: JobKind,
+    status: JobStatus,
+    attempts: i32,
+    max_attempts: i32,
+    progress: i32,
+    total: Option<i32>,
+    result: Option<serde_json::Value>,
+    last_error: Option<String>,
+    created_at: DateTime,
+    started_at: Option<DateTime>,
+    finished_at: Option<DateTime>,
+}
+
+impl GetJobById {
+    pub async fn run(&self, id: Uuid) -> Result<Option<JobById>, sqlx::Error> {
+        let job = sqlx::query_as!(
+            RawJob,
+            r#"SELECT
+                id, kind as "kind: JobKind", status as "status: JobStatus", attempts, max_attempts,
+                progress, total, result, last_error, created_at, started_at, finished_at
+            FROM jobs WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(self.pool.as_ref());
+
+        let errors = sqlx::query_as!(
+            JobItemError,
+            "SELECT item, message, created_at FROM job_item_errors WHERE job_id = $1 ORDER BY created_at",
+            id
+        )
+        .fetch_all(self.pool.as_ref());
+
+        let (job, errors) = join!(job, errors);
+
+        let Some(job) = job? else {
+            return Ok(None);
+        };
+
+        Ok(Some(JobById {
+            id: job.id,
+            kind: job.kind,
+            status: job.status,
+            attempts: job.attempts,
+            max_attempts: job.max_attempts,
+            progress: Progress {
+                done: job.progress,
+                total: job.total,
+            },
+            result: job.result,
+            last_error: job.last_error,
+            errors: errors?,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            finished_at: job.finished_at,
+        }))
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::persistence::db::DateTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "job_kind", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    RecompileMarkdowns,
+    DeliverWebhook,
+    CollectOrphanedImages,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "job_status", rename_all = "lowercase")]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobItemError {
+    pub item: String,
+    pub message: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Progress {
+    pub done: i32,
+    pub total: Option<i32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobById {
+    pub id: Uuid,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub progress: Progress,
+    pub result: Option<serde_json::Value>,
+    pub last_error: Option<String>,
+    pub errors: Vec<JobItemError>,
+    pub created_at: DateTime,
+    pub started_at: Option<DateTime>,
+    pub finished_at: Option<DateTime>,
+}
@@ -0,0 +1,54 @@
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::persistence::db::Pool;
+
+/// Lets a running job report how far it is and which items failed
+#[derive(Clone)]
+pub struct JobProgress {
+    pool: Data<Pool>,
+    job_id: Uuid,
+}
+
+impl JobProgress {
+    pub fn new(pool: Data<Pool>, job_id: Uuid) -> Self {
+        Self { pool, job_id }
+    }
+
+    pub async fn set_total(&self, total: i32) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE jobs SET total = $1 WHERE id = $2",
+            total,
+            self.job_id
+        )
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks one more item as done, it also extends the lock of the job as it is still alive
+    pub async fn step(&self) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE jobs SET progress = progress + 1, locked_until = now() + interval '10 minutes' WHERE id = $1",
+            self.job_id
+        )
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn item_error(&self, item: &str, message: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO job_item_errors (job_id, item, message) VALUES ($1, $2, $3)",
+            self.job_id,
+            item,
+            message
+        )
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use actix_web::web::Data;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog::{
+            features::{
+                collect_orphaned_images::{self, CollectOrphanedImages},
+                recompile_markdowns::{self, RecompileMarkdowns},
+                set_content::SetContent,
+            },
+            ImgHostInjectorFactory,
+        },
+        job::{JobKind, JobProgress, JobStatus},
+        read_cache::ReadCache,
+        server::ServerAddress,
+        webhook::delivery,
+    },
+    persistence::{blobs::BlobStore, db::Pool},
+};
+
+/// How long an idle worker waits before looking for new jobs
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+const BACKOFF_BASE_SECS: f64 = 30.0;
+const BACKOFF_MAX_SECS: f64 = 60.0 * 60.0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid payload: {0}")]
+    Payload(#[from] serde_json::Error),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("{0}")]
+    Webhook(#[from] delivery::Error),
+    #[error("{0}")]
+    CollectOrphanedImages(#[from] collect_orphaned_images::Error),
+    #[error("job was abandoned too many times")]
+    Abandoned,
+}
+
+struct ClaimedJob {
+    id: Uuid,
+    kind: JobKind,
+    payload: serde_json::Value,
+    attempts: i32,
+    max_attempts: i32,
+}
+
+/// Executes the jobs stored in the `jobs` table outside of the requests that enqueued them
+#[derive(Clone)]
+pub struct Runner {
+    pool: Data<Pool>,
+    server_address: Data<ServerAddress>,
+    cache: Data<ReadCache>,
+    blobs: Data<dyn BlobStore>,
+}
+
+impl Runner {
+    pub fn new(
+        pool: Data<Pool>,
+        server_address: Data<ServerAddress>,
+        cache: Data<ReadCache>,
+        blobs: Data<dyn BlobStore>,
+    ) -> Self {
+        Self {
+            pool,
+            server_address,
+            cache,
+            blobs,
+        }
+    }
+
+    /// Spawns `workers` tasks that poll the queue for as long as the server lives
+    pub fn spawn(self, workers: usize) {
+        for _ in 0..workers {
+            actix_web::rt::spawn(self.clone().work());
+        }
+    }
+
+    async fn work(self) {
+        loop {
+            match claim(self.pool.as_ref()).await {
+                Ok(Some(job)) => self.execute(job).await,
+                Ok(None) => actix_web::rt::time::sleep(POLL_INTERVAL).await,
+                Err(e) => {
+                    eprintln!("Could not poll jobs: {:?}", e);
+                    actix_web::rt::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn execute(&self, job: ClaimedJob) {
+        let result = if job.attempts > job.max_attempts {
+            Err(Error::Abandoned)
+        } else {
+            self.dispatch(&job).await
+        };
+
+        let result = match result {
+            Ok(value) => complete(self.pool.as_ref(), job.id, value).await,
+            Err(e) => fail(self.pool.as_ref(), &job, &e.to_string()).await,
+        };
+
+        if let Err(e) = result {
+            eprintln!("Could not store the result of job {}: {:?}", job.id, e);
+        }
+    }
+
+    async fn dispatch(&self, job: &ClaimedJob) -> Result<serde_json::Value, Error> {
+        let progress = JobProgress::new(self.pool.clone(), job.id);
+
+        match job.kind {
+            JobKind::RecompileMarkdowns => {
+                let recompile_markdowns::Payload { dry_run } =
+                    serde_json::from_value(job.payload.clone())?;
+
+                let injector_factory =
+                    ImgHostInjectorFactory::new(self.server_address.clone(), self.blobs.clone());
+                let recompile = RecompileMarkdowns::new(
+                    self.pool.clone(),
+                    SetContent::new(
+                        self.pool.clone(),
+                        injector_factory.clone(),
+                        self.cache.clone(),
+                    ),
+                    injector_factory,
+                );
+
+                let report = recompile.run(dry_run, progress).await?;
+                Ok(serde_json::to_value(report)?)
+            }
+            JobKind::DeliverWebhook => {
+                let delivery::Payload { delivery_id } =
+                    serde_json::from_value(job.payload.clone())?;
+
+                let is_last_attempt = job.attempts >= job.max_attempts;
+                delivery::deliver(self.pool.as_ref(), delivery_id, is_last_attempt).await?;
+
+                Ok(serde_json::Value::Null)
+            }
+            JobKind::CollectOrphanedImages => {
+                let collect_orphaned_images::Payload {
+                    dry_run,
+                    grace_secs,
+                } = serde_json::from_value(job.payload.clone())?;
+
+                let collect = CollectOrphanedImages::new(self.pool.clone(), self.blobs.clone());
+
+                let report = collect
+                    .run(dry_run, Duration::from_secs(grace_secs), progress)
+                    .await?;
+                Ok(serde_json::to_value(report)?)
+            }
+        }
+    }
+}
+
+/// Takes the next job to run, starting its progress over when it is a retry so its report
+/// only tells about this attempt
+async fn claim(pool: &Pool) -> Result<Option<ClaimedJob>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let job = sqlx::query_as!(
+        ClaimedJob,
+        r#"UPDATE jobs
+            SET
+                status = 'running',
+                attempts = attempts + 1,
+                progress = 0,
+                total = NULL,
+                started_at = now(),
+                locked_until = now() + interval '10 minutes'
+            WHERE id = (
+                SELECT id FROM jobs
+                WHERE
+                    (status = 'pending' AND run_at <= now())
+                    OR (status = 'running' AND locked_until < now())
+                ORDER BY run_at
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING id, kind as "kind: JobKind", payload, attempts, max_attempts"#
+    )
+    .fetch_optional(&mut tx)
+    .await?;
+
+    if let Some(job) = &job {
+        sqlx::query!("DELETE FROM job_item_errors WHERE job_id = $1", job.id)
+            .execute(&mut tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(job)
+}
+
+async fn complete(pool: &Pool, id: Uuid, result: serde_json::Value) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"UPDATE jobs
+            SET status = 'succeeded', result = $1, last_error = NULL, locked_until = NULL, finished_at = now()
+            WHERE id = $2"#,
+        Json(result) as _,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Schedules a retry of the job, or marks it as failed once it run out of attempts
+async fn fail(pool: &Pool, job: &ClaimedJob, error: &str) -> Result<(), sqlx::Error> {
+    let status = if job.attempts < job.max_attempts {
+        JobStatus::Pending
+    } else {
+        JobStatus::Failed
+    };
+
+    sqlx::query!(
+        r#"UPDATE jobs
+            SET
+                status = $1,
+                last_error = $2,
+                run_at = now() + make_interval(secs => $3),
+                locked_until = NULL,
+                finished_at = CASE WHEN $1 = 'failed'::job_status THEN now() END
+            WHERE id = $4"#,
+        status as JobStatus,
+        error,
+        backoff_secs(job.attempts),
+        job.id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Seconds to wait before retrying a job that failed `attempts` times
+fn backoff_secs(attempts: i32) -> f64 {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16);
+    (BACKOFF_BASE_SECS * 2f64.powi(exponent)).min(BACKOFF_MAX_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles_backoff_on_each_attempt() {
+        assert_eq!(backoff_secs(1), BACKOFF_BASE_SECS);
+        assert_eq!(backoff_secs(2), BACKOFF_BASE_SECS * 2.0);
+        assert_eq!(backoff_secs(3), BACKOFF_BASE_SECS * 4.0);
+    }
+
+    #[test]
+    fn caps_backoff() {
+        assert_eq!(backoff_secs(100), BACKOFF_MAX_SECS);
+    }
+}
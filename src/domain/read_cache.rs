@@ -0,0 +1,238 @@
+mod lru;
+
+use std::{sync::Arc, time::Duration};
+
+use actix_web::web::{Data, ServiceConfig};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog::{
+            features::{get_all::BlogPreview, get_by_id::BlogById},
+            include::Include,
+        },
+        blog_grouping::{category::Category, sub_category::SubCategory, tag::Tag},
+    },
+    persistence::db::{notify, Transaction, Version},
+    server::AppConfig,
+};
+
+pub use lru::{Lru, Stats};
+
+/// Channel every instance listens to for the changes made by the others
+pub const CHANNEL: &str = "read_cache";
+
+/// What changed, so the entries built from it are evicted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Invalidation {
+    /// A blog changed without leaving or joining any listing, like its tags
+    BlogChanged {
+        blog_id: Uuid,
+    },
+    /// A blog was created, removed or changed its title, which moves it through the listings
+    BlogMoved {
+        blog_id: Uuid,
+    },
+    CommentsChanged {
+        blog_id: Uuid,
+    },
+    /// Replies only show on the blogs listing their comment
+    RepliesChanged {
+        comment_id: Uuid,
+    },
+    CategoriesChanged,
+    SubCategoriesChanged {
+        category_id: Uuid,
+    },
+    TagsChanged {
+        category_id: Uuid,
+    },
+    /// A category, sub category or tag was removed, unlinking it from any blog by cascade
+    GroupingRemoved,
+}
+
+/// Value along with the version of the rows it was loaded from
+pub struct Versioned<T> {
+    pub version: Version,
+    pub value: T,
+}
+
+/// Blog with what it includes
+pub type BlogKey = (Uuid, Include);
+
+/// Listing of blogs as requested, `(limit, offset, search, include)`
+pub type ListingKey = (i64, i64, String, Include);
+
+/// In process cache of the hottest reads, every feature changing their rows invalidates the
+/// entries it touches
+pub struct ReadCache {
+    pub blogs: Lru<BlogKey, Arc<Versioned<BlogById>>>,
+    pub listings: Lru<ListingKey, Arc<Vec<BlogPreview>>>,
+    pub categories: Lru<(), Arc<Vec<Category>>>,
+    /// Sub categories by category
+    pub sub_categories: Lru<Uuid, Arc<Vec<SubCategory>>>,
+    /// Tags by category
+    pub tags: Lru<Uuid, Arc<Vec<Tag>>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadCacheStats {
+    pub blogs: Stats,
+    pub listings: Stats,
+    pub categories: Stats,
+    pub sub_categories: Stats,
+    pub tags: Stats,
+}
+
+impl ReadCache {
+    /// `capacity` bounds each of the caches
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            blogs: Lru::new(capacity, ttl),
+            listings: Lru::new(capacity, ttl),
+            categories: Lru::new(1, ttl),
+            sub_categories: Lru::new(capacity, ttl),
+            tags: Lru::new(capacity, ttl),
+        }
+    }
+
+    /// Commits a change along with its notification, so the other instances hear of it exactly
+    /// when it commits, or the change fails. The entries it touched here are evicted right away
+    pub async fn commit(
+        &self,
+        mut tx: Transaction<'_>,
+        invalidation: Invalidation,
+    ) -> Result<(), sqlx::Error> {
+        // Serializing plain data can not fail
+        let payload = serde_json::to_string(&invalidation).unwrap();
+        notify::notify(&mut *tx, CHANNEL, &payload).await?;
+
+        tx.commit().await?;
+
+        self.apply(&invalidation);
+
+        Ok(())
+    }
+
+    fn apply(&self, invalidation: &Invalidation) {
+        match *invalidation {
+            Invalidation::BlogChanged { blog_id } => {
+                self.remove_blog(blog_id);
+                self.listings
+                    .retain(|_, listing| listing.iter().all(|blog| blog.id != blog_id));
+            }
+            Invalidation::BlogMoved { blog_id } => {
+                self.remove_blog(blog_id);
+                self.listings.clear();
+            }
+            Invalidation::CommentsChanged { blog_id } => self
+                .blogs
+                .retain(|&(id, include), _| id != blog_id || !include.comments),
+            Invalidation::RepliesChanged { comment_id } => self.blogs.retain(|_, blog| {
+                blog.value
+                    .comments
+                    .iter()
+                    .flatten()
+                    .all(|comment| comment.id != comment_id)
+            }),
+            Invalidation::CategoriesChanged => self.categories.clear(),
+            Invalidation::SubCategoriesChanged { category_id } => {
+                self.sub_categories.remove(&category_id)
+            }
+            Invalidation::TagsChanged { category_id } => self.tags.remove(&category_id),
+            Invalidation::GroupingRemoved => self.clear(),
+        }
+    }
+
+    /// Every include of the blog
+    fn remove_blog(&self, blog_id: Uuid) {
+        self.blogs.retain(|&(id, _), _| id != blog_id);
+    }
+
+    fn clear(&self) {
+        self.blogs.clear();
+        self.listings.clear();
+        self.categories.clear();
+        self.sub_categories.clear();
+        self.tags.clear();
+    }
+
+    pub fn stats(&self) -> ReadCacheStats {
+        ReadCacheStats {
+            blogs: self.blogs.stats(),
+            listings: self.listings.stats(),
+            categories: self.categories.stats(),
+            sub_categories: self.sub_categories.stats(),
+            tags: self.tags.stats(),
+        }
+    }
+}
+
+impl notify::Handler for ReadCache {
+    fn notified(&self, payload: &str) {
+        match serde_json::from_str(payload) {
+            Ok(invalidation) => self.apply(&invalidation),
+            Err(e) => {
+                eprintln!("Unknown invalidation {}, flushing: {}", payload, e);
+                self.clear();
+            }
+        }
+    }
+
+    fn missed(&self) {
+        self.clear();
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    cache: Data<ReadCache>,
+}
+
+impl Config {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            cache: Data::new(ReadCache::new(capacity, ttl)),
+        }
+    }
+
+    pub fn cache(&self) -> Data<ReadCache> {
+        self.cache.clone()
+    }
+}
+
+impl AppConfig for Config {
+    fn configure(self, config: &mut ServiceConfig) {
+        config.app_data(self.cache);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidations_are_camel_case_json() {
+        let invalidation = Invalidation::SubCategoriesChanged {
+            category_id: Uuid::nil(),
+        };
+
+        let payload = serde_json::to_string(&invalidation).unwrap();
+
+        assert_eq!(
+            payload,
+            r#"{"type":"subCategoriesChanged","categoryId":"00000000-0000-0000-0000-000000000000"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Invalidation>(&payload).unwrap(),
+            invalidation
+        );
+    }
+}
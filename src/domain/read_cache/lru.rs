@@ -0,0 +1,250 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Counters of a cache since the server started
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+}
+
+struct Entry<V> {
+    value: V,
+    stored_at: Instant,
+    /// Position in the recency order, higher is more recent
+    used_at: u64,
+}
+
+struct Inner<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    recency: BTreeMap<u64, K>,
+    clock: u64,
+    /// Bumped on every invalidation, so loads started before one are not stored
+    epoch: u64,
+    stats: Stats,
+}
+
+/// Map bounded by `capacity`, dropping the least recently used entry when full, whose entries
+/// expire `ttl` after being stored
+pub struct Lru<K, V> {
+    capacity: usize,
+    ttl: Duration,
+    inner: Mutex<Inner<K, V>>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Lru<K, V> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            inner: Mutex::new(Inner {
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+                clock: 0,
+                epoch: 0,
+                stats: Stats::default(),
+            }),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let Some(entry) = inner.entries.get_mut(key) else {
+            inner.stats.misses += 1;
+            return None;
+        };
+
+        if entry.stored_at.elapsed() >= self.ttl {
+            let used_at = entry.used_at;
+            inner.entries.remove(key);
+            inner.recency.remove(&used_at);
+            inner.stats.misses += 1;
+            return None;
+        }
+
+        inner.recency.remove(&entry.used_at);
+        inner.recency.insert(clock, key.clone());
+        entry.used_at = clock;
+        inner.stats.hits += 1;
+
+        Some(entry.value.clone())
+    }
+
+    /// Taken before loading a value, to be given back to [`Lru::insert`]
+    pub fn epoch(&self) -> u64 {
+        self.inner.lock().unwrap().epoch
+    }
+
+    /// Stores the value unless the cache was invalidated since `epoch`, as it could be loaded
+    /// from data changed afterwards
+    pub fn insert(&self, key: K, value: V, epoch: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.epoch != epoch {
+            return;
+        }
+
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let previous = inner.entries.insert(
+            key.clone(),
+            Entry {
+                value,
+                stored_at: Instant::now(),
+                used_at: clock,
+            },
+        );
+
+        if let Some(previous) = previous {
+            inner.recency.remove(&previous.used_at);
+        }
+        inner.recency.insert(clock, key);
+
+        while inner.entries.len() > self.capacity {
+            let Some((_, oldest)) = inner.recency.pop_first() else {
+                break;
+            };
+
+            inner.entries.remove(&oldest);
+            inner.stats.evictions += 1;
+        }
+    }
+
+    pub fn remove(&self, key: &K) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.epoch += 1;
+
+        if let Some(entry) = inner.entries.remove(key) {
+            inner.recency.remove(&entry.used_at);
+        }
+    }
+
+    /// Keeps only the entries for which `keep` returns `true`
+    pub fn retain(&self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        inner.epoch += 1;
+
+        let recency = &mut inner.recency;
+        inner.entries.retain(|key, entry| {
+            let kept = keep(key, &entry.value);
+            if !kept {
+                recency.remove(&entry.used_at);
+            }
+
+            kept
+        });
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.epoch += 1;
+        inner.entries.clear();
+        inner.recency.clear();
+    }
+
+    pub fn stats(&self) -> Stats {
+        let inner = self.inner.lock().unwrap();
+
+        Stats {
+            entries: inner.entries.len(),
+            ..inner.stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = Lru::new(2, TTL);
+
+        cache.insert(1, "a", 0);
+        cache.insert(2, "b", 0);
+        cache.get(&1);
+        cache.insert(3, "c", 0);
+
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some("c"));
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn expires_after_ttl() {
+        let cache = Lru::new(2, Duration::ZERO);
+
+        cache.insert(1, "a", 0);
+
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn skips_values_loaded_before_invalidation() {
+        let cache = Lru::new(2, TTL);
+
+        let epoch = cache.epoch();
+        cache.remove(&1);
+        cache.insert(1, "stale", epoch);
+
+        assert_eq!(cache.get(&1), None);
+
+        cache.insert(1, "fresh", cache.epoch());
+
+        assert_eq!(cache.get(&1), Some("fresh"));
+    }
+
+    #[test]
+    fn retains_matching_entries() {
+        let cache = Lru::new(3, TTL);
+
+        cache.insert(1, "a", 0);
+        cache.insert(2, "b", 0);
+        cache.retain(|_, value| *value != "a");
+
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), Some("b"));
+    }
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let cache = Lru::new(2, TTL);
+
+        cache.get(&1);
+        cache.insert(1, "a", 0);
+        cache.get(&1);
+        cache.get(&1);
+
+        assert_eq!(
+            cache.stats(),
+            Stats {
+                hits: 2,
+                misses: 1,
+                evictions: 0,
+                entries: 1,
+            }
+        );
+    }
+}
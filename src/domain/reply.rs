@@ -21,7 +21,10 @@ mod models {
 }
 
 mod db {
-    use crate::persistence::db::{entities::IdSelect, Pool, Slice};
+    use crate::{
+        domain::event::{self, DomainEvent},
+        persistence::db::{entities::IdSelect, Pool, Slice, Transaction},
+    };
     use sqlx::query_as;
     use uuid::Uuid;
 
@@ -77,14 +80,15 @@ mod db {
         .await
     }
 
+    /// Creates the reply within `tx`, left for the caller to commit
     pub async fn create(
-        pool: &Pool,
+        tx: &mut Transaction<'_>,
         content: &str,
         account_id: Uuid,
         comment_id: Uuid,
         parent_id: Option<Uuid>,
     ) -> Result<IdSelect, sqlx::Error> {
-        query_as!(
+        let reply = query_as!(
             IdSelect,
             "INSERT INTO replies (content, account_id, comment_id, parent_id) \
             VALUES ($1, $2, $3, $4) RETURNING id",
@@ -93,7 +97,17 @@ mod db {
             comment_id,
             parent_id,
         )
-        .fetch_one(pool)
-        .await
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let event = DomainEvent::ReplyCreated {
+            reply_id: reply.id,
+            comment_id,
+            parent_id,
+            account_id,
+        };
+        event::record(tx, &event).await?;
+
+        Ok(reply)
     }
 }
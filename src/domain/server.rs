@@ -46,6 +46,10 @@ mod config {
                 address: Data::from(protocol),
             }
         }
+
+        pub fn address(&self) -> Data<Address> {
+            self.address.clone()
+        }
     }
 
     impl AppConfig for Config {
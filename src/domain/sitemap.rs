@@ -0,0 +1,54 @@
+pub mod features;
+pub mod render;
+mod robots;
+
+pub use robots::{Config, RobotsTxt};
+
+use crate::{domain::blog::page::post_url, persistence::db::DateTime};
+
+/// Most urls a single sitemap may list
+pub const MAX_URLS: i64 = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageKind {
+    Blog,
+    Category,
+    Tag,
+}
+
+pub struct SitemapPage {
+    pub kind: PageKind,
+    /// Slug of blogs, id of categories and tags
+    pub key: String,
+    /// When the blog, category or tag itself was last updated
+    pub lastmod: DateTime,
+}
+
+impl SitemapPage {
+    pub fn url(&self, address: &str) -> String {
+        let path = match self.kind {
+            PageKind::Blog => return post_url(address, &self.key),
+            PageKind::Category => "categories",
+            PageKind::Tag => "tags",
+        };
+
+        format!("{}/{}/{}/", address, path, self.key)
+    }
+}
+
+/// Amount of sitemaps needed to list `total` urls
+pub fn sitemap_count(total: i64) -> i64 {
+    ((total + MAX_URLS - 1) / MAX_URLS).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_sitemaps() {
+        assert_eq!(sitemap_count(0), 1);
+        assert_eq!(sitemap_count(MAX_URLS), 1);
+        assert_eq!(sitemap_count(MAX_URLS + 1), 2);
+    }
+}
@@ -0,0 +1,86 @@
+use actix_web::web::Data;
+
+use crate::{
+    domain::{
+        server::ServerAddress,
+        sitemap::{render, sitemap_count, PageKind, SitemapPage, MAX_URLS},
+    },
+    persistence::db::{DateTime, Pool},
+    server::service::sync_service,
+};
+
+sync_service!(GetSitemap; pool: Data<Pool>, server_address: Data<ServerAddress>);
+
+struct RawPage {
+    kind: i32,
+    key: String,
+    lastmod: DateTime,
+}
+
+impl GetSitemap {
+    /// The whole sitemap while it fits in a single one, an index of the numbered sitemaps
+    /// otherwise
+    pub async fn root(&self) -> Result<String, sqlx::Error> {
+        let count = sitemap_count(self.count().await?);
+
+        if count > 1 {
+            return Ok(render::index(&self.server_address.to_string(), count));
+        }
+
+        self.pages(0).await
+    }
+
+    /// Renders the `page`th sitemap of the index, numbered from 1, `None` if it does not exist
+    pub async fn page(&self, page: i64) -> Result<Option<String>, sqlx::Error> {
+        let count = sitemap_count(self.count().await?);
+
+        if page < 1 || page > count {
+            return Ok(None);
+        }
+
+        self.pages((page - 1) * MAX_URLS).await.map(Some)
+    }
+
+    async fn count(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT
+                (SELECT COUNT(*) FROM blogs)
+                + (SELECT COUNT(*) FROM categories)
+                + (SELECT COUNT(*) FROM tags) as "count!""#
+        )
+        .fetch_one(self.pool.as_ref())
+        .await
+    }
+
+    async fn pages(&self, offset: i64) -> Result<String, sqlx::Error> {
+        let pages = sqlx::query_as!(
+            RawPage,
+            r#"SELECT kind as "kind!", key as "key!", lastmod as "lastmod!" FROM (
+                SELECT 0 as kind, slug as key, updated_at as lastmod FROM blogs
+                UNION ALL
+                SELECT 1, id::text, updated_at FROM categories
+                UNION ALL
+                SELECT 2, id::text, updated_at FROM tags
+            ) pages
+            ORDER BY kind, key
+            LIMIT $1 OFFSET $2"#,
+            MAX_URLS,
+            offset
+        )
+        .fetch_all(self.pool.as_ref())
+        .await?
+        .into_iter()
+        .map(|page| SitemapPage {
+            kind: match page.kind {
+                0 => PageKind::Blog,
+                1 => PageKind::Category,
+                _ => PageKind::Tag,
+            },
+            key: page.key,
+            lastmod: page.lastmod,
+        })
+        .collect::<Vec<_>>();
+
+        Ok(render::urlset(&self.server_address.to_string(), &pages))
+    }
+}
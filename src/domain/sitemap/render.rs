@@ -0,0 +1,89 @@
+use chrono::{SecondsFormat, TimeZone, Utc};
+
+use crate::shared::xml::escape;
+
+use super::SitemapPage;
+
+pub const CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+const NAMESPACE: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+
+/// Renders a `urlset` listing every page
+pub fn urlset(address: &str, pages: &[SitemapPage]) -> String {
+    let mut xml = format!(r#"{}<urlset xmlns="{}">"#, XML_HEADER, NAMESPACE);
+
+    for page in pages {
+        xml.push_str(&format!(
+            "<url><loc>{}</loc><lastmod>{}</lastmod></url>",
+            escape(&page.url(address)),
+            Utc.from_utc_datetime(&page.lastmod)
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+        ));
+    }
+
+    xml.push_str("</urlset>");
+    xml
+}
+
+/// Renders a `sitemapindex` pointing at `count` sitemaps, numbered from 1
+pub fn index(address: &str, count: i64) -> String {
+    let mut xml = format!(r#"{}<sitemapindex xmlns="{}">"#, XML_HEADER, NAMESPACE);
+
+    for page in 1..=count {
+        xml.push_str(&format!(
+            "<sitemap><loc>{}</loc></sitemap>",
+            escape(&format!("{}/sitemaps/{}.xml", address, page))
+        ));
+    }
+
+    xml.push_str("</sitemapindex>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::sitemap::PageKind;
+
+    use super::*;
+
+    #[test]
+    fn renders_urls_with_lastmod() {
+        let lastmod = chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_opt(3, 4, 5)
+            .unwrap();
+        let pages = [
+            SitemapPage {
+                kind: PageKind::Blog,
+                key: "hello".into(),
+                lastmod,
+            },
+            SitemapPage {
+                kind: PageKind::Tag,
+                key: Uuid::nil().to_string(),
+                lastmod,
+            },
+        ];
+
+        let xml = urlset("http://localhost:3000", &pages);
+
+        assert!(xml.contains(
+            "<url><loc>http://localhost:3000/posts/hello/</loc><lastmod>2024-01-02T03:04:05Z</lastmod></url>"
+        ));
+        assert!(xml.contains(&format!(
+            "<url><loc>http://localhost:3000/tags/{}/</loc><lastmod>2024-01-02T03:04:05Z</lastmod></url>",
+            Uuid::nil()
+        )));
+    }
+
+    #[test]
+    fn renders_index() {
+        let xml = index("http://localhost:3000", 2);
+
+        assert!(xml.contains("<loc>http://localhost:3000/sitemaps/1.xml</loc>"));
+        assert!(xml.contains("<loc>http://localhost:3000/sitemaps/2.xml</loc>"));
+    }
+}
@@ -0,0 +1,57 @@
+use actix_web::web::{Data, ServiceConfig};
+
+use crate::{domain::server::ServerAddress, server::AppConfig};
+
+/// Rules used when no robots.txt is configured
+const DEFAULT_RULES: &str = "User-agent: *\nAllow: /\n";
+
+/// Contents of `/robots.txt`, the configured rules followed by the sitemap location
+pub struct RobotsTxt(String);
+
+impl RobotsTxt {
+    pub fn new(rules: Option<&str>, address: &ServerAddress) -> Self {
+        let rules = rules.unwrap_or(DEFAULT_RULES).trim_end();
+        Self(format!("{}\n\nSitemap: {}/sitemap.xml\n", rules, address))
+    }
+}
+
+impl AsRef<str> for RobotsTxt {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    robots: Data<RobotsTxt>,
+}
+
+impl Config {
+    pub fn new(robots: RobotsTxt) -> Self {
+        Self {
+            robots: Data::new(robots),
+        }
+    }
+}
+
+impl AppConfig for Config {
+    fn configure(self, config: &mut ServiceConfig) {
+        config.app_data(self.robots);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_at_sitemap() {
+        let address = ServerAddress::new_arc("http://localhost:3000");
+        let robots = RobotsTxt::new(Some("User-agent: *\nDisallow: /auth/\n"), &address);
+
+        assert_eq!(
+            robots.as_ref(),
+            "User-agent: *\nDisallow: /auth/\n\nSitemap: http://localhost:3000/sitemap.xml\n"
+        );
+    }
+}
@@ -4,7 +4,10 @@ use sqlx::{query, query_as};
 use uuid::Uuid;
 
 use crate::{
-    domain::user::value_objects::{email::EmailBuf, username::UsernameBuf},
+    domain::{
+        event::{self, DomainEvent},
+        user::value_objects::{email::EmailBuf, username::UsernameBuf},
+    },
     persistence::db::{entities::IdSelect, Pool},
     server::{auth::HashedPassword, service::sync_service},
 };
@@ -50,6 +53,8 @@ impl Register {
             .execute(&mut tx)
             .await?;
 
+        event::record(&mut tx, &DomainEvent::AccountRegistered { account_id: id }).await?;
+
         tx.commit().await.unwrap();
 
         Ok(Response {
@@ -0,0 +1,17 @@
+pub mod delivery;
+pub mod features;
+pub mod models;
+mod subscriber;
+
+pub use subscriber::WebhookSubscriber;
+
+use crate::domain::event::EventKind;
+
+/// Events exposed to external endpoints, account events are kept private
+pub const SUBSCRIBABLE_EVENTS: [EventKind; 5] = [
+    EventKind::BlogCreated,
+    EventKind::BlogUpdated,
+    EventKind::BlogContentSet,
+    EventKind::BlogDeleted,
+    EventKind::CommentCreated,
+];
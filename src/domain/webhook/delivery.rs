@@ -0,0 +1,280 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        event::{DomainEvent, EventKind},
+        job::{JobKind, JobPayload},
+        webhook::models::DeliveryStatus,
+    },
+    persistence::db::{DateTime, Pool},
+};
+
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+pub const EVENT_HEADER: &str = "X-Event-Type";
+pub const DELIVERY_HEADER: &str = "X-Delivery-Id";
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Payload {
+    pub delivery_id: Uuid,
+}
+
+impl JobPayload for Payload {
+    const KIND: JobKind = JobKind::DeliverWebhook;
+    const MAX_ATTEMPTS: i32 = 8;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("could not deliver webhook: {0}")]
+    Delivery(String),
+}
+
+/// Body posted to the webhook endpoints
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Body {
+    id: Uuid,
+    created_at: DateTime,
+    #[serde(flatten)]
+    event: DomainEvent,
+}
+
+struct PendingDelivery {
+    id: Uuid,
+    url: String,
+    secret: String,
+    event_id: Uuid,
+    event_kind: EventKind,
+    event_created_at: DateTime,
+    payload: Json<DomainEvent>,
+}
+
+/// Posts the event of a delivery to its webhook, failing so the job is retried while the
+/// endpoint does not answer with a success status
+pub async fn deliver(pool: &Pool, delivery_id: Uuid, is_last_attempt: bool) -> Result<(), Error> {
+    let delivery = sqlx::query_as!(
+        PendingDelivery,
+        r#"SELECT
+            d.id, w.url, w.secret,
+            e.id as event_id, e.kind as "event_kind: EventKind", e.created_at as event_created_at,
+            e.payload as "payload: Json<DomainEvent>"
+        FROM webhook_deliveries d
+        JOIN webhooks w ON w.id = d.webhook_id
+        JOIN events e ON e.id = d.event_id
+        WHERE d.id = $1 AND d.status = 'pending'"#,
+        delivery_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    // The webhook was removed or the delivery is already done
+    let Some(delivery) = delivery else {
+        return Ok(());
+    };
+
+    let body = Body {
+        id: delivery.event_id,
+        created_at: delivery.event_created_at,
+        event: delivery.payload.0,
+    };
+
+    // Serializing plain data can not fail
+    let body = serde_json::to_vec(&body).unwrap();
+
+    let result = send(
+        &delivery.url,
+        &delivery.secret,
+        delivery.id,
+        delivery.event_kind,
+        body,
+    )
+    .await;
+
+    let (status, response_status, error) = match &result {
+        Ok(code) if (200..300).contains(code) => (DeliveryStatus::Delivered, Some(*code), None),
+        Ok(code) => (
+            DeliveryStatus::Pending,
+            Some(*code),
+            Some(format!("endpoint answered with status {}", code)),
+        ),
+        Err(e) => (DeliveryStatus::Pending, None, Some(e.clone())),
+    };
+
+    let status = match status {
+        DeliveryStatus::Pending if is_last_attempt => DeliveryStatus::Failed,
+        status => status,
+    };
+
+    sqlx::query!(
+        r#"UPDATE webhook_deliveries
+        SET
+            status = $1,
+            attempts = attempts + 1,
+            response_status = $2,
+            last_error = $3,
+            delivered_at = CASE WHEN $1 = 'delivered'::webhook_delivery_status THEN now() END
+        WHERE id = $4"#,
+        status as DeliveryStatus,
+        response_status.map(i32::from),
+        error,
+        delivery.id
+    )
+    .execute(pool)
+    .await?;
+
+    match error {
+        Some(error) => Err(Error::Delivery(error)),
+        None => Ok(()),
+    }
+}
+
+/// Posts a signed body, returning the status code the endpoint answered with
+pub async fn send(
+    url: &str,
+    secret: &str,
+    delivery_id: Uuid,
+    kind: EventKind,
+    body: Vec<u8>,
+) -> Result<u16, String> {
+    let signature = sign(secret.as_bytes(), &body);
+
+    // Serializing a unit enum can not fail
+    let kind = serde_json::to_value(kind).unwrap();
+    let kind = kind.as_str().unwrap_or_default();
+
+    let client = awc::Client::builder().timeout(TIMEOUT).finish();
+    let response = client
+        .post(url)
+        .content_type("application/json")
+        .insert_header((SIGNATURE_HEADER, signature))
+        .insert_header((EVENT_HEADER, kind))
+        .insert_header((DELIVERY_HEADER, delivery_id.to_string()))
+        .send_body(body)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(response.status().as_u16())
+}
+
+/// Hex encoded HMAC-SHA256 of the body, prefixed by the algorithm as `sha256=`
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any size");
+    mac.update(body);
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use actix_web::{
+        web::{self, Bytes, Data},
+        App, HttpRequest, HttpResponse, HttpServer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        let signature = sign(b"key", b"The quick brown fox jumps over the lazy dog");
+
+        assert_eq!(
+            signature,
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    type Received = Arc<Mutex<Vec<(String, String, Bytes)>>>;
+
+    /// Local endpoint recording what it receives and answering with `status`
+    fn stand_in(status: u16) -> (String, Received, actix_web::dev::ServerHandle) {
+        let received = Received::default();
+
+        let data = Data::new(received.clone());
+        let server = HttpServer::new(move || {
+            App::new().app_data(data.clone()).route(
+                "/hook",
+                web::post().to(
+                    move |req: HttpRequest, body: Bytes, received: Data<Received>| async move {
+                        let header = |name| {
+                            req.headers()
+                                .get(name)
+                                .and_then(|value| value.to_str().ok())
+                                .unwrap_or_default()
+                                .to_owned()
+                        };
+
+                        received.lock().unwrap().push((
+                            header(SIGNATURE_HEADER),
+                            header(EVENT_HEADER),
+                            body,
+                        ));
+
+                        HttpResponse::build(status.try_into().unwrap()).finish()
+                    },
+                ),
+            )
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+
+        let url = format!("http://{}/hook", server.addrs()[0]);
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        (url, received, handle)
+    }
+
+    #[actix_web::test]
+    async fn sends_signed_body() {
+        let (url, received, handle) = stand_in(200);
+        let body = br#"{"type":"blogCreated"}"#.to_vec();
+
+        let status = send(
+            &url,
+            "secret",
+            Uuid::nil(),
+            EventKind::BlogCreated,
+            body.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, 200);
+
+        let (signature, kind, received_body) = received.lock().unwrap().remove(0);
+
+        assert_eq!(signature, sign(b"secret", &body));
+        assert_eq!(kind, "blogCreated");
+        assert_eq!(received_body.as_ref(), body.as_slice());
+
+        handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn reports_failed_status() {
+        let (url, _, handle) = stand_in(500);
+
+        let status = send(&url, "secret", Uuid::nil(), EventKind::BlogCreated, vec![])
+            .await
+            .unwrap();
+
+        assert_eq!(status, 500);
+
+        handle.stop(false).await;
+    }
+}
@@ -0,0 +1,4 @@
+pub mod create_one;
+pub mod delete_one;
+pub mod get_all;
+pub mod get_deliveries;
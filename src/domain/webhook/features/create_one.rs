@@ -0,0 +1,28 @@
+use actix_web::web::Data;
+
+use crate::{
+    domain::event::EventKind,
+    persistence::db::{entities::IdSelect, Pool},
+    server::service::sync_service,
+};
+
+sync_service!(CreateWebhook; pool: Data<Pool>);
+
+impl CreateWebhook {
+    pub async fn run(
+        &self,
+        url: &str,
+        secret: &str,
+        events: &[EventKind],
+    ) -> Result<IdSelect, sqlx::Error> {
+        sqlx::query_as!(
+            IdSelect,
+            "INSERT INTO webhooks (url, secret, events) VALUES ($1, $2, $3) RETURNING id",
+            url,
+            secret,
+            events as _
+        )
+        .fetch_one(self.pool.as_ref())
+        .await
+    }
+}
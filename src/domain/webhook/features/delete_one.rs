@@ -0,0 +1,18 @@
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    persistence::db::{Pool, QueryResult},
+    server::service::sync_service,
+};
+
+sync_service!(DeleteWebhook; pool: Data<Pool>);
+
+impl DeleteWebhook {
+    /// Pending deliveries of the webhook are dropped along with it
+    pub async fn run(&self, id: Uuid) -> Result<QueryResult, sqlx::Error> {
+        sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(self.pool.as_ref())
+            .await
+    }
+}
@@ -0,0 +1,21 @@
+use actix_web::web::Data;
+
+use crate::{
+    domain::{event::EventKind, webhook::models::Webhook},
+    persistence::db::Pool,
+    server::service::sync_service,
+};
+
+sync_service!(GetWebhooks; pool: Data<Pool>);
+
+impl GetWebhooks {
+    pub async fn run(&self) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id, url, events as "events: Vec<EventKind>", active, created_at
+            FROM webhooks ORDER BY created_at"#
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+    }
+}
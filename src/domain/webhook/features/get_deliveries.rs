@@ -0,0 +1,39 @@
+use actix_web::web::Data;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        event::EventKind,
+        webhook::models::{DeliveryStatus, WebhookDelivery},
+    },
+    persistence::db::{Pool, Slice},
+    server::service::sync_service,
+};
+
+sync_service!(GetDeliveries; pool: Data<Pool>);
+
+impl GetDeliveries {
+    /// Latest deliveries first
+    pub async fn run(
+        &self,
+        webhook_id: Uuid,
+        Slice { limit, offset }: Slice,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT
+                d.id, d.event_id, e.kind as "event_kind: EventKind", d.status as "status: DeliveryStatus",
+                d.attempts, d.response_status, d.last_error, d.created_at, d.delivered_at
+            FROM webhook_deliveries d
+            JOIN events e ON e.id = d.event_id
+            WHERE d.webhook_id = $1
+            ORDER BY d.created_at DESC
+            LIMIT $2 OFFSET $3"#,
+            webhook_id,
+            limit,
+            offset
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+    }
+}
@@ -0,0 +1,38 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{domain::event::EventKind, persistence::db::DateTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+#[serde(rename_all = "camelCase")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// A registered endpoint, the secret is never sent back
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<EventKind>,
+    pub active: bool,
+    pub created_at: DateTime,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub event_kind: EventKind,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime,
+    pub delivered_at: Option<DateTime>,
+}
@@ -0,0 +1,69 @@
+use actix_web::web::Data;
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        event::{DomainEvent, EventKind, Subscriber},
+        job::features::enqueue::enqueue,
+    },
+    persistence::db::Pool,
+};
+
+use super::delivery;
+
+struct DeliveryId {
+    id: Uuid,
+}
+
+/// Schedules a delivery for every active webhook interested in the event
+pub struct WebhookSubscriber {
+    pool: Data<Pool>,
+}
+
+impl WebhookSubscriber {
+    pub fn new(pool: Data<Pool>) -> Self {
+        Self { pool }
+    }
+
+    async fn schedule(&self, event_id: Uuid, kind: EventKind) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Redelivered events do not create duplicated deliveries
+        let deliveries = sqlx::query_as!(
+            DeliveryId,
+            r#"INSERT INTO webhook_deliveries (webhook_id, event_id)
+            SELECT id, $1 FROM webhooks WHERE active AND $2 = ANY(events)
+            ON CONFLICT (webhook_id, event_id) DO NOTHING
+            RETURNING id"#,
+            event_id,
+            kind as EventKind
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        for DeliveryId { id } in deliveries {
+            enqueue(&mut tx, &delivery::Payload { delivery_id: id }).await?;
+        }
+
+        tx.commit().await
+    }
+}
+
+impl Subscriber for WebhookSubscriber {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    fn handle<'a>(
+        &'a self,
+        id: Uuid,
+        event: &'a DomainEvent,
+    ) -> LocalBoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            self.schedule(id, event.kind())
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+}
@@ -1,3 +1,4 @@
+pub mod blobs;
 pub mod db;
 pub mod images;
 pub mod public;
@@ -0,0 +1,127 @@
+mod fs;
+mod s3;
+
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use actix_web::web::Bytes;
+use futures_util::{
+    future::LocalBoxFuture,
+    stream::{self, BoxStream, LocalBoxStream},
+    StreamExt,
+};
+
+pub use fs::FsStore;
+pub use s3::{S3Config, S3Store};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("request to the store failed: {0}")]
+    Request(String),
+    #[error("blocking task of the store failed")]
+    Blocking,
+    #[error("invalid store configuration: {0}")]
+    Config(String),
+    /// Content streamed to the store ended in an error of its own
+    #[error("content ended before it was stored")]
+    Interrupted,
+}
+
+/// Content of a blob in chunks, as it is read
+pub type BlobStream = BoxStream<'static, Result<Bytes, Error>>;
+
+/// Content of a blob in chunks, as it is received
+pub type LocalBlobStream<'a> = LocalBoxStream<'a, Result<Bytes, Error>>;
+
+/// Url a blob can be fetched from directly for a while
+pub struct Presigned {
+    pub url: String,
+    pub expires_in: Duration,
+}
+
+/// Blob found by [`BlobStore::list`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Listed {
+    pub key: String,
+    /// Bytes of the blob
+    pub size: u64,
+    /// When it was last stored
+    pub modified: SystemTime,
+}
+
+/// Where uploads are stored, by keys like `objects/{hash}.png` laid out as `STATIC_DIR` is.
+///
+/// Several instances of the server can share a store that is not local to any of them.
+pub trait BlobStore: Send + Sync {
+    /// Stores the blob, replacing the one under `key` if any
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        content: Vec<u8>,
+        content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), Error>>;
+
+    /// Stores the blob unless one is under `key` already, returning whether it was stored
+    fn put_if_absent<'a>(
+        &'a self,
+        key: &'a str,
+        content: Vec<u8>,
+        content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<bool, Error>>;
+
+    /// Like [`BlobStore::put`] without holding the whole blob, for those stored as received.
+    /// When the content ends in an error nothing is stored, and the blob under `key` is left as
+    /// it was. Returns the bytes stored
+    fn put_stream<'a>(
+        &'a self,
+        key: &'a str,
+        content: LocalBlobStream<'a>,
+        content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<u64, Error>>;
+
+    fn get<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<Option<Bytes>, Error>>;
+
+    /// Like [`BlobStore::get`] without holding the whole blob, for those sent on as read
+    fn get_stream<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Option<BlobStream>, Error>> {
+        Box::pin(async move {
+            let content = self.get(key).await?;
+            Ok(content.map(|content| stream::once(async { Ok(content) }).boxed()))
+        })
+    }
+
+    fn exists<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<bool, Error>>;
+
+    /// Deleting a blob that does not exist is not an error
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<(), Error>>;
+
+    /// Blobs right inside `dir`, like files of a directory, without those further down
+    fn list<'a>(&'a self, dir: &'a str) -> LocalBoxFuture<'a, Result<Vec<Listed>, Error>>;
+
+    /// Every blob under `dir`, however far down
+    fn list_all<'a>(&'a self, dir: &'a str) -> LocalBoxFuture<'a, Result<Vec<Listed>, Error>>;
+
+    /// Path of the blob when it is stored on this machine, so it is served from the file
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// Url the server redirects to instead of sending the blob itself, when the store offers one
+    fn presigned_url<'a>(
+        &'a self,
+        _key: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Option<Presigned>, Error>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Url anyone can fetch the blob from, when the store is publicly readable
+    fn public_url(&self, _key: &str) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,341 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use actix_web::web::{self, Bytes};
+use futures_util::{future::LocalBoxFuture, StreamExt};
+use uuid::Uuid;
+
+use super::{BlobStore, Error, Listed, LocalBlobStream};
+
+/// Stores blobs as files inside a directory, keys being their paths relative to it. Files are
+/// touched off the async runtime
+pub struct FsStore {
+    root: PathBuf,
+}
+
+impl FsStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl BlobStore for FsStore {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        content: Vec<u8>,
+        _content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), Error>> {
+        let path = self.path(key);
+
+        Box::pin(blocking(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            std::fs::write(path, content)
+        }))
+    }
+
+    fn put_if_absent<'a>(
+        &'a self,
+        key: &'a str,
+        content: Vec<u8>,
+        _content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<bool, Error>> {
+        let path = self.path(key);
+
+        Box::pin(blocking(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(false),
+                Err(e) => return Err(e),
+            };
+
+            if let Err(e) = file.write_all(&content) {
+                let _ = std::fs::remove_file(&path);
+                return Err(e);
+            }
+
+            Ok(true)
+        }))
+    }
+
+    fn put_stream<'a>(
+        &'a self,
+        key: &'a str,
+        content: LocalBlobStream<'a>,
+        _content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<u64, Error>> {
+        let path = self.path(key);
+
+        Box::pin(async move {
+            // Written aside under a name of its own first, so the file under `key` is never
+            // half written
+            let mut partial = path.clone().into_os_string();
+            partial.push(format!(".{}.partial", Uuid::new_v4()));
+            let partial = PathBuf::from(partial);
+
+            let result = match write_stream(partial.clone(), content).await {
+                Ok(size) => blocking({
+                    let partial = partial.clone();
+                    move || std::fs::rename(partial, path)
+                })
+                .await
+                .map(|()| size),
+                Err(e) => Err(e),
+            };
+
+            if result.is_err() {
+                let _ = blocking(move || std::fs::remove_file(partial)).await;
+            }
+
+            result
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<Option<Bytes>, Error>> {
+        let path = self.path(key);
+
+        Box::pin(blocking(move || match std::fs::read(path) {
+            Ok(content) => Ok(Some(content.into())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }))
+    }
+
+    fn exists<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<bool, Error>> {
+        let path = self.path(key);
+
+        Box::pin(blocking(move || Ok(path.is_file())))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<(), Error>> {
+        let path = self.path(key);
+
+        Box::pin(blocking(move || match std::fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        }))
+    }
+
+    fn list<'a>(&'a self, dir: &'a str) -> LocalBoxFuture<'a, Result<Vec<Listed>, Error>> {
+        let path = self.path(dir);
+        let dir = dir.trim_end_matches('/').to_owned();
+
+        Box::pin(blocking(move || {
+            let entries = match std::fs::read_dir(path) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+                Err(e) => return Err(e),
+            };
+
+            let mut listed = vec![];
+            for entry in entries {
+                let entry = entry?;
+                let file = entry.metadata()?;
+
+                // Keys are valid utf-8, files that are not were not stored by key
+                let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+                    continue;
+                };
+
+                if file.is_file() {
+                    listed.push(Listed {
+                        key: format!("{}/{}", dir, name),
+                        size: file.len(),
+                        modified: file.modified()?,
+                    });
+                }
+            }
+
+            Ok(listed)
+        }))
+    }
+
+    fn list_all<'a>(&'a self, dir: &'a str) -> LocalBoxFuture<'a, Result<Vec<Listed>, Error>> {
+        let path = self.path(dir);
+        let dir = dir.trim_end_matches('/').to_owned();
+
+        Box::pin(blocking(move || {
+            let mut listed = vec![];
+            walk(&path, &dir, &mut listed)?;
+
+            Ok(listed)
+        }))
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.path(key))
+    }
+}
+
+/// Lists the files under `path`, keyed from `key`
+fn walk(path: &Path, key: &str, listed: &mut Vec<Listed>) -> std::io::Result<()> {
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let file = entry.metadata()?;
+
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        let key = format!("{}/{}", key, name);
+
+        if file.is_dir() {
+            walk(&entry.path(), &key, listed)?;
+        } else if file.is_file() {
+            listed.push(Listed {
+                key,
+                size: file.len(),
+                modified: file.modified()?,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the chunks to a new file at `path` as they are received, returning how many bytes
+async fn write_stream(path: PathBuf, mut content: LocalBlobStream<'_>) -> Result<u64, Error> {
+    let mut file = blocking(move || {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        File::create(path)
+    })
+    .await?;
+
+    let mut size = 0;
+    while let Some(chunk) = content.next().await {
+        let chunk = chunk?;
+        size += chunk.len() as u64;
+
+        file = blocking(move || file.write_all(&chunk).map(|()| file)).await?;
+    }
+
+    Ok(size)
+}
+
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> std::io::Result<T> + Send + 'static,
+) -> Result<T, Error> {
+    Ok(web::block(work).await.map_err(|_| Error::Blocking)??)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn stores_files_by_key() {
+        let root = std::env::temp_dir().join(format!("blobs-{}", Uuid::new_v4()));
+        let store = FsStore::new(&root);
+        let key = "objects/variants/d0e1.png/480.png";
+
+        store.put(key, b"png".to_vec(), "image/png").await.unwrap();
+
+        assert!(store.exists(key).await.unwrap());
+        assert_eq!(store.get(key).await.unwrap().unwrap().as_ref(), b"png");
+        assert_eq!(store.local_path(key).unwrap(), root.join(key));
+
+        store.delete(key).await.unwrap();
+        store.delete(key).await.unwrap();
+
+        assert!(!store.exists(key).await.unwrap());
+        assert!(store.get(key).await.unwrap().is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn lists_and_never_replaces_when_absent() {
+        let root = std::env::temp_dir().join(format!("blobs-{}", Uuid::new_v4()));
+        let store = FsStore::new(&root);
+
+        assert!(store
+            .put_if_absent("blogs/b/a.png", b"a".to_vec(), "image/png")
+            .await
+            .unwrap());
+        assert!(!store
+            .put_if_absent("blogs/b/a.png", b"b".to_vec(), "image/png")
+            .await
+            .unwrap());
+        store
+            .put("blogs/b/variants/a.png/480.png", vec![], "image/png")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get("blogs/b/a.png").await.unwrap().unwrap().as_ref(),
+            b"a"
+        );
+        let listed = store.list("blogs/b").await.unwrap();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|blob| (blob.key.as_str(), blob.size))
+                .collect::<Vec<_>>(),
+            [("blogs/b/a.png", 1)]
+        );
+        assert!(store.list("blogs/c").await.unwrap().is_empty());
+
+        let mut all = store
+            .list_all("blogs")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|blob| blob.key)
+            .collect::<Vec<_>>();
+        all.sort();
+        assert_eq!(all, ["blogs/b/a.png", "blogs/b/variants/a.png/480.png"]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn streams_files_in_whole_or_not_at_all() {
+        let root = std::env::temp_dir().join(format!("blobs-{}", Uuid::new_v4()));
+        let store = FsStore::new(&root);
+        let key = "attachments/b/notes.txt";
+
+        let chunks = [Ok(Bytes::from("no")), Ok(Bytes::from("tes"))];
+        let size = store
+            .put_stream(key, stream::iter(chunks).boxed_local(), "text/plain")
+            .await
+            .unwrap();
+        assert_eq!(size, 5);
+
+        let chunks = [Ok(Bytes::from("half")), Err(Error::Interrupted)];
+        assert!(store
+            .put_stream(key, stream::iter(chunks).boxed_local(), "text/plain")
+            .await
+            .is_err());
+
+        assert_eq!(store.get(key).await.unwrap().unwrap().as_ref(), b"notes");
+        let listed = store.list("attachments/b").await.unwrap();
+        assert_eq!(listed.len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
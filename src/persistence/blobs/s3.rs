@@ -0,0 +1,522 @@
+use std::time::Duration;
+
+use ::http::Method;
+use actix_web::{http::Uri, web::Bytes};
+use futures_util::{future::LocalBoxFuture, StreamExt, TryStreamExt};
+use object_store::{
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+    signer::Signer,
+    Attribute, AttributeValue, Attributes, ClientOptions, ObjectMeta, ObjectStore, PutMode,
+    PutMultipartOptions, PutOptions, PutPayload, WriteMultipart,
+};
+
+use super::{BlobStore, BlobStream, Error, Listed, LocalBlobStream, Presigned};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Parts of a streamed blob uploaded at once, each holding 5 MiB of it until it is sent
+const PARTS_IN_FLIGHT: usize = 4;
+
+pub struct S3Config {
+    /// Like `https://s3.eu-west-1.amazonaws.com` or `http://localhost:9000`, without a path
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Where the bucket is publicly readable from, like a CDN in front of it
+    pub public_url: Option<String>,
+    /// Images are served by redirecting to urls presigned for this long, instead of being
+    /// sent through the server
+    pub presign: Option<Duration>,
+}
+
+/// Stores blobs in a bucket of any S3 compatible service, like MinIO, addressing it by path
+/// so it works without DNS set up for the bucket. Its client is shared by every request
+pub struct S3Store {
+    bucket: AmazonS3,
+    public_url: Option<String>,
+    presign: Option<Duration>,
+}
+
+impl S3Store {
+    pub fn new(config: S3Config) -> Result<Self, Error> {
+        let endpoint: Uri = config
+            .endpoint
+            .parse()
+            .map_err(|e| Error::Config(format!("invalid S3 endpoint: {}", e)))?;
+        if endpoint.scheme().is_none() || endpoint.host().is_none() {
+            return Err(Error::Config(
+                "S3 endpoint needs a scheme and a host".to_owned(),
+            ));
+        }
+
+        let bucket = AmazonS3Builder::new()
+            .with_endpoint(config.endpoint)
+            .with_bucket_name(config.bucket)
+            .with_region(config.region)
+            .with_access_key_id(config.access_key)
+            .with_secret_access_key(config.secret_key)
+            .with_virtual_hosted_style_request(false)
+            .with_client_options(
+                ClientOptions::new()
+                    .with_timeout(TIMEOUT)
+                    .with_allow_http(true),
+            )
+            .build()
+            .map_err(|e| Error::Config(e.to_string()))?;
+
+        Ok(Self {
+            bucket,
+            public_url: config.public_url,
+            presign: config.presign,
+        })
+    }
+
+    async fn put_with(
+        &self,
+        key: &str,
+        content: Vec<u8>,
+        content_type: &str,
+        mode: PutMode,
+    ) -> Result<(), object_store::Error> {
+        let options = PutOptions {
+            mode,
+            attributes: attributes(content_type),
+            ..Default::default()
+        };
+
+        self.bucket
+            .put_opts(&Path::from(key), PutPayload::from(content), options)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl From<object_store::Error> for Error {
+    fn from(e: object_store::Error) -> Self {
+        Self::Request(e.to_string())
+    }
+}
+
+impl BlobStore for S3Store {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        content: Vec<u8>,
+        content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            Ok(self
+                .put_with(key, content, content_type, PutMode::Overwrite)
+                .await?)
+        })
+    }
+
+    fn put_if_absent<'a>(
+        &'a self,
+        key: &'a str,
+        content: Vec<u8>,
+        content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<bool, Error>> {
+        Box::pin(async move {
+            match self
+                .put_with(key, content, content_type, PutMode::Create)
+                .await
+            {
+                Ok(()) => Ok(true),
+                Err(object_store::Error::AlreadyExists { .. }) => Ok(false),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    /// Uploaded in parts as it is received, an upload that fails is aborted so none of its
+    /// parts are kept
+    fn put_stream<'a>(
+        &'a self,
+        key: &'a str,
+        mut content: LocalBlobStream<'a>,
+        content_type: &'a str,
+    ) -> LocalBoxFuture<'a, Result<u64, Error>> {
+        Box::pin(async move {
+            let options = PutMultipartOptions {
+                attributes: attributes(content_type),
+                ..Default::default()
+            };
+            let upload = self
+                .bucket
+                .put_multipart_opts(&Path::from(key), options)
+                .await?;
+            let mut upload = WriteMultipart::new(upload);
+
+            let mut size = 0;
+            let result = async {
+                while let Some(chunk) = content.next().await {
+                    let chunk = chunk?;
+                    size += chunk.len() as u64;
+
+                    upload.wait_for_capacity(PARTS_IN_FLIGHT).await?;
+                    upload.put(chunk);
+                }
+
+                Ok::<_, Error>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                if let Err(e) = upload.abort().await {
+                    eprintln!("Could not abort upload of {}: {}", key, e);
+                }
+                return Err(e);
+            }
+
+            upload.finish().await?;
+
+            Ok(size)
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<Option<Bytes>, Error>> {
+        Box::pin(async move {
+            match self.bucket.get(&Path::from(key)).await {
+                Ok(blob) => Ok(Some(blob.bytes().await?)),
+                Err(object_store::Error::NotFound { .. }) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn get_stream<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Option<BlobStream>, Error>> {
+        Box::pin(async move {
+            match self.bucket.get(&Path::from(key)).await {
+                Ok(blob) => Ok(Some(blob.into_stream().map_err(Error::from).boxed())),
+                Err(object_store::Error::NotFound { .. }) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn exists<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<bool, Error>> {
+        Box::pin(async move {
+            match self.bucket.head(&Path::from(key)).await {
+                Ok(_) => Ok(true),
+                Err(object_store::Error::NotFound { .. }) => Ok(false),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            match self.bucket.delete(&Path::from(key)).await {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn list<'a>(&'a self, dir: &'a str) -> LocalBoxFuture<'a, Result<Vec<Listed>, Error>> {
+        Box::pin(async move {
+            let listed = self
+                .bucket
+                .list_with_delimiter(Some(&Path::from(dir)))
+                .await?;
+
+            Ok(listed.objects.into_iter().map(listed_blob).collect())
+        })
+    }
+
+    fn list_all<'a>(&'a self, dir: &'a str) -> LocalBoxFuture<'a, Result<Vec<Listed>, Error>> {
+        Box::pin(async move {
+            self.bucket
+                .list(Some(&Path::from(dir)))
+                .map_ok(listed_blob)
+                .map_err(Error::from)
+                .try_collect()
+                .await
+        })
+    }
+
+    fn presigned_url<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Option<Presigned>, Error>> {
+        Box::pin(async move {
+            let Some(expires_in) = self.presign else {
+                return Ok(None);
+            };
+
+            let url = self
+                .bucket
+                .signed_url(Method::GET, &Path::from(key), expires_in)
+                .await?;
+
+            Ok(Some(Presigned {
+                url: url.to_string(),
+                expires_in,
+            }))
+        })
+    }
+
+    fn public_url(&self, key: &str) -> Option<String> {
+        let public_url = self.public_url.as_ref()?;
+        Some(format!(
+            "{}/{}",
+            public_url.trim_end_matches('/'),
+            Path::from(key)
+        ))
+    }
+}
+
+fn attributes(content_type: &str) -> Attributes {
+    Attributes::from_iter([(
+        Attribute::ContentType,
+        AttributeValue::from(content_type.to_owned()),
+    )])
+}
+
+fn listed_blob(blob: ObjectMeta) -> Listed {
+    Listed {
+        key: blob.location.to_string(),
+        size: blob.size,
+        modified: blob.last_modified.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use actix_web::{
+        http::header::{CONTENT_LENGTH, ETAG, IF_NONE_MATCH},
+        web::{self, Data, Query},
+        App, HttpRequest, HttpResponse, HttpServer,
+    };
+
+    use super::*;
+
+    type Bucket = Arc<Mutex<BTreeMap<String, Vec<u8>>>>;
+
+    const ACCESS_KEY: &str = "minio";
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct ListQuery {
+        prefix: Option<String>,
+        delimiter: Option<String>,
+    }
+
+    /// Local stand-in for MinIO keeping the objects of the `blog` bucket in memory, rejecting
+    /// requests not signed with its access key
+    fn stand_in() -> (String, Bucket, actix_web::dev::ServerHandle) {
+        let bucket = Bucket::default();
+
+        let data = Data::new(bucket.clone());
+        let server = HttpServer::new(move || {
+            App::new().app_data(data.clone()).default_service(web::to(
+                |req: HttpRequest, body: web::Bytes, bucket: Data<Bucket>| async move {
+                    let signed_by_key = req
+                        .headers()
+                        .get("authorization")
+                        .and_then(|value| value.to_str().ok())
+                        .is_some_and(|value| {
+                            value.contains(&format!("Credential={}/", ACCESS_KEY))
+                        });
+                    if !signed_by_key {
+                        return HttpResponse::Forbidden().finish();
+                    }
+
+                    let Some(key) = req.uri().path().strip_prefix("/blog") else {
+                        return HttpResponse::NotFound().finish();
+                    };
+                    let key = key.trim_start_matches('/').to_owned();
+                    let mut bucket = bucket.lock().unwrap();
+
+                    match req.method().as_str() {
+                        "GET" if key.is_empty() => {
+                            let Ok(query) = Query::<ListQuery>::from_query(req.query_string())
+                            else {
+                                return HttpResponse::BadRequest().finish();
+                            };
+
+                            HttpResponse::Ok()
+                                .content_type("application/xml")
+                                .body(list(
+                                    &bucket,
+                                    query.prefix.as_deref().unwrap_or(""),
+                                    query.delimiter.is_some(),
+                                ))
+                        }
+                        "PUT" => {
+                            if req.headers().contains_key(IF_NONE_MATCH)
+                                && bucket.contains_key(&key)
+                            {
+                                return HttpResponse::PreconditionFailed().finish();
+                            }
+
+                            bucket.insert(key, body.to_vec());
+                            HttpResponse::Ok().insert_header((ETAG, "\"0\"")).finish()
+                        }
+                        "GET" => match bucket.get(&key) {
+                            Some(content) => HttpResponse::Ok()
+                                .insert_header((ETAG, "\"0\""))
+                                .body(content.clone()),
+                            None => HttpResponse::NotFound().finish(),
+                        },
+                        "HEAD" => match bucket.get(&key) {
+                            Some(content) => HttpResponse::Ok()
+                                .insert_header((ETAG, "\"0\""))
+                                .insert_header((CONTENT_LENGTH, content.len()))
+                                .finish(),
+                            None => HttpResponse::NotFound().finish(),
+                        },
+                        "DELETE" => {
+                            bucket.remove(&key);
+                            HttpResponse::NoContent().finish()
+                        }
+                        _ => HttpResponse::MethodNotAllowed().finish(),
+                    }
+                },
+            ))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+
+        let endpoint = format!("http://{}", server.addrs()[0]);
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        (endpoint, bucket, handle)
+    }
+
+    /// Objects under `prefix` as `ListObjectsV2` answers, only those right under it when
+    /// `delimited` by slashes
+    fn list(bucket: &BTreeMap<String, Vec<u8>>, prefix: &str, delimited: bool) -> String {
+        let contents: String = bucket
+            .iter()
+            .filter(|(key, _)| {
+                key.strip_prefix(prefix)
+                    .is_some_and(|rest| !delimited || !rest.contains('/'))
+            })
+            .map(|(key, content)| {
+                format!(
+                    "<Contents><Key>{}</Key><Size>{}</Size><LastModified>2024-08-24T00:00:00.000Z</LastModified></Contents>",
+                    key,
+                    content.len()
+                )
+            })
+            .collect();
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><ListBucketResult><Name>blog</Name><Prefix>{}</Prefix><IsTruncated>false</IsTruncated>{}</ListBucketResult>"#,
+            prefix, contents
+        )
+    }
+
+    fn store(endpoint: String, access_key: &str) -> S3Store {
+        S3Store::new(S3Config {
+            endpoint,
+            bucket: "blog".to_owned(),
+            region: "us-east-1".to_owned(),
+            access_key: access_key.to_owned(),
+            secret_key: "minio-secret".to_owned(),
+            public_url: None,
+            presign: Some(Duration::from_secs(600)),
+        })
+        .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn stores_objects_in_the_bucket() {
+        let (endpoint, bucket, handle) = stand_in();
+        let store = store(endpoint.clone(), ACCESS_KEY);
+        let key = "objects/variants/d0e1.png/480.webp";
+
+        store
+            .put(key, b"webp".to_vec(), "image/webp")
+            .await
+            .unwrap();
+
+        assert!(bucket.lock().unwrap().contains_key(key));
+        assert!(store.exists(key).await.unwrap());
+        assert_eq!(store.get(key).await.unwrap().unwrap().as_ref(), b"webp");
+        let streamed: Vec<_> = store
+            .get_stream(key)
+            .await
+            .unwrap()
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(streamed.concat(), b"webp");
+        assert!(!store
+            .put_if_absent(key, b"png".to_vec(), "image/png")
+            .await
+            .unwrap());
+        let listed = store.list("objects/variants/d0e1.png").await.unwrap();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|blob| (blob.key.as_str(), blob.size))
+                .collect::<Vec<_>>(),
+            [(key, 4)]
+        );
+        assert!(store.list("objects").await.unwrap().is_empty());
+        assert_eq!(store.list_all("objects").await.unwrap(), listed);
+
+        store.delete(key).await.unwrap();
+
+        assert!(!store.exists(key).await.unwrap());
+        assert!(store.get(key).await.unwrap().is_none());
+
+        let presigned = store.presigned_url(key).await.unwrap().unwrap();
+        assert!(presigned
+            .url
+            .starts_with(&format!("{}/blog/{}?X-Amz-Algorithm=", endpoint, key)));
+        assert!(store.public_url(key).is_none());
+
+        handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn fails_with_wrong_credentials() {
+        let (endpoint, _, handle) = stand_in();
+        let store = store(endpoint, "wrong");
+
+        let result = store.put("objects/d0e1.png", vec![1], "image/png").await;
+
+        assert!(matches!(result, Err(Error::Request(_))));
+
+        handle.stop(false).await;
+    }
+
+    #[test]
+    fn rejects_endpoints_without_a_host() {
+        let config = |endpoint: &str| S3Config {
+            endpoint: endpoint.to_owned(),
+            bucket: "blog".to_owned(),
+            region: "us-east-1".to_owned(),
+            access_key: ACCESS_KEY.to_owned(),
+            secret_key: "minio-secret".to_owned(),
+            public_url: None,
+            presign: None,
+        };
+
+        assert!(matches!(
+            S3Store::new(config("localhost:9000")),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(S3Store::new(config("")), Err(Error::Config(_))));
+    }
+}
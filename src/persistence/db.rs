@@ -1,5 +1,5 @@
 pub mod entities;
-pub mod decode;
+pub mod notify;
 
 use actix_web::web::{Data, ServiceConfig};
 use sqlx::{self, migrate, postgres::PgPoolOptions, PgPool, Postgres};
@@ -19,6 +19,29 @@ pub trait Executor<'a>: sqlx::Executor<'a, Database = Database> {}
 impl<'a, E> Executor<'a> for E where E: sqlx::Executor<'a, Database = Database> {}
 
 pub use slice::Slice;
+pub use version::Version;
+
+/// Postgres code of a reference to a row that does not exist
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+/// Postgres code of a row taking a key another row has
+const UNIQUE_VIOLATION: &str = "23505";
+
+/// Whether the error is a reference to a row that does not exist
+pub fn is_foreign_key_violation(e: &sqlx::Error) -> bool {
+    has_code(e, FOREIGN_KEY_VIOLATION)
+}
+
+/// Whether the error is a row taking a key another row has
+pub fn is_unique_violation(e: &sqlx::Error) -> bool {
+    has_code(e, UNIQUE_VIOLATION)
+}
+
+fn has_code(e: &sqlx::Error, code: &str) -> bool {
+    match e {
+        sqlx::Error::Database(e) => e.code().as_deref() == Some(code),
+        _ => false,
+    }
+}
 
 #[derive(Clone)]
 pub struct DbConfig(Pool);
@@ -40,6 +63,10 @@ impl DbConfig {
 
         Self(pool)
     }
+
+    pub fn pool(&self) -> Data<Pool> {
+        Data::new(self.0.clone())
+    }
 }
 
 impl AppConfig for DbConfig {
@@ -65,3 +92,16 @@ mod slice {
         }
     }
 }
+
+mod version {
+    use super::DateTime;
+
+    /// Cheap fingerprint of the rows behind a response, it changes whenever they do
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Version {
+        /// Latest change of the rows, `activity_at` for blogs so comments count
+        pub updated_at: DateTime,
+        /// Amount of rows, so removals change the version too
+        pub rows: i64,
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use actix_web::web::Data;
+use sqlx::postgres::PgListener;
+
+use super::{Executor, Pool};
+
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+/// Reacts to the notifications of a channel
+pub trait Handler: Send + Sync + 'static {
+    fn notified(&self, payload: &str);
+
+    /// The listener was disconnected, so notifications sent meanwhile were lost
+    fn missed(&self);
+}
+
+/// Sends `payload` to every listener of `channel`, inside a transaction it is only sent once
+/// committed
+pub async fn notify(
+    executor: impl Executor<'_>,
+    channel: &str,
+    payload: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT pg_notify($1, $2)", channel, payload)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Listens to `channel` on its own connection for as long as the server lives, reconnecting
+/// whenever the connection is lost
+pub fn spawn(pool: Data<Pool>, channel: &'static str, handler: Arc<impl Handler>) {
+    actix_web::rt::spawn(listen(pool, channel, handler));
+}
+
+async fn listen(pool: Data<Pool>, channel: &'static str, handler: Arc<impl Handler>) {
+    let mut disconnected_at = None;
+    let mut backoff = RECONNECT_MIN;
+
+    loop {
+        let mut listener = match connect(&pool, channel).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Could not listen to {}: {:?}", channel, e);
+                disconnected_at.get_or_insert_with(Instant::now);
+
+                actix_web::rt::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(RECONNECT_MAX);
+                continue;
+            }
+        };
+
+        backoff = RECONNECT_MIN;
+
+        if let Some(since) = disconnected_at.take() {
+            eprintln!(
+                "Listener of {} was disconnected for {:?}, notifications may be lost so caches are flushed",
+                channel,
+                since.elapsed()
+            );
+            handler.missed();
+        }
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => handler.notified(notification.payload()),
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("Listener of {} failed: {:?}", channel, e);
+                    break;
+                }
+            }
+        }
+
+        disconnected_at = Some(Instant::now());
+    }
+}
+
+async fn connect(pool: &Pool, channel: &str) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(channel).await?;
+
+    Ok(listener)
+}
@@ -1,35 +1,4 @@
 pub use filename::{Error as FilenameError, Filename};
-pub use path::ImagePathBuf;
-
-mod path {
-    use std::path::{Path, PathBuf};
-
-    use super::filename::Filename;
-
-    /// A path type guaranteed not to be a directory
-    pub struct ImagePathBuf {
-        path: PathBuf,
-    }
-
-    impl ImagePathBuf {
-        pub fn new(mut parent: PathBuf, filename: &Filename) -> Self {
-            parent.push(filename);
-            Self { path: parent }
-        }
-
-        pub fn create_ancestors(&self) -> std::io::Result<()> {
-            // It's safe to unwrap because we ensure is a valid path on creation
-            let ancestors = self.path.parent().unwrap();
-            std::fs::create_dir_all(ancestors)
-        }
-    }
-
-    impl AsRef<Path> for ImagePathBuf {
-        fn as_ref(&self) -> &Path {
-            &self.path
-        }
-    }
-}
 
 pub mod filename {
     use std::{fmt::Display, path::Path};
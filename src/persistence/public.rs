@@ -61,24 +61,26 @@ mod public_dir {
 mod config {
     use actix_web::web::Data;
 
-    use crate::server::AppConfig;
+    use crate::{persistence::blobs::BlobStore, server::AppConfig};
 
     use super::public_dir::PublicDir;
 
     #[derive(Clone)]
     pub struct Config {
         images_dir: Data<PublicDir>,
+        blobs: Data<dyn BlobStore>,
     }
 
     impl Config {
-        pub fn new(images_dir: Data<PublicDir>) -> Self {
-            Self { images_dir }
+        pub fn new(images_dir: Data<PublicDir>, blobs: Data<dyn BlobStore>) -> Self {
+            Self { images_dir, blobs }
         }
     }
 
     impl AppConfig for Config {
         fn configure(self, config: &mut actix_web::web::ServiceConfig) {
             config.app_data(self.images_dir);
+            config.app_data(self.blobs);
         }
     }
 }
@@ -9,16 +9,24 @@ pub use app_config::{AppConfig, AppConfigurable};
 pub use server::run;
 
 mod server {
+    use std::{sync::Arc, time::Duration};
+
     use actix_cors::Cors;
     use actix_web::{
         middleware::{NormalizePath, TrailingSlash},
+        web::Data,
         App, HttpServer,
     };
 
     use crate::{
-        domain::blog,
-        persistence::db::DbConfig,
-        persistence::public,
+        domain::{
+            blog, event::EventBus, job, read_cache, sitemap, webhook::WebhookSubscriber,
+        },
+        persistence::db::{notify, DbConfig},
+        persistence::{
+            blobs::{BlobStore, FsStore, S3Config, S3Store},
+            public,
+        },
         server::{routes, AppConfigurable},
     };
 
@@ -39,14 +47,149 @@ mod server {
 
         let db_config = DbConfig::new().await;
 
-        let public_config = public::Config::new(static_dir.clone());
-        let blog_config = blog::Config::new(pkg_dir);
+        // Uploads stay in STATIC_DIR unless a bucket is set, which several instances can share
+        let blobs: Arc<dyn BlobStore> = match dotenvy::var("S3_BUCKET") {
+            Ok(bucket) => {
+                let var =
+                    |name| dotenvy::var(name).unwrap_or_else(|_| panic!("could not load {name}"));
+
+                let store = S3Store::new(S3Config {
+                    endpoint: var("S3_ENDPOINT"),
+                    bucket,
+                    region: dotenvy::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_owned()),
+                    access_key: var("S3_ACCESS_KEY"),
+                    secret_key: var("S3_SECRET_KEY"),
+                    public_url: dotenvy::var("S3_PUBLIC_URL").ok(),
+                    presign: dotenvy::var("S3_PRESIGN_SECS")
+                        .ok()
+                        .and_then(|secs| secs.parse().ok())
+                        .map(Duration::from_secs),
+                })
+                .unwrap_or_else(|e| panic!("could not set up the S3 store: {e}"));
+
+                Arc::new(store)
+            }
+            Err(_) => Arc::new(FsStore::new(static_dir.to_string())),
+        };
+        let blobs = Data::from(blobs);
+
+        // Uploads stored by filename were only ever in STATIC_DIR
+        if blobs.local_path("").is_none() {
+            match blog::images::legacy::copy_to_store(static_dir.to_string().into(), blobs.as_ref())
+                .await
+            {
+                Ok(0) => {}
+                Ok(copied) => println!("Copied {copied} uploads of STATIC_DIR to the store"),
+                Err(e) => eprintln!("Could not copy the uploads of STATIC_DIR to the store: {e}"),
+            }
+        }
+
+        let public_config = public::Config::new(static_dir.clone(), blobs.clone());
+        let blog_config = {
+            let embedded_comments = dotenvy::var("EMBEDDED_COMMENTS")
+                .ok()
+                .and_then(|comments| comments.parse().ok())
+                .unwrap_or(20);
+
+            let default_speed = blog::stats::ReadingSpeed::default();
+            let reading_speed = blog::stats::ReadingSpeed {
+                words_per_minute: dotenvy::var("READING_WPM")
+                    .ok()
+                    .and_then(|wpm| wpm.parse().ok())
+                    .unwrap_or(default_speed.words_per_minute),
+                code_words_per_minute: dotenvy::var("READING_CODE_WPM")
+                    .ok()
+                    .and_then(|wpm| wpm.parse().ok())
+                    .unwrap_or(default_speed.code_words_per_minute),
+            };
+
+            let default_limits = blog::features::upload_image::UploadLimits::default();
+            let upload_limits = blog::features::upload_image::UploadLimits {
+                file: dotenvy::var("UPLOAD_MAX_FILE_BYTES")
+                    .ok()
+                    .and_then(|bytes| bytes.parse().ok())
+                    .unwrap_or(default_limits.file),
+                request: dotenvy::var("UPLOAD_MAX_REQUEST_BYTES")
+                    .ok()
+                    .and_then(|bytes| bytes.parse().ok())
+                    .unwrap_or(default_limits.request),
+            };
+
+            let default_limits = blog::attachments::AttachmentLimits::default();
+            let attachment_limits = blog::attachments::AttachmentLimits {
+                file: dotenvy::var("ATTACHMENT_MAX_FILE_BYTES")
+                    .ok()
+                    .and_then(|bytes| bytes.parse().ok())
+                    .unwrap_or(default_limits.file),
+                request: dotenvy::var("ATTACHMENT_MAX_REQUEST_BYTES")
+                    .ok()
+                    .and_then(|bytes| bytes.parse().ok())
+                    .unwrap_or(default_limits.request),
+            };
+
+            blog::Config::new(
+                pkg_dir,
+                embedded_comments,
+                reading_speed,
+                upload_limits,
+                attachment_limits,
+            )
+        };
 
         let server_config = {
             let public_addr = dotenvy::var("PUBLIC_ADDR").expect("could not load PUBLIC_ADDR");
             crate::domain::server::Config::new(&public_addr)
         };
 
+        let sitemap_config = {
+            let robots = dotenvy::var("ROBOTS_TXT")
+                .ok()
+                .map(|path| std::fs::read_to_string(path).expect("could not read ROBOTS_TXT"));
+
+            sitemap::Config::new(sitemap::RobotsTxt::new(
+                robots.as_deref(),
+                &server_config.address(),
+            ))
+        };
+
+        let read_cache_config = {
+            let capacity = dotenvy::var("CACHE_CAPACITY")
+                .ok()
+                .and_then(|capacity| capacity.parse().ok())
+                .unwrap_or(1000);
+
+            let ttl = dotenvy::var("CACHE_TTL_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(300);
+
+            read_cache::Config::new(capacity, Duration::from_secs(ttl))
+        };
+
+        let job_workers = dotenvy::var("JOB_WORKERS")
+            .ok()
+            .and_then(|workers| workers.parse().ok())
+            .unwrap_or(2);
+
+        job::runner::Runner::new(
+            db_config.pool(),
+            server_config.address(),
+            read_cache_config.cache(),
+            blobs.clone(),
+        )
+        .spawn(job_workers);
+
+        notify::spawn(
+            db_config.pool(),
+            read_cache::CHANNEL,
+            read_cache_config.cache().into_inner(),
+        );
+
+        EventBus::default()
+            .subscribe(WebhookSubscriber::new(db_config.pool()))
+            .subscribe(blog::attachments::AttachmentSubscriber::new(blobs.clone()))
+            .spawn(db_config.pool());
+
         println!("Host: {}", &host);
 
         HttpServer::new(move || {
@@ -61,6 +204,8 @@ mod server {
                 .use_config(db_config.clone())
                 .use_config(public_config.clone())
                 .use_config(blog_config.clone())
+                .use_config(sitemap_config.clone())
+                .use_config(read_cache_config.clone())
                 .configure(super::auth::configure)
                 .configure(routes::router)
                 .wrap(NormalizePath::new(TrailingSlash::Always));
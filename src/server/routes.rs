@@ -1,17 +1,31 @@
 mod auth;
 mod blogs;
+mod cache;
 mod categories;
 mod comments;
+mod feeds;
+mod images;
+mod jobs;
+mod posts;
+mod sitemap;
 mod sub_categories;
 mod tags;
+mod webhooks;
 
 use actix_web::web::ServiceConfig;
 
 pub fn router(cfg: &mut ServiceConfig) {
-    cfg.configure(auth::router)
+    cfg.configure(feeds::router)
+        .configure(auth::router)
         .configure(blogs::router)
+        .configure(cache::router)
         .configure(comments::router)
         .configure(categories::router)
         .configure(sub_categories::router)
-        .configure(tags::router);
+        .configure(tags::router)
+        .configure(images::router)
+        .configure(jobs::router)
+        .configure(posts::router)
+        .configure(sitemap::router)
+        .configure(webhooks::router);
 }
@@ -1,9 +1,20 @@
+mod collect_orphaned_images;
+mod create_from_bundle;
 mod create_one;
+mod delete_attachment;
+mod delete_image;
+mod delete_one;
 mod get_all;
+mod get_attachment;
 mod get_image;
+mod get_og_image;
 mod get_one;
 mod get_content;
+mod list_attachments;
+mod list_images;
+mod rename_image;
 mod update_one;
+mod upload_attachments;
 mod upload_images;
 mod recompile_markdowns;
 mod set_content;
@@ -17,13 +28,24 @@ pub fn router(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("/blogs")
             .service(create_one::endpoint)
+            .service(create_from_bundle::endpoint)
             .service(get_all::endpoint)
             .service(get_one::endpoint)
             .service(upload_images::endpoint)
+            .service(list_images::endpoint)
+            .service(rename_image::endpoint)
+            .service(delete_image::endpoint)
             .service(get_image::endpoint)
+            .service(upload_attachments::endpoint)
+            .service(list_attachments::endpoint)
+            .service(get_attachment::endpoint)
+            .service(delete_attachment::endpoint)
+            .service(get_og_image::endpoint)
             .service(get_content::endpoint)
             .service(update_one::endpoint)
+            .service(delete_one::endpoint)
             .service(recompile_markdowns::endpoint)
+            .service(collect_orphaned_images::endpoint)
             .service(set_content::endpoint)
             .configure(comments::router)
             .configure(super::comments::router)
@@ -0,0 +1,58 @@
+use actix_web::{post, web::Query, HttpResponse};
+use serde::Deserialize;
+
+use crate::{
+    domain::{
+        blog::features::collect_orphaned_images::{Payload, DEFAULT_GRACE, MAX_GRACE, MIN_GRACE},
+        job::features::enqueue::EnqueueJob,
+    },
+    persistence::db::entities::IdSelect,
+    server::admin::IsAdminFactory,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    #[serde(default)]
+    pub dry_run: bool,
+    /// How long uploads are left alone, then kept in quarantine before being deleted. At least
+    /// an hour and at most ten years
+    pub grace_hours: Option<u64>,
+}
+
+/// Collection runs as a background job, its report is available at `/jobs/{id}/`
+#[post("/collect-orphaned-images/", wrap = "IsAdminFactory")]
+pub async fn endpoint(enqueue: EnqueueJob, query: Query<Request>) -> HttpResponse {
+    let Request {
+        dry_run,
+        grace_hours,
+    } = query.into_inner();
+
+    let grace_secs = match grace_hours {
+        Some(hours) if hours < MIN_GRACE.as_secs() / (60 * 60) => {
+            return HttpResponse::BadRequest().body(format!(
+                "graceHours can not be less than {}",
+                MIN_GRACE.as_secs() / (60 * 60)
+            ))
+        }
+        Some(hours) if hours > MAX_GRACE.as_secs() / (60 * 60) => {
+            return HttpResponse::BadRequest().body(format!(
+                "graceHours can not be more than {}",
+                MAX_GRACE.as_secs() / (60 * 60)
+            ))
+        }
+        Some(hours) => hours * 60 * 60,
+        None => DEFAULT_GRACE.as_secs(),
+    };
+
+    match enqueue
+        .run(&Payload {
+            dry_run,
+            grace_secs,
+        })
+        .await
+    {
+        Ok(id) => HttpResponse::Accepted().json(IdSelect { id }),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    domain::comment,
+    domain::{
+        comment,
+        read_cache::{Invalidation, ReadCache},
+    },
     persistence::db::Pool,
     server::auth::Claims,
     server::shared::{query::ValidJson, response::insert_response},
@@ -15,16 +18,22 @@ use uuid::Uuid;
 #[post("/")]
 pub async fn endpoint(
     pool: Data<Pool>,
+    cache: Data<ReadCache>,
     blog_id: Path<Uuid>,
     claims: Claims,
     req: ValidJson<comment::models::CreateComment>,
 ) -> impl Responder {
-    let result = comment::create(
-        pool.get_ref(),
-        req.as_ref(),
-        claims.id,
-        blog_id.into_inner(),
-    )
+    let blog_id = blog_id.into_inner();
+    let result = async {
+        let mut tx = pool.begin().await?;
+        let comment = comment::create(&mut tx, req.as_ref(), claims.id, blog_id).await?;
+
+        cache
+            .commit(tx, Invalidation::CommentsChanged { blog_id })
+            .await?;
+
+        Ok::<_, sqlx::Error>(comment)
+    }
     .await;
 
     insert_response(result)
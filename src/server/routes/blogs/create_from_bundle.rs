@@ -0,0 +1,193 @@
+use actix_multipart::Multipart;
+use actix_web::{
+    http::StatusCode,
+    post,
+    web::{self, Data},
+    HttpResponse, Responder, ResponseError,
+};
+use futures_util::StreamExt;
+use markdown_parse::preview::PreviewBuf;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        blog::{
+            bundle,
+            features::{
+                create_from_bundle::{self, CreateFromBundle, Details},
+                upload_image::UploadLimits,
+            },
+            value_objects::sub_categories::SubCategories,
+        },
+        user::admin_id::AdminId,
+    },
+    persistence::db::entities::IdSelect,
+    server::shared::{
+        domain_validation::{self, domain_valid, DomainValid},
+        multipart::{read_field, ReadError},
+    },
+};
+
+domain_valid!(pub struct FrontMatter {
+    preview: Option<PreviewBuf>,
+    category_id: Uuid,
+    tags: Vec<Uuid>,
+    sub_categories: SubCategories,
+}; UncheckedFrontMatter);
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Could not read payload")]
+    Payload,
+    #[error("Missing bundle field")]
+    NoBundle,
+    #[error("{0}")]
+    Bundle(bundle::Error),
+    #[error("Bundle is larger than allowed")]
+    TooLarge,
+    #[error("Invalid meta field: {0}")]
+    Meta(serde_json::Error),
+    #[error("Invalid front matter: {0}")]
+    FrontMatter(String),
+    #[error("{0}")]
+    Validation(domain_validation::Error),
+    #[error(transparent)]
+    Parse(markdown_parse::Error),
+    #[error("Can not infer preview")]
+    NoPreview,
+    #[error("index.md is empty")]
+    EmptyContent,
+    #[error("Images missing from the bundle: {}", .0.join(", "))]
+    MissingImages(Vec<String>),
+    #[error("Invalid image filename: {0}")]
+    InvalidImage(String),
+    #[error("Could not decode image: {0}")]
+    Decode(String),
+    #[error("Conflict creating blog")]
+    Conflict,
+    #[error("Could not store images")]
+    Save,
+    #[error("Database error")]
+    Database,
+}
+
+impl From<ReadError> for Error {
+    fn from(e: ReadError) -> Self {
+        match e {
+            ReadError::Payload => Self::Payload,
+            ReadError::RequestTooLarge => Self::TooLarge,
+        }
+    }
+}
+
+impl From<bundle::Error> for Error {
+    fn from(e: bundle::Error) -> Self {
+        match e {
+            bundle::Error::TooLarge(_) => Self::TooLarge,
+            e => Self::Bundle(e),
+        }
+    }
+}
+
+impl From<create_from_bundle::Error> for Error {
+    fn from(e: create_from_bundle::Error) -> Self {
+        match e {
+            create_from_bundle::Error::Parse(e) => Self::Parse(e),
+            create_from_bundle::Error::NoPreview => Self::NoPreview,
+            create_from_bundle::Error::EmptyContent => Self::EmptyContent,
+            create_from_bundle::Error::MissingImages(images) => Self::MissingImages(images),
+            create_from_bundle::Error::InvalidImage(image) => Self::InvalidImage(image),
+            create_from_bundle::Error::Decode(image) => Self::Decode(image),
+            create_from_bundle::Error::Conflict => Self::Conflict,
+            create_from_bundle::Error::Save => Self::Save,
+            create_from_bundle::Error::Database => Self::Database,
+        }
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::Save | Self::Database => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Creates a blog from a zip or tar.gz, sent as the `bundle` field, holding its `index.md` and
+/// an `images` folder. What is not markdown is told by the YAML front matter of the index, with
+/// the same keys as creating a blog, or by a `meta` JSON field, the front matter taking
+/// precedence. The bundle may take as much as every image uploaded at once
+#[post("/bundle/")]
+pub async fn endpoint(
+    admin_id: AdminId,
+    mut multipart: Multipart,
+    limits: Data<UploadLimits>,
+    create_from_bundle: CreateFromBundle,
+) -> Result<impl Responder, Error> {
+    let mut received = 0;
+    let mut archive = None;
+    let mut meta = Map::new();
+
+    while let Some(field) = multipart.next().await {
+        let mut field = field.map_err(|_| Error::Payload)?;
+        let name = field.name().to_owned();
+
+        let limit = match name.as_str() {
+            "bundle" | "meta" => limits.request,
+            _ => 0,
+        };
+        let content = read_field(&mut field, limit, &mut received, limits.request).await?;
+
+        match (name.as_str(), content) {
+            ("bundle", content) => archive = Some(content.ok_or(Error::TooLarge)?),
+            ("meta", Some(content)) => {
+                meta = serde_json::from_slice(&content).map_err(Error::Meta)?
+            }
+            _ => {}
+        }
+    }
+
+    let archive = archive.ok_or(Error::NoBundle)?;
+    let limits = **limits;
+
+    // Decompressing is slow, so it does not run on the async runtime
+    let bundle = web::block(move || bundle::read(&archive, &limits))
+        .await
+        .map_err(|_| Error::Payload)??;
+
+    let (front_matter, markdown) = bundle::split_front_matter(&bundle.markdown);
+
+    if let Some(front_matter) = front_matter {
+        let front_matter: Map<String, Value> = serde_yaml::from_str::<Option<_>>(front_matter)
+            .map_err(|e| Error::FrontMatter(e.to_string()))?
+            .unwrap_or_default();
+
+        meta.extend(front_matter);
+    }
+
+    let unchecked: UncheckedFrontMatter = serde_json::from_value(Value::Object(meta))
+        .map_err(|e| Error::FrontMatter(e.to_string()))?;
+    let FrontMatter {
+        preview,
+        category_id,
+        tags,
+        sub_categories,
+    } = FrontMatter::from_unchecked(unchecked).map_err(Error::Validation)?;
+
+    let details = Details {
+        preview,
+        category_id,
+        tags,
+        sub_categories,
+    };
+
+    let blog_id = create_from_bundle
+        .run(admin_id, markdown, details, bundle.images)
+        .await?;
+
+    Ok(HttpResponse::Created().json(IdSelect { id: blog_id }))
+}
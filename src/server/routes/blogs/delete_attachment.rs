@@ -0,0 +1,30 @@
+use actix_web::{delete, web::Path, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{
+        attachments::Filename,
+        features::delete_attachment::{self, DeleteAttachment},
+    },
+    server::admin::IsAdminFactory,
+};
+
+#[delete("/{id}/attachments/{filename}/", wrap = "IsAdminFactory")]
+pub async fn endpoint(
+    delete_attachment: DeleteAttachment,
+    path: Path<(Uuid, String)>,
+) -> impl Responder {
+    let (id, filename) = path.into_inner();
+
+    let Ok(filename) = Filename::new(&filename) else {
+        return HttpResponse::BadRequest().body(format!("invalid filename: {}", filename));
+    };
+
+    match delete_attachment.run(id, filename).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(delete_attachment::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(delete_attachment::Error::Storage | delete_attachment::Error::Database) => {
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
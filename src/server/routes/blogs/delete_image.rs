@@ -0,0 +1,27 @@
+use actix_web::{delete, web::Path, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{
+        features::delete_image::{self, DeleteImage},
+        images::Filename,
+    },
+    server::admin::IsAdminFactory,
+};
+
+#[delete("/{id}/images/{filename}/", wrap = "IsAdminFactory")]
+pub async fn endpoint(delete_image: DeleteImage, path: Path<(Uuid, String)>) -> impl Responder {
+    let (id, filename) = path.into_inner();
+
+    let Ok(filename) = Filename::new(&filename) else {
+        return HttpResponse::BadRequest().body(format!("invalid filename: {}", filename));
+    };
+
+    match delete_image.run(id, filename).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(delete_image::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(delete_image::Error::Database | delete_image::Error::Storage) => {
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
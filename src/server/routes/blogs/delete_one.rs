@@ -0,0 +1,16 @@
+use actix_web::{delete, web::Path, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::features::delete_one::{self, DeleteOne},
+    server::admin::IsAdminFactory,
+};
+
+#[delete("/{id}/", wrap = "IsAdminFactory")]
+pub async fn endpoint(delete_one: DeleteOne, id: Path<Uuid>) -> impl Responder {
+    match delete_one.run(id.into_inner()).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(delete_one::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(delete_one::Error::Database) => HttpResponse::InternalServerError().finish(),
+    }
+}
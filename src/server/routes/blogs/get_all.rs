@@ -1,23 +1,73 @@
-use actix_web::{get, web::Query, Responder};
+use actix_web::{get, http::header::ContentType, web::Query, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 
 use crate::{
-    domain::blog::features::get_all::GetAll,
-    server::shared::{query::QuerySlice, response::select_response},
+    domain::blog::{
+        features::get_all::{BlogPreview, GetAll},
+        include::Include,
+    },
+    server::shared::{
+        query::{Fields, QuerySlice},
+        response::Validators,
+    },
 };
 
 #[derive(Debug, Deserialize)]
 pub struct Request {
     pub search: Option<String>,
+    /// Defaults to tags and sub categories, or to those of them among the fields
+    pub include: Option<String>,
+    pub fields: Option<Fields>,
     #[serde(flatten)]
     pub slice: QuerySlice,
 }
 
 #[get("/")]
-pub async fn endpoint(get_all: GetAll, query: Query<Request>) -> impl Responder {
-    let Request { search, slice } = query.into_inner();
+pub async fn endpoint(req: HttpRequest, get_all: GetAll, query: Query<Request>) -> impl Responder {
+    let Request {
+        search,
+        include,
+        fields,
+        slice,
+    } = query.into_inner();
 
-    let blogs = get_all.run(slice, search.as_deref().unwrap_or("")).await;
+    let include = match include {
+        Some(include) => match Include::parse(&include, Include::LISTING) {
+            Ok(include) => include,
+            Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+        },
+        None => Include::LISTING,
+    };
 
-    select_response(blogs)
+    // What is left out of the response is not queried
+    let include = match &fields {
+        Some(fields) => match fields.validate(BlogPreview::FIELDS) {
+            Ok(()) => include.retain(|name| fields.contains(name)),
+            Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+        },
+        None => include,
+    };
+
+    match get_all
+        .run(slice, search.as_deref().unwrap_or(""), include)
+        .await
+    {
+        // Removed blogs leave the latest change unchanged, so only the etag of the body is sent
+        Ok(blogs) => {
+            let body = match &fields {
+                Some(fields) => fields
+                    .select(&*blogs)
+                    .and_then(|blogs| serde_json::to_vec(&blogs)),
+                None => serde_json::to_vec(&*blogs),
+            };
+            let Ok(body) = body else {
+                return HttpResponse::InternalServerError().finish();
+            };
+
+            Validators::of_body(&body).respond(&req, |response| {
+                response.content_type(ContentType::json()).body(body)
+            })
+        }
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
 }
@@ -0,0 +1,36 @@
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    get,
+    web::{Data, Path},
+    HttpRequest, HttpResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{attachments::Filename, features::get_attachment::GetAttachment},
+    persistence::blobs::BlobStore,
+    server::shared::response::download_response,
+};
+
+/// Download of the file the blog attaches by that name
+#[get("/{id}/attachments/{filename}/")]
+pub async fn endpoint(
+    req: HttpRequest,
+    path: Path<(Uuid, String)>,
+    get_attachment: GetAttachment,
+    blobs: Data<dyn BlobStore>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (id, filename) = path.into_inner();
+
+    let Ok(filename) = Filename::new(&filename) else {
+        return Err(ErrorBadRequest(""));
+    };
+
+    let key = get_attachment
+        .run(id, filename)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or_else(|| ErrorNotFound(""))?;
+
+    download_response(&req, blobs.as_ref(), &key, filename.as_ref()).await
+}
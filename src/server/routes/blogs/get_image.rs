@@ -1,21 +1,45 @@
-use actix_files::NamedFile;
-use actix_web::{error::ErrorBadRequest, get, web::Path, Responder};
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    get,
+    http::header::{HeaderValue, VARY},
+    web::{Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
 use uuid::Uuid;
 
-use crate::domain::blog::{features::get_image::GetImage, images::Filename};
+use crate::{
+    domain::blog::{features::get_image::GetImage, images::Filename},
+    persistence::blobs::BlobStore,
+    server::shared::{query::ImageVariant, response::blob_response},
+};
 
+/// Image by the name the blog gives it, which may point to other content once uploaded again
 #[get("/{id}/public/{filename}/")]
 pub async fn endpoint(
+    req: HttpRequest,
     path: Path<(Uuid, String)>,
+    query: Query<ImageVariant>,
     get_image: GetImage,
-) -> Result<impl Responder, actix_web::Error> {
+    blobs: Data<dyn BlobStore>,
+) -> Result<HttpResponse, actix_web::Error> {
     let (id, filename) = path.into_inner();
 
     let Ok(filename) = Filename::new(&filename) else {
         return Err(ErrorBadRequest(""));
     };
 
-    let image_path = get_image.run(id, filename);
+    let accepted = query.formats(&req)?;
 
-    Ok(NamedFile::open(image_path)?)
+    let key = get_image
+        .run(id, filename, query.w, &accepted)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or_else(|| ErrorNotFound(""))?;
+
+    let mut response = blob_response(&req, blobs.as_ref(), &key, None).await?;
+    response
+        .headers_mut()
+        .insert(VARY, HeaderValue::from_static("accept"));
+
+    Ok(response)
 }
@@ -0,0 +1,17 @@
+use actix_files::NamedFile;
+use actix_web::{error::ErrorInternalServerError, error::ErrorNotFound, get, web::Path, Responder};
+use uuid::Uuid;
+
+use crate::domain::blog::features::get_og_image::GetOgImage;
+
+#[get("/{id}/og.png/")]
+pub async fn endpoint(
+    id: Path<Uuid>,
+    get_og_image: GetOgImage,
+) -> Result<impl Responder, actix_web::Error> {
+    match get_og_image.run(id.into_inner()).await {
+        Ok(Some(path)) => Ok(NamedFile::open(path)?),
+        Ok(None) => Err(ErrorNotFound("")),
+        Err(e) => Err(ErrorInternalServerError(e)),
+    }
+}
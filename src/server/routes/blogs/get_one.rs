@@ -1,12 +1,38 @@
-use actix_web::{get, web::Path, HttpResponse, Responder, ResponseError};
+use actix_web::{
+    get,
+    http::header::ContentType,
+    web::{Path, Query},
+    HttpRequest, HttpResponse, ResponseError,
+};
+use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::domain::blog::features::get_by_id::GetById;
+use crate::{
+    domain::blog::{
+        features::get_by_id::{BlogById, GetById},
+        include::{Include, UnknownInclude},
+    },
+    server::shared::{
+        query::{Fields, UnknownField},
+        response::{modified_at, Validators},
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    /// Defaults to comments, tags and sub categories, or to those of them among the fields
+    pub include: Option<String>,
+    pub fields: Option<Fields>,
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Blog not found")]
     NotFound,
+    #[error("{0}")]
+    Include(#[from] UnknownInclude),
+    #[error("{0}")]
+    Field(#[from] UnknownField),
     #[error("")]
     Database,
 }
@@ -15,15 +41,54 @@ impl ResponseError for Error {
     fn status_code(&self) -> actix_web::http::StatusCode {
         match self {
             Error::NotFound => actix_web::http::StatusCode::NOT_FOUND,
+            Error::Include(_) | Error::Field(_) => actix_web::http::StatusCode::BAD_REQUEST,
             Error::Database => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
 
 #[get("/{blog_id}/")]
-pub async fn endpoint(get_by_id: GetById, id: Path<Uuid>) -> Result<impl Responder, Error> {
-    match get_by_id.run(id.into_inner()).await {
-        Ok(Some(blog)) => Ok(HttpResponse::Ok().json(blog)),
+pub async fn endpoint(
+    req: HttpRequest,
+    get_by_id: GetById,
+    id: Path<Uuid>,
+    query: Query<Request>,
+) -> Result<HttpResponse, Error> {
+    let Request { include, fields } = query.into_inner();
+
+    let include = match (include, &fields) {
+        (Some(include), _) => Include::parse(&include, Include::ALL)?,
+        (None, Some(_)) => Include::ALL,
+        (None, None) => Include::BLOG,
+    };
+
+    // What is left out of the response is not queried
+    let include = match &fields {
+        Some(fields) => {
+            fields.validate(BlogById::FIELDS)?;
+            include.retain(|name| fields.contains(name))
+        }
+        None => include,
+    };
+
+    match get_by_id.run(id.into_inner(), include).await {
+        Ok(Some(blog)) => {
+            let body = match &fields {
+                Some(fields) => fields
+                    .select(&blog.value)
+                    .and_then(|blog| serde_json::to_vec(&blog)),
+                None => serde_json::to_vec(&blog.value),
+            };
+            let Ok(body) = body else {
+                return Ok(HttpResponse::InternalServerError().finish());
+            };
+
+            let validators = Validators::of_body(&body).last_modified(modified_at(&blog.version));
+
+            Ok(validators.respond(&req, |response| {
+                response.content_type(ContentType::json()).body(body)
+            }))
+        }
         Ok(None) => Err(Error::NotFound),
         Err(_) => Err(Error::Database),
     }
@@ -0,0 +1,16 @@
+use actix_web::{get, web::Path, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::features::list_attachments::{self, ListAttachments},
+    server::admin::IsAdminFactory,
+};
+
+#[get("/{id}/attachments/", wrap = "IsAdminFactory")]
+pub async fn endpoint(list_attachments: ListAttachments, id: Path<Uuid>) -> impl Responder {
+    match list_attachments.run(id.into_inner()).await {
+        Ok(attachments) => HttpResponse::Ok().json(attachments),
+        Err(list_attachments::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(list_attachments::Error::Database) => HttpResponse::InternalServerError().finish(),
+    }
+}
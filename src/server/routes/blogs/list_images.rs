@@ -0,0 +1,18 @@
+use actix_web::{get, web::Path, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::features::list_images::{self, ListImages},
+    server::admin::IsAdminFactory,
+};
+
+#[get("/{id}/images/", wrap = "IsAdminFactory")]
+pub async fn endpoint(list_images: ListImages, id: Path<Uuid>) -> impl Responder {
+    match list_images.run(id.into_inner()).await {
+        Ok(images) => HttpResponse::Ok().json(images),
+        Err(list_images::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(list_images::Error::Database | list_images::Error::Storage) => {
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -1,13 +1,26 @@
-use actix_web::{post, HttpResponse};
+use actix_web::{post, web::Query, HttpResponse};
+use serde::Deserialize;
 
 use crate::{
-    domain::blog::features::recompile_markdowns::RecompileMarkdowns, server::admin::IsAdminFactory,
+    domain::{blog::features::recompile_markdowns::Payload, job::features::enqueue::EnqueueJob},
+    persistence::db::entities::IdSelect,
+    server::admin::IsAdminFactory,
 };
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Recompilation runs as a background job, its status is available at `/jobs/{id}/`
 #[post("/recompile-markdowns/", wrap = "IsAdminFactory")]
-pub async fn endpoint(recompile: RecompileMarkdowns) -> HttpResponse {
-    match recompile.run().await {
-        Ok(_) => HttpResponse::Ok().finish(),
+pub async fn endpoint(enqueue: EnqueueJob, query: Query<Request>) -> HttpResponse {
+    let Request { dry_run } = query.into_inner();
+
+    match enqueue.run(&Payload { dry_run }).await {
+        Ok(id) => HttpResponse::Accepted().json(IdSelect { id }),
         Err(_) => HttpResponse::InternalServerError().finish(),
     }
 }
@@ -0,0 +1,54 @@
+use actix_web::{
+    patch,
+    web::{Json, Path},
+    HttpResponse, Responder,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{
+        features::rename_image::{self, RenameImage},
+        images::Filename,
+    },
+    server::admin::IsAdminFactory,
+};
+
+#[derive(Deserialize)]
+pub struct Request {
+    filename: String,
+}
+
+#[patch("/{id}/images/{filename}/", wrap = "IsAdminFactory")]
+pub async fn endpoint(
+    rename_image: RenameImage,
+    path: Path<(Uuid, String)>,
+    request: Json<Request>,
+) -> impl Responder {
+    let (id, from) = path.into_inner();
+    let Request { filename: to } = request.into_inner();
+
+    let Ok(from) = Filename::new(&from) else {
+        return HttpResponse::BadRequest().body(format!("invalid filename: {}", from));
+    };
+    let Ok(to) = Filename::new(&to) else {
+        return HttpResponse::BadRequest().body(format!("invalid filename: {}", to));
+    };
+
+    match rename_image.run(id, from, to).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(rename_image::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(rename_image::Error::Conflict) => {
+            HttpResponse::Conflict().body("another image has the name")
+        }
+        Err(rename_image::Error::ExtensionChanged) => {
+            HttpResponse::BadRequest().body("the extension can not change")
+        }
+        Err(rename_image::Error::Parse(_)) => {
+            HttpResponse::BadRequest().body("Can not parse content")
+        }
+        Err(rename_image::Error::Database | rename_image::Error::Storage) => {
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::cell::Cell;
+
+use actix_multipart::Multipart;
+use actix_web::{
+    post,
+    web::{Data, Path},
+    HttpResponse,
+};
+use futures_util::StreamExt;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{
+        attachments::{AttachmentLimits, Filename},
+        features::upload_attachment::{Error, UploadAttachment},
+    },
+    server::{
+        admin::IsAdminFactory,
+        shared::multipart::{read_field, stream_field, ReadError, StreamStop},
+    },
+};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum Status {
+    Saved,
+    InvalidFilename,
+    TooLarge,
+}
+
+#[derive(Serialize, Debug)]
+struct FileResult {
+    filename: String,
+    status: Status,
+}
+
+/// Saves every file field as it is received, answering how it went for each as uploading
+/// images does. Files are told apart by the extension of their filename, browsers disagree on
+/// the content type of most
+#[post("/{id}/attachments/", wrap = "IsAdminFactory")]
+pub async fn endpoint(
+    path: Path<Uuid>,
+    mut multipart: Multipart,
+    upload_attachment: UploadAttachment,
+    limits: Data<AttachmentLimits>,
+) -> HttpResponse {
+    let id = path.into_inner();
+
+    let mut received = 0;
+    let mut results = vec![];
+
+    while let Some(result) = multipart.next().await {
+        let Ok(mut field) = result else {
+            return HttpResponse::BadRequest().body("Could not read payload");
+        };
+
+        let Some(filename) = field
+            .content_disposition()
+            .get_filename()
+            .map(str::to_owned)
+        else {
+            if let Err(e) = read_field(&mut field, 0, &mut received, limits.request).await {
+                return read_error_response(e, results);
+            }
+            continue;
+        };
+
+        // Rejected files are still read to reach the next field
+        let read = match Filename::new(&filename) {
+            Err(_) => read_field(&mut field, 0, &mut received, limits.request)
+                .await
+                .map(|_| Status::InvalidFilename),
+            Ok(valid) => {
+                let stop = Cell::new(None);
+                let content = stream_field(
+                    &mut field,
+                    limits.file,
+                    &mut received,
+                    limits.request,
+                    &stop,
+                );
+
+                match upload_attachment.run(id, valid, content).await {
+                    Ok(()) => Ok(Status::Saved),
+                    Err(Error::Interrupted) => match stop.get() {
+                        Some(StreamStop::FieldTooLarge) => {
+                            read_field(&mut field, 0, &mut received, limits.request)
+                                .await
+                                .map(|_| Status::TooLarge)
+                        }
+                        Some(StreamStop::Read(e)) => Err(e),
+                        None => return HttpResponse::InternalServerError().finish(),
+                    },
+                    Err(Error::NotFound) => return HttpResponse::NotFound().finish(),
+                    Err(Error::Save | Error::Database) => {
+                        return HttpResponse::InternalServerError().finish()
+                    }
+                }
+            }
+        };
+
+        let status = match read {
+            Ok(status) => status,
+            Err(ReadError::RequestTooLarge) => {
+                results.push(FileResult {
+                    filename,
+                    status: Status::TooLarge,
+                });
+                return read_error_response(ReadError::RequestTooLarge, results);
+            }
+            Err(e) => return read_error_response(e, results),
+        };
+
+        results.push(FileResult { filename, status });
+    }
+
+    HttpResponse::Ok().json(results)
+}
+
+fn read_error_response(e: ReadError, results: Vec<FileResult>) -> HttpResponse {
+    match e {
+        ReadError::Payload => HttpResponse::BadRequest().body("Could not read field"),
+        ReadError::RequestTooLarge => HttpResponse::PayloadTooLarge().json(results),
+    }
+}
@@ -1,62 +1,118 @@
 use actix_multipart::Multipart;
-use actix_web::{post, web::Path, HttpResponse};
+use actix_web::{
+    post,
+    web::{Data, Path},
+    HttpResponse,
+};
 use futures_util::StreamExt;
-use image::EncodableLayout;
+use serde::Serialize;
 use uuid::Uuid;
 
-use crate::domain::blog::{
-    features::upload_image::{Error, UploadImage},
-    images::{Filename, ImagePathFactory, ALLOWED_FILETYPES},
+use crate::{
+    domain::blog::{
+        features::upload_image::{Error, UploadImage, UploadLimits},
+        images::{Filename, ALLOWED_FILETYPES},
+    },
+    server::{
+        admin::IsAdminFactory,
+        shared::multipart::{read_field, ReadError},
+    },
 };
 
-#[post("/{id}/public/")]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum Status {
+    Saved,
+    RejectedType,
+    InvalidFilename,
+    TooLarge,
+    DecodeFailed,
+}
+
+#[derive(Serialize, Debug)]
+struct FileResult {
+    filename: String,
+    status: Status,
+}
+
+/// Saves every image field, answering how it went for each. Fields without a filename are not
+/// files and are skipped. Once the request grows past its limit nothing else is read, it is
+/// answered with what was saved until then
+#[post("/{id}/public/", wrap = "IsAdminFactory")]
 pub async fn endpoint(
     path: Path<Uuid>,
     mut multipart: Multipart,
-    image_manager: ImagePathFactory,
     upload_image: UploadImage,
+    limits: Data<UploadLimits>,
 ) -> HttpResponse {
     let id = path.into_inner();
 
-    let mut buffer = vec![];
+    let mut received = 0;
+    let mut results = vec![];
+
     while let Some(result) = multipart.next().await {
         let Ok(mut field) = result else {
             return HttpResponse::BadRequest().body("Could not read payload");
         };
 
-        match field.content_type() {
-            Some(filetype) => {
-                if !ALLOWED_FILETYPES.contains(filetype) {
-                    continue;
-                }
+        let Some(filename) = field
+            .content_disposition()
+            .get_filename()
+            .map(str::to_owned)
+        else {
+            if let Err(e) = read_field(&mut field, 0, &mut received, limits.request).await {
+                return read_error_response(e, results);
             }
-            None => continue,
+            continue;
         };
 
-        let filename = field.content_disposition().get_filename().unwrap_or("");
-        let Ok(filename) = Filename::new(filename) else {
-            return HttpResponse::BadRequest().body(format!("invalid filename: {}", filename));
+        let allowed = field
+            .content_type()
+            .is_some_and(|filetype| ALLOWED_FILETYPES.contains(&filetype.essence_str()));
+        let valid = Filename::new(&filename).is_ok();
+
+        // Rejected files are still read to reach the next field
+        let limit = if allowed && valid { limits.file } else { 0 };
+        let content = match read_field(&mut field, limit, &mut received, limits.request).await {
+            Ok(content) => content,
+            Err(ReadError::RequestTooLarge) => {
+                results.push(FileResult {
+                    filename,
+                    status: Status::TooLarge,
+                });
+                return read_error_response(ReadError::RequestTooLarge, results);
+            }
+            Err(e) => return read_error_response(e, results),
         };
 
-        let image_path = image_manager.create_path(id, filename);
+        let status = match content {
+            _ if !allowed => Status::RejectedType,
+            _ if !valid => Status::InvalidFilename,
+            None => Status::TooLarge,
+            Some(content) => {
+                // Validated above
+                let filename = Filename::new(&filename).unwrap();
 
-        while let Some(result) = field.next().await {
-            let Ok(bytes) = result else {
-                return HttpResponse::BadRequest().body("Could not read field");
-            };
+                match upload_image.run(id, filename, content).await {
+                    Ok(()) => Status::Saved,
+                    Err(Error::Decode) => Status::DecodeFailed,
+                    Err(Error::NotFound) => return HttpResponse::NotFound().finish(),
+                    Err(Error::Save | Error::Database) => {
+                        return HttpResponse::InternalServerError().finish()
+                    }
+                }
+            }
+        };
 
-            buffer.extend_from_slice(bytes.as_bytes());
-        }
+        results.push(FileResult { filename, status });
+    }
 
-        if let Err(e) = upload_image.run(&image_path, buffer.as_ref()) {
-            return match e {
-                Error::Save => HttpResponse::InternalServerError().finish(),
-                Error::Decode => HttpResponse::BadRequest().body("invalid image"),
-            };
-        }
+    HttpResponse::Ok().json(results)
+}
 
-        buffer.clear();
+fn read_error_response(e: ReadError, results: Vec<FileResult>) -> HttpResponse {
+    match e {
+        ReadError::Payload => HttpResponse::BadRequest().body("Could not read field"),
+        ReadError::RequestTooLarge => HttpResponse::PayloadTooLarge().json(results),
     }
-
-    HttpResponse::Ok().finish()
 }
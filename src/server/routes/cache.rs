@@ -0,0 +1,16 @@
+use actix_web::web::{scope, ServiceConfig};
+
+mod get_stats {
+    use actix_web::{get, web::Data, HttpResponse};
+
+    use crate::{domain::read_cache::ReadCache, server::admin::IsAdminFactory};
+
+    #[get("/stats/", wrap = "IsAdminFactory")]
+    pub async fn endpoint(cache: Data<ReadCache>) -> HttpResponse {
+        HttpResponse::Ok().json(cache.stats())
+    }
+}
+
+pub fn router(cfg: &mut ServiceConfig) {
+    cfg.service(scope("/cache").service(get_stats::endpoint));
+}
@@ -5,29 +5,49 @@ mod create_one;
 
 use actix_web::{
     delete, get,
+    http::header::ContentType,
     web::{scope, Data, Path, ServiceConfig},
-    Responder,
+    HttpRequest, HttpResponse, Responder,
 };
 use uuid::Uuid;
 
 use crate::{
-    domain::blog_grouping,
+    domain::{
+        blog_grouping::{self, get_categories::GetCategories},
+        read_cache::{Invalidation, ReadCache},
+    },
     persistence::db::Pool,
     server::{
         admin::IsAdminFactory,
-        shared::response::{deleted_response, select_response},
+        shared::response::{deleted_response, Validators},
     },
 };
 
 #[get("/")]
-async fn get_all(pool: Data<Pool>) -> impl Responder {
-    let result = blog_grouping::get_all_categories(pool.as_ref()).await;
-    select_response(result)
+async fn get_all(req: HttpRequest, get_categories: GetCategories) -> impl Responder {
+    match get_categories.run().await {
+        Ok(categories) => match serde_json::to_vec(&*categories) {
+            Ok(body) => Validators::of_body(&body).respond(&req, |response| {
+                response.content_type(ContentType::json()).body(body)
+            }),
+            Err(_) => HttpResponse::InternalServerError().finish(),
+        },
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
 }
 
 #[delete("/{id}/", wrap = "IsAdminFactory")]
-async fn delete_one(id: Path<Uuid>, pool: Data<Pool>) -> impl Responder {
-    let result = blog_grouping::delete_category(pool.as_ref(), id.into_inner()).await;
+async fn delete_one(id: Path<Uuid>, pool: Data<Pool>, cache: Data<ReadCache>) -> impl Responder {
+    let result = async {
+        let mut tx = pool.begin().await?;
+        let deleted = blog_grouping::delete_category(&mut tx, id.into_inner()).await?;
+
+        cache.commit(tx, Invalidation::GroupingRemoved).await?;
+
+        Ok::<_, sqlx::Error>(deleted)
+    }
+    .await;
+
     deleted_response(result)
 }
 
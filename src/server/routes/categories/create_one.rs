@@ -1,7 +1,10 @@
 use actix_web::{post, web::Data, Responder};
 
 use crate::{
-    domain::blog_grouping,
+    domain::{
+        blog_grouping,
+        read_cache::{Invalidation, ReadCache},
+    },
     persistence::db::Pool,
     server::{
         admin::IsAdminFactory,
@@ -16,8 +19,21 @@ pub struct Request {
 }
 
 #[post("/", wrap = "IsAdminFactory")]
-pub async fn endpoint(pool: Data<Pool>, request: ValidJson<Request>) -> impl Responder {
+pub async fn endpoint(
+    pool: Data<Pool>,
+    cache: Data<ReadCache>,
+    request: ValidJson<Request>,
+) -> impl Responder {
     let Request { name } = request.into_inner();
-    let response = blog_grouping::create_category(pool.as_ref(), &name).await;
+    let response = async {
+        let mut tx = pool.begin().await?;
+        let category = blog_grouping::create_category(&mut tx, &name).await?;
+
+        cache.commit(tx, Invalidation::CategoriesChanged).await?;
+
+        Ok::<_, sqlx::Error>(category)
+    }
+    .await;
+
     insert_response(response)
 }
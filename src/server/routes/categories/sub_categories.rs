@@ -1,20 +1,20 @@
 use actix_web::web::{scope, ServiceConfig};
 
 mod get_all {
-    use actix_web::{
-        get,
-        web::{Data, Path},
-        Responder,
-    };
+    use actix_web::{get, web::Path, Responder};
 
     use crate::{
-        domain::blog_grouping, persistence::db::Pool, server::shared::response::select_response,
+        domain::blog_grouping::get_sub_categories_by_category::GetSubCategoriesByCategory,
+        server::shared::response::select_response,
     };
 
     #[get("/")]
-    pub async fn endpoint(pool: Data<Pool>, path: Path<uuid::Uuid>) -> impl Responder {
+    pub async fn endpoint(
+        service: GetSubCategoriesByCategory,
+        path: Path<uuid::Uuid>,
+    ) -> impl Responder {
         let id = path.into_inner();
-        let result = blog_grouping::get_sub_categories_by_category(pool.get_ref(), id).await;
+        let result = service.run(id).await;
         select_response(result)
     }
 }
@@ -27,7 +27,10 @@ mod create_one {
     };
 
     use crate::{
-        domain::blog_grouping,
+        domain::{
+            blog_grouping,
+            read_cache::{Invalidation, ReadCache},
+        },
         persistence::db::Pool,
         server::{
             admin::IsAdminFactory,
@@ -45,13 +48,24 @@ mod create_one {
     #[post("/", wrap = "IsAdminFactory")]
     pub async fn endpoint(
         pool: Data<Pool>,
+        cache: Data<ReadCache>,
         req: ValidJson<Request>,
         path: Path<uuid::Uuid>,
     ) -> impl Responder {
         let id = path.into_inner();
         let Request { name } = req.into_inner();
 
-        let result = blog_grouping::create_subcategory(pool.get_ref(), &name, id).await;
+        let result = async {
+            let mut tx = pool.begin().await?;
+            let sub_category = blog_grouping::create_subcategory(&mut tx, &name, id).await?;
+
+            cache
+                .commit(tx, Invalidation::SubCategoriesChanged { category_id: id })
+                .await?;
+
+            Ok::<_, sqlx::Error>(sub_category)
+        }
+        .await;
 
         insert_response(result)
     }
@@ -3,20 +3,17 @@ use actix_web::web::{self, scope, ServiceConfig};
 use crate::server::admin::IsAdminFactory;
 
 mod get_all {
-    use actix_web::{
-        get,
-        web::{Data, Path},
-        Responder,
-    };
+    use actix_web::{get, web::Path, Responder};
 
     use crate::{
-        domain::blog_grouping, persistence::db::Pool, server::shared::response::select_response,
+        domain::blog_grouping::get_tags_by_category::GetTagsByCategory,
+        server::shared::response::select_response,
     };
 
     #[get("/")]
-    pub async fn endpoint(pool: Data<Pool>, path: Path<uuid::Uuid>) -> impl Responder {
+    pub async fn endpoint(service: GetTagsByCategory, path: Path<uuid::Uuid>) -> impl Responder {
         let id = path.into_inner();
-        let result = blog_grouping::get_tags_by_category(pool.get_ref(), id).await;
+        let result = service.run(id).await;
         select_response(result)
     }
 }
@@ -28,7 +25,10 @@ mod create_one {
     };
 
     use crate::{
-        domain::blog_grouping,
+        domain::{
+            blog_grouping,
+            read_cache::{Invalidation, ReadCache},
+        },
         persistence::db::Pool,
         server::shared::{query::ValidJson, response::insert_response},
     };
@@ -43,13 +43,24 @@ mod create_one {
 
     pub async fn endpoint(
         pool: Data<Pool>,
+        cache: Data<ReadCache>,
         req: ValidJson<Request>,
         path: Path<uuid::Uuid>,
     ) -> impl Responder {
         let id = path.into_inner();
         let Request { name, color } = req.into_inner();
 
-        let result = blog_grouping::create_tag(pool.get_ref(), id, &name, &color).await;
+        let result = async {
+            let mut tx = pool.begin().await?;
+            let tag = blog_grouping::create_tag(&mut tx, id, &name, &color).await?;
+
+            cache
+                .commit(tx, Invalidation::TagsChanged { category_id: id })
+                .await?;
+
+            Ok::<_, sqlx::Error>(tag)
+        }
+        .await;
 
         insert_response(result)
     }
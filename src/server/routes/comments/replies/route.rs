@@ -7,7 +7,11 @@ use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
-    domain::{comment::models::CreateComment, reply},
+    domain::{
+        comment::models::CreateComment,
+        read_cache::{Invalidation, ReadCache},
+        reply,
+    },
     persistence::db::Pool,
     server::shared::query::QuerySlice,
     server::shared::{
@@ -61,6 +65,7 @@ pub async fn get_all(
 #[post("/")]
 pub async fn create(
     pool: Data<Pool>,
+    cache: Data<ReadCache>,
     path: Path<Uuid>,
     Claims { id, .. }: Claims,
     req: ValidJson<CreateComment>,
@@ -68,13 +73,23 @@ pub async fn create(
 ) -> impl Responder {
     let comment_id = path.into_inner();
 
-    let result = reply::create(
-        pool.get_ref(),
-        &req.into_inner().content,
-        id,
-        comment_id,
-        parent_id.parent_id,
-    )
+    let result = async {
+        let mut tx = pool.begin().await?;
+        let reply = reply::create(
+            &mut tx,
+            &req.into_inner().content,
+            id,
+            comment_id,
+            parent_id.parent_id,
+        )
+        .await?;
+
+        cache
+            .commit(tx, Invalidation::RepliesChanged { comment_id })
+            .await?;
+
+        Ok::<_, sqlx::Error>(reply)
+    }
     .await;
 
     insert_response(result)
@@ -0,0 +1,140 @@
+use actix_web::{
+    get,
+    web::{Path, Query, ServiceConfig},
+    HttpRequest, HttpResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::feed::{
+        atom,
+        features::get_feed::{GetFeed, FEED_SIZE, MAX_PAGE_SIZE},
+        json,
+        models::{Feed, FeedScope},
+        rss,
+    },
+    persistence::db::Slice,
+    server::shared::{query::QuerySlice, response::Validators},
+};
+
+enum Format {
+    Rss,
+    Atom,
+    /// Paginated by the request
+    Json(QuerySlice),
+}
+
+async fn respond(
+    req: HttpRequest,
+    get_feed: GetFeed,
+    scope: FeedScope,
+    format: Format,
+) -> HttpResponse {
+    let slice = match format {
+        // An empty page would link to itself as the next one
+        Format::Json(ref slice) => Slice {
+            limit: (slice.limit as i64).clamp(1, MAX_PAGE_SIZE),
+            offset: slice.offset as i64,
+        },
+        _ => Slice {
+            limit: FEED_SIZE,
+            offset: 0,
+        },
+    };
+
+    let feed = match get_feed.run(scope, slice).await {
+        Ok(Some(feed)) => feed,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(_) => return HttpResponse::InternalServerError().finish(),
+    };
+
+    let (content_type, body) = render(&feed, format);
+
+    // No Last-Modified, the newest item tells nothing of items deleted or moved out of the
+    // scope, nor of renamed categories, which the body reflects
+    Validators::of_body(body.as_bytes()).respond(&req, |response| {
+        response.content_type(content_type).body(body)
+    })
+}
+
+fn render(feed: &Feed, format: Format) -> (&'static str, String) {
+    match format {
+        Format::Rss => (rss::CONTENT_TYPE, rss::render(feed)),
+        Format::Atom => (atom::CONTENT_TYPE, atom::render(feed)),
+        Format::Json(_) => (json::CONTENT_TYPE, json::render(feed)),
+    }
+}
+
+#[get("/feed.xml/")]
+async fn rss_all(req: HttpRequest, get_feed: GetFeed) -> HttpResponse {
+    respond(req, get_feed, FeedScope::All, Format::Rss).await
+}
+
+#[get("/atom.xml/")]
+async fn atom_all(req: HttpRequest, get_feed: GetFeed) -> HttpResponse {
+    respond(req, get_feed, FeedScope::All, Format::Atom).await
+}
+
+#[get("/feed.json/")]
+async fn json_all(req: HttpRequest, get_feed: GetFeed, slice: Query<QuerySlice>) -> HttpResponse {
+    let format = Format::Json(slice.into_inner());
+    respond(req, get_feed, FeedScope::All, format).await
+}
+
+#[get("/categories/{id}/feed.xml/")]
+async fn rss_category(req: HttpRequest, get_feed: GetFeed, id: Path<Uuid>) -> HttpResponse {
+    let scope = FeedScope::Category(id.into_inner());
+    respond(req, get_feed, scope, Format::Rss).await
+}
+
+#[get("/categories/{id}/atom.xml/")]
+async fn atom_category(req: HttpRequest, get_feed: GetFeed, id: Path<Uuid>) -> HttpResponse {
+    let scope = FeedScope::Category(id.into_inner());
+    respond(req, get_feed, scope, Format::Atom).await
+}
+
+#[get("/categories/{id}/feed.json/")]
+async fn json_category(
+    req: HttpRequest,
+    get_feed: GetFeed,
+    id: Path<Uuid>,
+    slice: Query<QuerySlice>,
+) -> HttpResponse {
+    let scope = FeedScope::Category(id.into_inner());
+    respond(req, get_feed, scope, Format::Json(slice.into_inner())).await
+}
+
+#[get("/tags/{id}/feed.xml/")]
+async fn rss_tag(req: HttpRequest, get_feed: GetFeed, id: Path<Uuid>) -> HttpResponse {
+    respond(req, get_feed, FeedScope::Tag(id.into_inner()), Format::Rss).await
+}
+
+#[get("/tags/{id}/atom.xml/")]
+async fn atom_tag(req: HttpRequest, get_feed: GetFeed, id: Path<Uuid>) -> HttpResponse {
+    respond(req, get_feed, FeedScope::Tag(id.into_inner()), Format::Atom).await
+}
+
+#[get("/tags/{id}/feed.json/")]
+async fn json_tag(
+    req: HttpRequest,
+    get_feed: GetFeed,
+    id: Path<Uuid>,
+    slice: Query<QuerySlice>,
+) -> HttpResponse {
+    let scope = FeedScope::Tag(id.into_inner());
+    respond(req, get_feed, scope, Format::Json(slice.into_inner())).await
+}
+
+/// Must be registered before the `/categories` and `/tags` scopes, they would match the scoped
+/// feeds otherwise
+pub fn router(cfg: &mut ServiceConfig) {
+    cfg.service(rss_all)
+        .service(atom_all)
+        .service(json_all)
+        .service(rss_category)
+        .service(atom_category)
+        .service(json_category)
+        .service(rss_tag)
+        .service(atom_tag)
+        .service(json_tag);
+}
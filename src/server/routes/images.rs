@@ -0,0 +1,58 @@
+use actix_web::web::{scope, ServiceConfig};
+
+mod get_one {
+    use actix_web::{
+        error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+        get,
+        http::header::{CacheControl, CacheDirective, HeaderValue, VARY},
+        web::{Data, Path, Query},
+        HttpRequest, HttpResponse,
+    };
+
+    use crate::{
+        domain::blog::{features::get_image::GetImage, images::Filename},
+        persistence::blobs::BlobStore,
+        server::shared::{query::ImageVariant, response::blob_response},
+    };
+
+    /// A year, the most browsers honor
+    const MAX_AGE: u32 = 365 * 24 * 60 * 60;
+
+    /// Upload by the hash of its content, which never changes what its url shows
+    #[get("/{object}/")]
+    pub async fn endpoint(
+        req: HttpRequest,
+        object: Path<String>,
+        query: Query<ImageVariant>,
+        get_image: GetImage,
+        blobs: Data<dyn BlobStore>,
+    ) -> Result<HttpResponse, actix_web::Error> {
+        let Ok(object) = Filename::new(&object) else {
+            return Err(ErrorBadRequest(""));
+        };
+
+        let accepted = query.formats(&req)?;
+        let key = get_image
+            .object(object, query.w, &accepted)
+            .await
+            .map_err(ErrorInternalServerError)?
+            .ok_or_else(|| ErrorNotFound(""))?;
+
+        let cache = CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(MAX_AGE),
+            CacheDirective::Extension("immutable".to_owned(), None),
+        ]);
+
+        let mut response = blob_response(&req, blobs.as_ref(), &key, Some(cache)).await?;
+        response
+            .headers_mut()
+            .insert(VARY, HeaderValue::from_static("accept"));
+
+        Ok(response)
+    }
+}
+
+pub fn router(cfg: &mut ServiceConfig) {
+    cfg.service(scope("/images").service(get_one::endpoint));
+}
@@ -0,0 +1,21 @@
+use actix_web::web::{scope, ServiceConfig};
+
+mod get_one {
+    use actix_web::{get, web::Path, HttpResponse};
+    use uuid::Uuid;
+
+    use crate::{domain::job::features::get_by_id::GetJobById, server::admin::IsAdminFactory};
+
+    #[get("/{id}/", wrap = "IsAdminFactory")]
+    pub async fn endpoint(get_job: GetJobById, id: Path<Uuid>) -> HttpResponse {
+        match get_job.run(id.into_inner()).await {
+            Ok(Some(job)) => HttpResponse::Ok().json(job),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(_) => HttpResponse::InternalServerError().finish(),
+        }
+    }
+}
+
+pub fn router(cfg: &mut ServiceConfig) {
+    cfg.service(scope("/jobs").service(get_one::endpoint));
+}
@@ -0,0 +1,29 @@
+use actix_web::{
+    get,
+    web::{Data, Path, ServiceConfig},
+    HttpResponse,
+};
+
+use crate::domain::{
+    blog::{features::get_post::GetPost, page},
+    server::ServerAddress,
+};
+
+#[get("/posts/{key}/")]
+async fn get_one(
+    get_post: GetPost,
+    server_address: Data<ServerAddress>,
+    key: Path<String>,
+) -> HttpResponse {
+    match get_post.run(&key).await {
+        Ok(Some(post)) => HttpResponse::Ok()
+            .content_type(mime::TEXT_HTML_UTF_8)
+            .body(page::render(&post, &server_address.to_string())),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+pub fn router(cfg: &mut ServiceConfig) {
+    cfg.service(get_one);
+}
@@ -0,0 +1,39 @@
+use actix_web::{
+    get,
+    web::{Data, Path, ServiceConfig},
+    HttpResponse,
+};
+
+use crate::domain::sitemap::{features::get_sitemap::GetSitemap, render, RobotsTxt};
+
+#[get("/sitemap.xml/")]
+async fn root(get_sitemap: GetSitemap) -> HttpResponse {
+    match get_sitemap.root().await {
+        Ok(xml) => HttpResponse::Ok()
+            .content_type(render::CONTENT_TYPE)
+            .body(xml),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/sitemaps/{page}.xml/")]
+async fn page(get_sitemap: GetSitemap, page: Path<i64>) -> HttpResponse {
+    match get_sitemap.page(page.into_inner()).await {
+        Ok(Some(xml)) => HttpResponse::Ok()
+            .content_type(render::CONTENT_TYPE)
+            .body(xml),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/robots.txt/")]
+async fn robots(robots: Data<RobotsTxt>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(mime::TEXT_PLAIN_UTF_8)
+        .body(robots.as_ref().as_ref().to_owned())
+}
+
+pub fn router(cfg: &mut ServiceConfig) {
+    cfg.service(root).service(page).service(robots);
+}
@@ -9,14 +9,30 @@ mod delete {
     use uuid::Uuid;
 
     use crate::{
-        domain::blog_grouping,
+        domain::{
+            blog_grouping,
+            read_cache::{Invalidation, ReadCache},
+        },
         persistence::db::Pool,
         server::{admin::IsAdminFactory, shared::response::deleted_response},
     };
 
     #[delete("/{id}/", wrap = "IsAdminFactory")]
-    pub async fn endpoint(pool: Data<Pool>, id: Path<Uuid>) -> impl Responder {
-        let result = blog_grouping::delete_tag(pool.get_ref(), id.into_inner()).await;
+    pub async fn endpoint(
+        pool: Data<Pool>,
+        cache: Data<ReadCache>,
+        id: Path<Uuid>,
+    ) -> impl Responder {
+        let result = async {
+            let mut tx = pool.begin().await?;
+            let deleted = blog_grouping::delete_tag(&mut tx, id.into_inner()).await?;
+
+            cache.commit(tx, Invalidation::GroupingRemoved).await?;
+
+            Ok::<_, sqlx::Error>(deleted)
+        }
+        .await;
+
         deleted_response(result)
     }
 }
@@ -0,0 +1,130 @@
+use actix_web::web::{scope, ServiceConfig};
+
+mod create_one {
+    use actix_web::{post, Responder};
+    use serde::Deserialize;
+    use validator::{Validate, ValidationError};
+
+    use crate::{
+        domain::{
+            event::EventKind,
+            webhook::{self, features::create_one::CreateWebhook},
+        },
+        server::{
+            admin::IsAdminFactory,
+            shared::{query::ValidJson, response::insert_response},
+        },
+    };
+
+    #[derive(Deserialize, Validate)]
+    pub struct Request {
+        #[validate(url, custom = "is_http")]
+        url: String,
+        #[validate(length(min = 16))]
+        secret: String,
+        #[validate(length(min = 1), custom = "are_subscribable")]
+        events: Vec<EventKind>,
+    }
+
+    fn is_http(url: &str) -> Result<(), ValidationError> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Ok(());
+        }
+
+        Err(ValidationError::new("http"))
+    }
+
+    fn are_subscribable(events: &[EventKind]) -> Result<(), ValidationError> {
+        if events
+            .iter()
+            .all(|event| webhook::SUBSCRIBABLE_EVENTS.contains(event))
+        {
+            return Ok(());
+        }
+
+        Err(ValidationError::new("subscribable"))
+    }
+
+    #[post("/", wrap = "IsAdminFactory")]
+    pub async fn endpoint(create: CreateWebhook, request: ValidJson<Request>) -> impl Responder {
+        let Request {
+            url,
+            secret,
+            events,
+        } = request.into_inner();
+
+        insert_response(create.run(&url, &secret, &events).await)
+    }
+}
+
+mod get_all {
+    use actix_web::{get, Responder};
+
+    use crate::{
+        domain::webhook::features::get_all::GetWebhooks,
+        server::{admin::IsAdminFactory, shared::response::select_response},
+    };
+
+    #[get("/", wrap = "IsAdminFactory")]
+    pub async fn endpoint(get_all: GetWebhooks) -> impl Responder {
+        select_response(get_all.run().await)
+    }
+}
+
+mod delete_one {
+    use actix_web::{delete, web::Path, HttpResponse};
+    use uuid::Uuid;
+
+    use crate::{
+        domain::webhook::features::delete_one::DeleteWebhook, server::admin::IsAdminFactory,
+    };
+
+    #[delete("/{id}/", wrap = "IsAdminFactory")]
+    pub async fn endpoint(delete: DeleteWebhook, id: Path<Uuid>) -> HttpResponse {
+        match delete.run(id.into_inner()).await {
+            Ok(result) if result.rows_affected() == 0 => HttpResponse::NotFound().finish(),
+            Ok(_) => HttpResponse::NoContent().finish(),
+            Err(_) => HttpResponse::InternalServerError().finish(),
+        }
+    }
+}
+
+mod get_deliveries {
+    use actix_web::{
+        get,
+        web::{Path, Query},
+        Responder,
+    };
+    use uuid::Uuid;
+
+    use crate::{
+        domain::webhook::features::get_deliveries::GetDeliveries,
+        server::{
+            admin::IsAdminFactory,
+            shared::{query::QuerySlice, response::select_response},
+        },
+    };
+
+    #[get("/{id}/deliveries/", wrap = "IsAdminFactory")]
+    pub async fn endpoint(
+        get_deliveries: GetDeliveries,
+        id: Path<Uuid>,
+        slice: Query<QuerySlice>,
+    ) -> impl Responder {
+        select_response(
+            get_deliveries
+                .run(id.into_inner(), slice.into_inner().into())
+                .await,
+        )
+    }
+}
+
+pub fn router(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/webhooks")
+            .service(create_one::endpoint)
+            .service(get_all::endpoint)
+            .service(delete_one::endpoint)
+            .service(get_deliveries::endpoint),
+    );
+}
@@ -1,4 +1,5 @@
 pub mod domain_validation;
+pub mod multipart;
 pub mod query;
 pub mod response;
 pub mod traits;
@@ -0,0 +1,86 @@
+use std::cell::Cell;
+
+use actix_multipart::Field;
+use futures_util::StreamExt;
+
+use crate::persistence::blobs::{self, LocalBlobStream};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError {
+    Payload,
+    /// The fields read so far went past the limit of the request
+    RequestTooLarge,
+}
+
+/// Content of the field while it fits in `limit`, `None` past it. The rest of the field is
+/// drained either way, every byte adding to `received`, which may not go past `request_limit`
+pub async fn read_field(
+    field: &mut Field,
+    limit: usize,
+    received: &mut usize,
+    request_limit: usize,
+) -> Result<Option<Vec<u8>>, ReadError> {
+    let mut content = Some(vec![]);
+
+    while let Some(chunk) = field.next().await {
+        let chunk = chunk.map_err(|_| ReadError::Payload)?;
+
+        *received += chunk.len();
+        if *received > request_limit {
+            return Err(ReadError::RequestTooLarge);
+        }
+
+        content = content
+            .filter(|content| content.len() + chunk.len() <= limit)
+            .map(|mut content| {
+                content.extend_from_slice(&chunk);
+                content
+            });
+    }
+
+    Ok(content)
+}
+
+/// What ended a field streamed by [`stream_field`] early
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamStop {
+    /// The field went past its own limit, the rest of it is left to drain
+    FieldTooLarge,
+    Read(ReadError),
+}
+
+/// Content of the field as it is received, ending in an error once it goes past `limit` or
+/// fails to be read. Every byte adds to `received`, which may not go past `request_limit`.
+/// Why it ended early is left in `stop`
+pub fn stream_field<'a>(
+    field: &'a mut Field,
+    limit: usize,
+    received: &'a mut usize,
+    request_limit: usize,
+    stop: &'a Cell<Option<StreamStop>>,
+) -> LocalBlobStream<'a> {
+    let mut size = 0;
+
+    field
+        .map(move |chunk| {
+            let stopped = |reason| {
+                stop.set(Some(reason));
+                blobs::Error::Interrupted
+            };
+
+            let chunk = chunk.map_err(|_| stopped(StreamStop::Read(ReadError::Payload)))?;
+
+            *received += chunk.len();
+            if *received > request_limit {
+                return Err(stopped(StreamStop::Read(ReadError::RequestTooLarge)));
+            }
+
+            size += chunk.len();
+            if size > limit {
+                return Err(stopped(StreamStop::FieldTooLarge));
+            }
+
+            Ok(chunk)
+        })
+        .boxed_local()
+}
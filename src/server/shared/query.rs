@@ -1,7 +1,11 @@
 mod domain_json;
+mod fields;
+mod image_variant;
 mod slice;
 mod valid_json;
 
 pub use domain_json::DomainJson;
+pub use fields::{Fields, UnknownField};
+pub use image_variant::ImageVariant;
 pub use slice::QuerySlice;
 pub use valid_json::ValidJson;
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Top level keys requested by `fields=id,title`, the others are left out of the response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fields(Vec<String>);
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown field {0}")]
+pub struct UnknownField(pub String);
+
+impl Fields {
+    /// Fails with the first requested key the response can not have
+    pub fn validate(&self, known: &[&str]) -> Result<(), UnknownField> {
+        match self.0.iter().find(|field| !known.contains(&field.as_str())) {
+            Some(field) => Err(UnknownField(field.clone())),
+            None => Ok(()),
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.0.iter().any(|field| field == key)
+    }
+
+    /// Keeps only the requested keys of an object, or of every object of an array
+    pub fn select(&self, value: &impl Serialize) -> Result<Value, serde_json::Error> {
+        let mut value = serde_json::to_value(value)?;
+
+        match &mut value {
+            Value::Array(items) => items.iter_mut().for_each(|item| self.retain(item)),
+            item => self.retain(item),
+        }
+
+        Ok(value)
+    }
+
+    fn retain(&self, item: &mut Value) {
+        if let Value::Object(object) = item {
+            object.retain(|key, _| self.contains(key));
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let fields = String::deserialize(deserializer)?;
+
+        Ok(Self(
+            fields
+                .split(',')
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::web::Query;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Request {
+        fields: Option<Fields>,
+    }
+
+    #[test]
+    fn selects_keys_of_objects() {
+        let Query(Request { fields }) = Query::from_query("fields=id,title").unwrap();
+
+        let selected = fields
+            .unwrap()
+            .select(&json!([{ "id": 1, "title": "a", "content": "b" }]))
+            .unwrap();
+
+        assert_eq!(selected, json!([{ "id": 1, "title": "a" }]));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let Query(Request { fields }) = Query::from_query("fields=id,author").unwrap();
+
+        assert_eq!(
+            fields.unwrap().validate(&["id", "title"]),
+            Err(UnknownField("author".to_owned()))
+        );
+    }
+
+    #[test]
+    fn missing_fields_select_nothing() {
+        let Query(Request { fields }) = Query::from_query("").unwrap();
+
+        assert_eq!(fields, None);
+    }
+}
@@ -0,0 +1,46 @@
+use actix_web::{
+    error::ErrorBadRequest,
+    http::header::{self, Header},
+    HttpRequest,
+};
+use serde::Deserialize;
+
+use crate::domain::blog::images::variants::Format;
+
+/// Variant of an image requested by the `srcset` of its `<picture>`
+#[derive(Debug, Deserialize)]
+pub struct ImageVariant {
+    /// Width the image is shown at
+    pub w: Option<u32>,
+    /// Extension of the preferred format, overriding the `Accept` header
+    pub format: Option<String>,
+}
+
+impl ImageVariant {
+    /// Formats preferred over the one of the image
+    pub fn formats(&self, req: &HttpRequest) -> Result<Vec<Format>, actix_web::Error> {
+        match &self.format {
+            Some(format) => match Format::from_extension(format) {
+                Some(format) => Ok(vec![format]),
+                None => Err(ErrorBadRequest("unknown format")),
+            },
+            None => Ok(accepted_formats(req)),
+        }
+    }
+}
+
+/// Formats explicitly listed in `Accept`, wildcards are sent by browsers unable to show them too
+fn accepted_formats(req: &HttpRequest) -> Vec<Format> {
+    let Ok(header::Accept(items)) = header::Accept::parse(req) else {
+        return vec![];
+    };
+
+    Format::ALL
+        .into_iter()
+        .filter(|format| {
+            items
+                .iter()
+                .any(|item| item.item == format.mime() && item.quality > header::Quality::ZERO)
+        })
+        .collect()
+}
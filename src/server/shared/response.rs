@@ -1,4 +1,6 @@
+mod blob;
 mod code;
+mod conditional;
 mod json;
 
 use actix_web::HttpResponse;
@@ -6,7 +8,9 @@ use serde::Serialize;
 
 use crate::persistence::db::QueryResult;
 
+pub use blob::{blob_response, download_response};
 pub use code::HttpCode;
+pub use conditional::{modified_at, Validators};
 pub use json::JsonResponse;
 
 pub fn select_response<T: Serialize>(result: Result<T, sqlx::Error>) -> HttpResponse {
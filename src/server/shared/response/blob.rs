@@ -0,0 +1,115 @@
+use actix_files::NamedFile;
+use actix_web::{
+    error::{ErrorInternalServerError, ErrorNotFound},
+    http::header::{
+        CacheControl, CacheDirective, Charset, ContentDisposition, DispositionParam,
+        DispositionType, ExtendedValue, HeaderValue, TryIntoHeaderPair, CONTENT_SECURITY_POLICY,
+        LOCATION,
+    },
+    HttpRequest, HttpResponse,
+};
+
+use crate::persistence::blobs::BlobStore;
+
+/// Svgs are sanitized when uploaded, this keeps any script they might still hold from running
+/// when opened on their own
+const SVG_POLICY: &str = "default-src 'none'; style-src 'unsafe-inline'; sandbox";
+
+/// Sends the blob the way the store serves it best: from its file when stored locally, by
+/// redirecting to a presigned url when the store offers them, or else streamed through the server.
+/// `cache` only goes along the blob itself, redirects are cached for half their validity
+pub async fn blob_response(
+    req: &HttpRequest,
+    blobs: &dyn BlobStore,
+    key: &str,
+    cache: Option<CacheControl>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let presigned = blobs
+        .presigned_url(key)
+        .await
+        .map_err(ErrorInternalServerError)?;
+
+    if let Some(presigned) = presigned {
+        let max_age = (presigned.expires_in.as_secs() / 2) as u32;
+
+        return Ok(HttpResponse::TemporaryRedirect()
+            .insert_header((LOCATION, presigned.url))
+            .insert_header(CacheControl(vec![
+                CacheDirective::Private,
+                CacheDirective::MaxAge(max_age),
+            ]))
+            .finish());
+    }
+
+    let mut response = match blobs.local_path(key) {
+        Some(path) => NamedFile::open(path)?.into_response(req),
+        None => {
+            let content = blobs
+                .get_stream(key)
+                .await
+                .map_err(ErrorInternalServerError)?
+                .ok_or_else(|| ErrorNotFound(""))?;
+
+            let extension = key.rsplit_once('.').map_or("", |(_, extension)| extension);
+
+            HttpResponse::Ok()
+                .content_type(actix_files::file_extension_to_mime(extension))
+                .streaming(content)
+        }
+    };
+
+    if key.ends_with(".svg") {
+        response.headers_mut().insert(
+            CONTENT_SECURITY_POLICY,
+            HeaderValue::from_static(SVG_POLICY),
+        );
+    }
+
+    if let Some(cache) = cache {
+        let (name, value) = cache.try_into_pair()?;
+        response.headers_mut().insert(name, value);
+    }
+
+    Ok(response)
+}
+
+/// Sends the blob to be saved as `filename` instead of shown, as it is read from the store so
+/// large files are never held whole. It is never redirected to a presigned url, which would
+/// not name it
+pub async fn download_response(
+    req: &HttpRequest,
+    blobs: &dyn BlobStore,
+    key: &str,
+    filename: &str,
+) -> Result<HttpResponse, actix_web::Error> {
+    let disposition = ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![match filename.is_ascii() {
+            true => DispositionParam::Filename(filename.to_owned()),
+            false => DispositionParam::FilenameExt(ExtendedValue {
+                charset: Charset::Ext("UTF-8".to_owned()),
+                language_tag: None,
+                value: filename.as_bytes().to_vec(),
+            }),
+        }],
+    };
+
+    if let Some(path) = blobs.local_path(key) {
+        return Ok(NamedFile::open(path)?
+            .set_content_disposition(disposition)
+            .into_response(req));
+    }
+
+    let content = blobs
+        .get_stream(key)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or_else(|| ErrorNotFound(""))?;
+
+    let extension = key.rsplit_once('.').map_or("", |(_, extension)| extension);
+
+    Ok(HttpResponse::Ok()
+        .content_type(actix_files::file_extension_to_mime(extension))
+        .insert_header(disposition)
+        .streaming(content))
+}
@@ -0,0 +1,224 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use actix_web::{
+    http::header::{
+        CacheControl, CacheDirective, ETag, EntityTag, Header, HttpDate, IfModifiedSince,
+        IfNoneMatch, LastModified, AUTHORIZATION, IF_NONE_MATCH, VARY,
+    },
+    HttpRequest, HttpResponse, HttpResponseBuilder,
+};
+use chrono::{TimeZone, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::{persistence::db::Version, server::admin::uncheked_admin_id};
+
+/// Seconds public responses may be reused before revalidating them
+const PUBLIC_MAX_AGE: u32 = 60;
+
+/// Validators of a response, compared against the conditional headers of requests
+pub struct Validators {
+    etag: EntityTag,
+    last_modified: Option<SystemTime>,
+}
+
+impl Validators {
+    /// Validators of an already built body. Its tag is strong, which tells bodies apart byte
+    /// for byte, so it changes with whatever the body was built from: the rows, the query and
+    /// whether the requester is an admin
+    pub fn of_body(body: &[u8]) -> Self {
+        Self {
+            etag: EntityTag::new_strong(hex::encode(&Sha256::digest(body)[..16])),
+            last_modified: None,
+        }
+    }
+
+    /// Also sends `Last-Modified`, only right when removed rows can not leave it unchanged
+    pub fn last_modified(mut self, last_modified: SystemTime) -> Self {
+        self.last_modified = Some(last_modified);
+        self
+    }
+
+    /// Whether the client copy is still fresh, `If-None-Match` takes precedence over
+    /// `If-Modified-Since`
+    pub fn is_fresh(&self, req: &HttpRequest) -> bool {
+        if req.headers().contains_key(IF_NONE_MATCH) {
+            return match IfNoneMatch::parse(req) {
+                Ok(IfNoneMatch::Any) => true,
+                Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&self.etag)),
+                Err(_) => false,
+            };
+        }
+
+        self.last_modified
+            .map(|last_modified| is_fresh(req, last_modified))
+            .unwrap_or(false)
+    }
+
+    /// Adds the validators along with the cache policy of the requester
+    pub fn headers(&self, req: &HttpRequest, response: &mut HttpResponseBuilder) {
+        response
+            .insert_header(ETag(self.etag.clone()))
+            .insert_header(cache_control(req))
+            .insert_header((VARY, AUTHORIZATION.as_str()));
+
+        if let Some(last_modified) = self.last_modified {
+            response.insert_header(LastModified(HttpDate::from(last_modified)));
+        }
+    }
+
+    pub fn not_modified(&self, req: &HttpRequest) -> HttpResponse {
+        let mut response = HttpResponse::NotModified();
+        self.headers(req, &mut response);
+        response.finish()
+    }
+
+    /// `304 Not Modified` when the client copy is fresh, the built response otherwise
+    pub fn respond(
+        &self,
+        req: &HttpRequest,
+        build: impl FnOnce(&mut HttpResponseBuilder) -> HttpResponse,
+    ) -> HttpResponse {
+        if self.is_fresh(req) {
+            return self.not_modified(req);
+        }
+
+        let mut response = HttpResponse::Ok();
+        self.headers(req, &mut response);
+        build(&mut response)
+    }
+}
+
+/// Admins may see drafts of changes, so their responses are never stored by shared caches nor
+/// reused without revalidating them
+fn cache_control(req: &HttpRequest) -> CacheControl {
+    if uncheked_admin_id::get_unchecked_admin_id(req).is_ok() {
+        CacheControl(vec![CacheDirective::Private, CacheDirective::NoCache])
+    } else {
+        CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(PUBLIC_MAX_AGE),
+        ])
+    }
+}
+
+/// Time of a version as sent in `Last-Modified`
+pub fn modified_at(version: &Version) -> SystemTime {
+    Utc.from_utc_datetime(&version.updated_at).into()
+}
+
+/// Whether the client copy, dated by `If-Modified-Since`, is still fresh
+pub fn is_fresh(req: &HttpRequest, last_modified: SystemTime) -> bool {
+    let Ok(IfModifiedSince(since)) = IfModifiedSince::parse(req) else {
+        return false;
+    };
+
+    // Http dates have no subseconds
+    truncate_secs(last_modified) <= SystemTime::from(since)
+}
+
+fn truncate_secs(time: SystemTime) -> SystemTime {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => UNIX_EPOCH + Duration::from_secs(duration.as_secs()),
+        Err(_) => time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test::TestRequest};
+
+    use super::*;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn etag_of(response: &HttpResponse) -> String {
+        response
+            .headers()
+            .get("ETag")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn fresh_when_not_modified_after_header() {
+        let req = TestRequest::default()
+            .insert_header(("If-Modified-Since", "Thu, 01 Jan 1970 00:01:40 GMT"))
+            .to_http_request();
+
+        assert!(is_fresh(&req, at(100) + Duration::from_millis(500)));
+        assert!(is_fresh(&req, at(99)));
+        assert!(!is_fresh(&req, at(101)));
+    }
+
+    #[test]
+    fn stale_without_header() {
+        let req = TestRequest::default().to_http_request();
+
+        assert!(!is_fresh(&req, at(0)));
+    }
+
+    #[test]
+    fn etag_tells_bodies_apart() {
+        let etag = |body: &[u8]| Validators::of_body(body).etag;
+
+        assert_eq!(etag(b"body"), etag(b"body"));
+        assert_ne!(etag(b"body"), etag(b"other"));
+        assert!(!etag(b"body").weak);
+    }
+
+    #[test]
+    fn not_modified_when_etag_matches() {
+        let req = TestRequest::default().to_http_request();
+        let validators = Validators::of_body(b"body");
+
+        let response = validators.respond(&req, |response| response.body("body"));
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let req = TestRequest::default()
+            .insert_header(("If-None-Match", etag_of(&response)))
+            .to_http_request();
+
+        let response = validators.respond(&req, |response| response.body("body"));
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(response.headers().contains_key("ETag"));
+    }
+
+    #[test]
+    fn if_none_match_takes_precedence() {
+        let validators = Validators::of_body(b"body").last_modified(at(100));
+
+        let req = TestRequest::default()
+            .insert_header(("If-None-Match", "\"other\""))
+            .insert_header(("If-Modified-Since", "Thu, 01 Jan 1970 00:01:40 GMT"))
+            .to_http_request();
+
+        assert!(!validators.is_fresh(&req));
+
+        let req = TestRequest::default()
+            .insert_header(("If-Modified-Since", "Thu, 01 Jan 1970 00:01:40 GMT"))
+            .to_http_request();
+
+        assert!(validators.is_fresh(&req));
+    }
+
+    #[test]
+    fn public_requests_are_cacheable() {
+        let req = TestRequest::default().to_http_request();
+
+        let response = Validators::of_body(b"").respond(&req, |response| response.finish());
+
+        assert_eq!(
+            response
+                .headers()
+                .get("Cache-Control")
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "public, max-age=60"
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod future;
 pub mod str_wrapper;
+pub mod xml;